@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rustvalidity::prelude::*;
+use rustvalidity::rules::advanced::{Domain, SemVer, IP};
+use rustvalidity::rules::common::{Email, Length, Phone, Required, UrlRule, UuidRule};
+
+/// Benchmark a string rule against a valid and an invalid input
+fn bench_str_rule<R: Rule<str>>(c: &mut Criterion, name: &str, rule: R, valid: &str, invalid: &str) {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Elements(1));
+    for (label, input) in [("valid", valid), ("invalid", invalid)] {
+        group.bench_with_input(BenchmarkId::from_parameter(label), input, |b, input| {
+            b.iter(|| rule.validate(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn string_rules(c: &mut Criterion) {
+    bench_str_rule(c, "email", Email { check_dns: false }, "jane.doe@example.com", "jane.doe@example");
+    bench_str_rule(c, "phone", Phone { allow_empty: false }, "+14155552671", "555-2671");
+    bench_str_rule(c, "semver", SemVer, "1.42.0-beta.3+build.17", "1.42");
+    bench_str_rule(c, "domain", Domain, "api.eu-west.example.com", "-example.com");
+    bench_str_rule(c, "url", UrlRule { allowed_schemes: None }, "https://example.com/a?b=c", "example com");
+    bench_str_rule(c, "uuid", UuidRule, "67e55044-10b1-426f-9247-bb680e5fe0c8", "67e55044-10b1");
+    bench_str_rule(c, "length", Length { min: 3, max: Some(64) }, "rustvalidity", "ab");
+    bench_str_rule(c, "required", Required, "value", "");
+}
+
+fn ip_rule(c: &mut Criterion) {
+    let rule = IP { allow_v4: true, allow_v6: true };
+    let mut group = c.benchmark_group("ip");
+    group.throughput(Throughput::Elements(1));
+    for (label, input) in [
+        ("v4", "192.168.10.254"),
+        ("v6", "2001:db8:85a3::8a2e:370:7334"),
+        ("v6_zone", "fe80::1%eth0"),
+        ("invalid", "256.1.1.1"),
+    ] {
+        group.bench_with_input(BenchmarkId::from_parameter(label), input, |b, input| {
+            b.iter(|| Rule::<str>::validate(&rule, black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+struct Row {
+    name: String,
+    email: String,
+    phone: String,
+    website: String,
+}
+
+/// A bulk import: every row checked by a schema, as when loading a CSV file
+fn import(c: &mut Criterion) {
+    let schema = Validator::for_type::<Row>()
+        .with_mode(ValidationMode::CollectAll)
+        .field("name", |r: &Row| &r.name, vec![Box::new(Required), Box::new(Length { min: 2, max: Some(50) })])
+        .field("email", |r: &Row| &r.email, vec![Box::new(Required), Box::new(Email { check_dns: false })])
+        .field("phone", |r: &Row| &r.phone, vec![Box::new(Phone { allow_empty: true })])
+        .field("website", |r: &Row| &r.website, vec![Box::new(UrlRule { allowed_schemes: None })]);
+
+    let rows: Vec<Row> = (0..10_000)
+        .map(|i| Row {
+            name: format!("Customer {}", i),
+            email: if i % 10 == 0 { format!("customer{}", i) } else { format!("customer{}@example.com", i) },
+            phone: format!("+1415555{:04}", i),
+            website: format!("https://example.com/customers/{}", i),
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("import");
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    group.sample_size(20);
+    group.bench_function("schema", |b| {
+        b.iter(|| rows.iter().filter(|row| schema.validate(black_box(row)).is_err()).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, string_rules, ip_rule, import);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use futures::executor::block_on;
+use futures::future::BoxFuture;
+use rustvalidity::asynchronous::{AsyncRule, AsyncSchema, AsyncValidate, Available, Exists, InMemoryLookup, Timeout};
+use rustvalidity::error::ValidationError;
+use rustvalidity::rules::common;
+use rustvalidity::validator::ValidationMode;
+
+struct Signup {
+    username: String,
+    email: String,
+    coupon: String,
+}
+
+// Stand-ins for the tables a real application would query
+static USERNAMES: LazyLock<Arc<InMemoryLookup>> = LazyLock::new(|| {
+    let lookup: InMemoryLookup = ["admin", "johndoe"].into_iter().collect();
+    Arc::new(lookup.with_latency(Duration::from_millis(20)))
+});
+
+static COUPONS: LazyLock<Arc<InMemoryLookup>> = LazyLock::new(|| {
+    let lookup: InMemoryLookup = ["WELCOME10"].into_iter().collect();
+    Arc::new(lookup.with_latency(Duration::from_millis(50)))
+});
+
+static SIGNUP_SCHEMA: LazyLock<AsyncSchema<Signup>> = LazyLock::new(|| {
+    AsyncSchema::new()
+        .with_mode(ValidationMode::CollectAll)
+        .bail(true)
+        .field_async("username", |s: &Signup| &s.username, vec![
+            Box::new(common::Required),
+            Box::new(common::Length { min: 3, max: Some(20) }),
+        ], vec![
+            Box::new(Available { lookup: USERNAMES.clone() }),
+        ])
+        .field("email", |s: &Signup| &s.email, vec![
+            Box::new(common::Required),
+            Box::new(common::Email { check_dns: false }),
+        ])
+        .field_async("coupon", |s: &Signup| &s.coupon, vec![], vec![
+            Box::new(Timeout::new(Exists { lookup: COUPONS.clone() }, Duration::from_millis(200))),
+        ])
+});
+
+impl AsyncValidate for Signup {
+    fn validate_async(&self) -> BoxFuture<'_, Result<(), ValidationError>> {
+        Box::pin(SIGNUP_SCHEMA.validate(self))
+    }
+}
+
+fn main() {
+    let valid_signup = Signup {
+        username: "janedoe".to_string(),
+        email: "jane@example.com".to_string(),
+        coupon: "WELCOME10".to_string(),
+    };
+
+    match block_on(valid_signup.validate_async()) {
+        Ok(_) => println!("Valid signup: {}", valid_signup.username),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    let invalid_signup = Signup {
+        username: "johndoe".to_string(),
+        email: "not-an-email".to_string(),
+        coupon: "EXPIRED".to_string(),
+    };
+
+    match block_on(invalid_signup.validate_async()) {
+        Ok(_) => println!("Valid signup: {}", invalid_signup.username),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    // A store slower than the timeout is reported instead of blocking the request
+    let slow_coupons = InMemoryLookup::new().with_latency(Duration::from_millis(500));
+    let rule = Timeout::new(Exists { lookup: slow_coupons }, Duration::from_millis(100));
+    match block_on(rule.validate_async("WELCOME10")) {
+        Ok(_) => println!("Coupon accepted"),
+        Err(err) => println!("Coupon check failed: {}", err),
+    }
+}
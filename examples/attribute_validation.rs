@@ -1,10 +1,7 @@
-use std::any::Any;
-use std::collections::HashMap;
-
-use rustvalidity::prelude::*;
-use rustvalidity::error::ValidationError;
+use rustvalidity::error::{ErrorTree, ValidationError};
+use rustvalidity::path::FieldPath;
 use rustvalidity::validator::{Validator, Validate};
-use rustvalidity::rules::{Rule, common, numeric, collection, advanced};
+use rustvalidity::rules::{common, numeric, collection};
 
 #[derive(Debug)]
 struct Product {
@@ -34,46 +31,46 @@ impl Validate for Product {
         validator.add_rule("min_price", numeric::Min { value: 0.0 });
         validator.add_rule("email", common::Email { check_dns: false });
         validator.add_rule("categories_required", collection::MinSize { min: 1 });
-        validator.add_rule("url", common::Url { allowed_schemes: Some(vec!["http".to_string(), "https".to_string()]) });
+        validator.add_rule("url", common::UrlRule { allowed_schemes: Some(vec!["http".to_string(), "https".to_string()]) });
         
         // Validate fields
-        let mut errors = HashMap::new();
+        let mut errors = ErrorTree::new();
         
         // Validate name (required, length between 3 and 50)
-        if let Err(err) = validator.get_rule("required").unwrap().validate(&self.name as &dyn Any) {
-            errors.entry("name".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        } else if let Err(err) = validator.get_rule("name_length").unwrap().validate(&self.name as &dyn Any) {
-            errors.entry("name".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
+        if let Err(err) = validator.get_rule("required").unwrap().validate(&self.name) {
+            errors.add_error("name", err);
+        } else if let Err(err) = validator.get_rule("name_length").unwrap().validate(&self.name) {
+            errors.add_error("name", err);
         }
         
         // Validate price (min 0)
-        if let Err(err) = validator.get_rule("min_price").unwrap().validate(&self.price as &dyn Any) {
-            errors.entry("price".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
+        if let Err(err) = validator.get_rule("min_price").unwrap().validate(&self.price) {
+            errors.add_error("price", err);
         }
         
         // Validate contact_email (required, email format)
-        if let Err(err) = validator.get_rule("required").unwrap().validate(&self.contact_email as &dyn Any) {
-            errors.entry("contact_email".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        } else if let Err(err) = validator.get_rule("email").unwrap().validate(&self.contact_email as &dyn Any) {
-            errors.entry("contact_email".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
+        if let Err(err) = validator.get_rule("required").unwrap().validate(&self.contact_email) {
+            errors.add_error("contact_email", err);
+        } else if let Err(err) = validator.get_rule("email").unwrap().validate(&self.contact_email) {
+            errors.add_error("contact_email", err);
         }
         
         // Validate categories (min_size 1)
-        if let Err(err) = validator.get_rule("categories_required").unwrap().validate(&self.categories as &dyn Any) {
-            errors.entry("categories".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
+        if let Err(err) = validator.get_rule("categories_required").unwrap().validate(&self.categories) {
+            errors.add_error("categories", err);
         }
         
         // Validate each category (required)
         for (i, category) in self.categories.iter().enumerate() {
-            if let Err(err) = validator.get_rule("required").unwrap().validate(category as &dyn Any) {
-                errors.entry(format!("categories[{}]", i)).or_insert_with(Vec::new).push(format!("{}", err));
+            if let Err(err) = validator.get_rule("required").unwrap().validate(category) {
+                errors.add_error(FieldPath::from("categories").index(i), err);
             }
         }
         
         // Validate website (url format)
         if !self.website.is_empty() {
-            if let Err(err) = validator.get_rule("url").unwrap().validate(&self.website as &dyn Any) {
-                errors.entry("website".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
+            if let Err(err) = validator.get_rule("url").unwrap().validate(&self.website) {
+                errors.add_error("website", err);
             }
         }
         
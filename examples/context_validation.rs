@@ -0,0 +1,102 @@
+use std::sync::LazyLock;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rustvalidity::context::{ContextData, FixedClock, ValidationContext};
+use rustvalidity::error::{ValidationError, Violation};
+use rustvalidity::rules::conditional::RequiredIf;
+use rustvalidity::rules::{common, Rule};
+use rustvalidity::schema::Schema;
+use rustvalidity::validator::{ValidationMode, Validator};
+
+struct Order {
+    delivery: String,
+    shipping_address: String,
+    discount_percent: u32,
+    delivery_date: NaiveDate,
+}
+
+/// The user placing the order, supplied by the caller for every validation
+struct CurrentUser {
+    staff: bool,
+}
+
+/// Validates that a date is not before today, according to the context's clock
+struct NotInPast;
+
+impl Rule<NaiveDate> for NotInPast {
+    fn validate(&self, value: &NaiveDate) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &NaiveDate, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let today = ctx.now().date_naive();
+        if *value < today {
+            return Err(Violation::new("date.past", "Date must not be in the past")
+                .with_param("today", &today.to_string())
+                .into());
+        }
+        Ok(())
+    }
+}
+
+/// Validates that only staff grant discounts above 10 percent
+struct DiscountAllowed;
+
+impl Rule<u32> for DiscountAllowed {
+    fn validate(&self, value: &u32) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &u32, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let staff = ctx.data::<CurrentUser>().is_some_and(|user| user.staff);
+        if *value > 10 && !staff {
+            return Err(Violation::new("discount.not_allowed", "Only staff can grant this discount")
+                .with_param("max", &10)
+                .into());
+        }
+        Ok(())
+    }
+}
+
+static ORDER_SCHEMA: LazyLock<Schema<Order>> = LazyLock::new(|| {
+    Validator::for_type::<Order>()
+        .with_mode(ValidationMode::CollectAll)
+        .with_clock(FixedClock(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap()))
+        .field("delivery", |o| &o.delivery, vec![
+            Box::new(common::OneOf { values: vec!["ship".to_string(), "pickup".to_string()] }),
+        ])
+        .field("shipping_address", |o| &o.shipping_address, vec![
+            // The condition reads a sibling field from the parent instead of capturing it
+            Box::new(RequiredIf {
+                condition: Box::new(|ctx| ctx.parent::<Order>().is_some_and(|o| o.delivery == "ship")),
+            }),
+        ])
+        .field("discount_percent", |o| &o.discount_percent, vec![Box::new(DiscountAllowed)])
+        .field("delivery_date", |o| &o.delivery_date, vec![Box::new(NotInPast)])
+});
+
+fn main() {
+    let order = Order {
+        delivery: "ship".to_string(),
+        shipping_address: "".to_string(),
+        discount_percent: 25,
+        delivery_date: NaiveDate::from_ymd_opt(2024, 5, 30).unwrap(),
+    };
+
+    let customer = ContextData::new().with(CurrentUser { staff: false });
+    match ORDER_SCHEMA.validate_with_data(&order, &customer) {
+        Ok(_) => println!("Order accepted"),
+        Err(err) => println!("Order rejected: {}", err),
+    }
+
+    let staff = ContextData::new().with(CurrentUser { staff: true });
+    let pickup = Order {
+        delivery: "pickup".to_string(),
+        delivery_date: NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(),
+        ..order
+    };
+    match ORDER_SCHEMA.validate_with_data(&pickup, &staff) {
+        Ok(_) => println!("Order accepted"),
+        Err(err) => println!("Order rejected: {}", err),
+    }
+}
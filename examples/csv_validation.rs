@@ -0,0 +1,38 @@
+use std::fs::File;
+
+use rustvalidity::rules::advanced::RegexRule;
+use rustvalidity::rules::common::{Email, Length};
+use rustvalidity::rules::numeric::{Positive, Range};
+use rustvalidity::tabular::TableSchema;
+use rustvalidity::validator::ValidationMode;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Rules are bound to columns by header name; cells are converted to the rules' types
+    let schema = TableSchema::new()
+        .with_mode(ValidationMode::CollectAll)
+        .required("sku")
+        .column::<String>("sku", vec![Box::new(RegexRule::new("^[A-Z]{3}-[0-9]{4}$").expect("valid pattern"))])
+        .required("name")
+        .column::<String>("name", vec![Box::new(Length { min: 2, max: Some(60) })])
+        .column::<f64>("price", vec![Box::new(Positive)])
+        .column::<i64>("quantity", vec![Box::new(Range { min: 1, max: 100 })])
+        .column::<String>("email", vec![Box::new(Email { check_dns: false })])
+        .column::<String>("warehouse", vec![Box::new(Length { min: 2, max: Some(10) })]);
+
+    // Rows are read and checked one at a time
+    let file = File::open(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/data/products.csv"))?;
+    let report = schema.validate_csv(file)?;
+
+    for entry in report.header().entries() {
+        println!("header: {}", entry.violation);
+    }
+    for entry in report.entries() {
+        println!("row {} (line {}), {}: {}", entry.row, entry.line, entry.column, entry.violation);
+    }
+
+    println!("\n{} of {} rows invalid", report.invalid_rows(), report.row_count());
+    for (rule, count) in report.counts() {
+        println!("  {}: {}", rule, count);
+    }
+    Ok(())
+}
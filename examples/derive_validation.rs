@@ -1,3 +1,4 @@
+use chrono::NaiveDate;
 use rustvalidity::prelude::*;
 
 // Define a struct with validation attributes
@@ -19,6 +20,47 @@ struct Product {
     website: String,
 }
 
+// Struct-level comparisons check fields against each other
+#[derive(Debug, Validate)]
+#[validate(compare(field = "min_price", less_or_equal = "max_price"))]
+#[validate(compare(field = "start_date", less_than = "end_date", path = "end_date"))]
+#[validate(compare(field = "end_date", within = "start_date", tolerance = "chrono::TimeDelta::days(90)"))]
+struct Promotion {
+    #[validate(required)]
+    code: String,
+
+    min_price: f64,
+    max_price: f64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+}
+
+// Rules tagged with groups only run when one of their groups is validated;
+// untagged rules belong to the default group, which other groups inherit
+#[derive(Debug, Validate)]
+#[validate(compare(field = "password_confirmation", equal_to = "password", groups = ["create"]))]
+struct Account {
+    #[validate(required, email)]
+    email: String,
+
+    #[validate(required(groups = ["create"]), length(min = 8, groups = ["create"]))]
+    password: String,
+    password_confirmation: String,
+}
+
+// Modifiers normalize form input in place before it is validated
+#[derive(Debug, Validate)]
+struct Contact {
+    #[validate(sanitize(collapse_whitespace), required)]
+    name: String,
+
+    #[validate(sanitize(trim, lowercase), required, email)]
+    email: String,
+
+    #[validate(sanitize(nfkc, digits_only), phone)]
+    phone: String,
+}
+
 fn main() {
     // Create a valid product
     let valid_product = Product {
@@ -49,4 +91,45 @@ fn main() {
         Ok(_) => println!("Valid product: {}", invalid_product.name),
         Err(err) => println!("Validation failed: {}", err),
     }
+
+    let promotion = Promotion {
+        code: "SUMMER".to_string(),
+        min_price: 50.0,
+        max_price: 20.0,
+        start_date: NaiveDate::from_ymd_opt(2024, 9, 1).unwrap(),
+        end_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+    };
+
+    match promotion.validate() {
+        Ok(_) => println!("Valid promotion: {}", promotion.code),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    // Updating an account does not resend the password
+    let account = Account {
+        email: "user@example.com".to_string(),
+        password: String::new(),
+        password_confirmation: String::new(),
+    };
+
+    match account.validate_group("update") {
+        Ok(_) => println!("Valid account update: {}", account.email),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    match account.validate_group("create") {
+        Ok(_) => println!("Valid new account: {}", account.email),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    let mut contact = Contact {
+        name: "  Jane   Doe ".to_string(),
+        email: "  Jane.Doe@Example.COM ".to_string(),
+        phone: "＋１ (415) ５５５-2671".to_string(),
+    };
+
+    match contact.sanitize_and_validate() {
+        Ok(_) => println!("Valid contact: {:?}", contact),
+        Err(err) => println!("Validation failed: {}", err),
+    }
 }
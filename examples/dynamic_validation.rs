@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use rustvalidity::dynamic::DynamicSchema;
+use rustvalidity::rules::collection::{Each, MinSize, Unique};
+use rustvalidity::rules::common::{Email, Length, OneOf};
+use rustvalidity::rules::numeric::{Min, Range};
+use rustvalidity::rules::Rule;
+use rustvalidity::validator::ValidationMode;
+use serde_json::{json, Value};
+
+fn main() {
+    // Rules are bound to paths in the payload; `[*]` selects every item
+    let tag_rule: Box<dyn Rule<String>> = Box::new(Length { min: 2, max: Some(12) });
+    let schema = DynamicSchema::new()
+        .with_mode(ValidationMode::CollectAll)
+        .required("event")
+        .field::<String>("event", vec![Box::new(OneOf {
+            values: vec!["order.created".to_string(), "order.paid".to_string()],
+        })])
+        .required("customer.email")
+        .field::<String>("customer.email", vec![Box::new(Email { check_dns: false })])
+        .field::<Vec<Value>>("items", vec![Box::new(MinSize { min: 1 })])
+        .required("items[*].sku")
+        .field::<f64>("items[*].price", vec![Box::new(Range { min: 0.01, max: 10_000.0 })])
+        .field::<i64>("items[*].quantity", vec![Box::new(Min { value: 1 })])
+        .field::<Vec<String>>("items[*].tags", vec![Box::new(Each { rule: tag_rule }), Box::new(Unique)]);
+
+    // Prices and quantities sent as strings are converted before they are checked
+    let payload = json!({
+        "event": "order.created",
+        "customer": { "email": "jane.doe@example.com" },
+        "items": [
+            { "sku": "ABC-1", "price": "9.99", "quantity": "2", "tags": ["gift"] },
+            { "sku": "ABC-2", "price": 4.5, "quantity": 1 }
+        ]
+    });
+    match schema.validate(&payload) {
+        Ok(()) => println!("Payload is valid"),
+        Err(err) => println!("Payload is invalid: {}", err),
+    }
+
+    let payload = json!({
+        "event": "order.shipped",
+        "customer": { "email": "jane.doe" },
+        "items": [
+            { "sku": "ABC-1", "price": "free", "quantity": 0 },
+            { "price": 20000, "quantity": 1.5, "tags": ["gift", "gift", "x"] }
+        ]
+    });
+    if let Err(err) = schema.validate(&payload) {
+        // Errors are keyed by the concrete path of each offending value
+        println!("Payload is invalid:");
+        for entry in err.entries() {
+            println!("  {} [{}] {}", entry.path, entry.violation.code, entry.violation.message);
+        }
+    }
+
+    // Maps of JSON values are validated the same way
+    let mut fields: HashMap<String, Value> = HashMap::new();
+    fields.insert("event".to_string(), json!("order.paid"));
+    fields.insert("customer".to_string(), json!({ "email": "jane.doe@example.com" }));
+    fields.insert("items".to_string(), json!([]));
+    match schema.validate(&fields) {
+        Ok(()) => println!("Fields are valid"),
+        Err(err) => println!("Fields are invalid: {}", err),
+    }
+}
@@ -0,0 +1,53 @@
+use rustvalidity::form::{FormData, FormErrors, FormSchema};
+use rustvalidity::rules::collection::{Each, MaxSize, MinSize, Unique};
+use rustvalidity::rules::common::{Email, Length, OneOf};
+use rustvalidity::rules::numeric::{Positive, Range};
+use rustvalidity::validator::ValidationMode;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Rules are bound to field names; values are converted to the rules' types
+    let signup = FormSchema::new()
+        .with_mode(ValidationMode::CollectAll)
+        .required("name")
+        .field::<String>("name", vec![Box::new(Length { min: 2, max: Some(40) })])
+        .required("email")
+        .field::<String>("email", vec![Box::new(Email { check_dns: false })])
+        .field::<i32>("age", vec![Box::new(Range { min: 18, max: 120 })])
+        // A group of checkboxes sends the same name once per checked box
+        .values::<String>("interests", vec![
+            Box::new(MinSize { min: 1 }),
+            Box::new(MaxSize { max: 3 }),
+            Box::new(Unique),
+            Box::new(Each { rule: Box::new(OneOf { values: vec!["rust".to_string(), "go".to_string(), "zig".to_string()] }) }),
+        ])
+        .values::<u32>("referrers", vec![Box::new(Each { rule: Box::new(Positive) })]);
+
+    let body = "name=A&email=ada%40example&age=forty&interests=rust&interests=rust&interests=cobol&referrers=7&referrers=x";
+    let form = FormData::parse(body);
+
+    match signup.validate(&form) {
+        Ok(()) => println!("Signed up {}", form.get("name").unwrap_or_default()),
+        Err(err) => {
+            // Errors keyed by field, ready to show next to the inputs
+            let errors = FormErrors::from(&err);
+            for (field, messages) in errors.fields() {
+                println!("{} (sent {:?}):", field, form.get_all(field));
+                for message in messages {
+                    println!("  {}", message);
+                }
+            }
+            println!("\n{}", serde_json::to_string_pretty(&errors)?);
+        },
+    }
+
+    // Query strings are read the same way
+    let search = FormSchema::new()
+        .field::<u32>("page", vec![Box::new(Range { min: 1, max: 500 })])
+        .field::<String>("sort", vec![Box::new(OneOf { values: vec!["name".to_string(), "date".to_string()] })]);
+
+    for query in ["?page=2&sort=date", "?page=0&sort=size", "?page=two"] {
+        let errors = search.validate(&FormData::from_query(query)).err().map(FormErrors::from).unwrap_or_default();
+        println!("\n{}: {:?}", query, errors.fields());
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use rustvalidity::prelude::*;
+
+/// A postal address
+#[derive(Debug, Validate)]
+struct Address {
+    #[validate(required, length(max = 100))]
+    street: String,
+
+    #[validate(required)]
+    city: String,
+
+    /// Postal code, if the country uses them
+    postal_code: Option<String>,
+}
+
+/// A customer account
+#[derive(Debug, Validate)]
+struct Customer {
+    /// The name shown on invoices
+    #[validate(required, length(min = 3, max = 50))]
+    name: String,
+
+    #[validate(required, email)]
+    email: String,
+
+    #[validate(range(min = 18, max = 130))]
+    age: u8,
+
+    #[validate(unique, length(max = 10))]
+    tags: Vec<String>,
+
+    #[validate(url)]
+    website: String,
+
+    billing_address: Address,
+    shipping_addresses: Vec<Address>,
+}
+
+fn main() {
+    // The document describes the constraints enforced by `validate`
+    let schema = Customer::schema();
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+
+    let mut customer = Customer {
+        name: "Jane Doe".to_string(),
+        email: "jane@example.com".to_string(),
+        age: 34,
+        tags: vec!["retail".to_string()],
+        website: "https://example.com".to_string(),
+        billing_address: Address {
+            street: "1 Main Street".to_string(),
+            city: "Springfield".to_string(),
+            postal_code: Some("12345".to_string()),
+        },
+        shipping_addresses: Vec::new(),
+    };
+
+    match customer.validate() {
+        Ok(_) => println!("Valid customer: {}", customer.name),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    // Nested addresses are validated along with the customer
+    customer.shipping_addresses.push(Address {
+        street: "2 Side Street".to_string(),
+        city: String::new(),
+        postal_code: None,
+    });
+    if let Err(err) = customer.validate() {
+        for entry in err.entries() {
+            println!("{}: {}", entry.path, entry.violation);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use rustvalidity::json_schema::CompiledSchema;
+use serde_json::json;
+
+fn main() {
+    // Compile the schema once and reuse it for every document
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/schemas/order.json");
+    let schema = match CompiledSchema::from_file(path) {
+        Ok(schema) => schema,
+        Err(err) => {
+            println!("Failed to load schema: {}", err);
+            return;
+        }
+    };
+
+    let order = json!({
+        "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "customer": { "email": "jane.doe@example.com" },
+        "placed_on": "2024-03-01",
+        "status": "paid",
+        "items": [{ "sku": "ABC-1234", "quantity": 2, "price": 9.99 }]
+    });
+    match schema.validate(&order) {
+        Ok(()) => println!("Order is valid"),
+        Err(err) => println!("Order is invalid: {}", err),
+    }
+
+    let order = json!({
+        "id": "not-a-uuid",
+        "customer": { "website": "example com" },
+        "placed_on": "2024-02-30",
+        "status": "lost",
+        "items": [
+            { "sku": "ABC-1234", "quantity": 2, "price": 9.99 },
+            { "sku": "abc", "quantity": 0, "price": 0, "gift": true }
+        ]
+    });
+    if let Err(err) = schema.validate(&order) {
+        // Every violation is reported at the JSON Pointer of the offending value
+        println!("Order is invalid:");
+        for entry in err.entries() {
+            println!("  {} [{}] {}", entry.pointer, entry.violation.code, entry.violation.message);
+        }
+    }
+
+    // Schemas are checked when they are compiled
+    if let Err(err) = CompiledSchema::compile(&json!({ "properties": { "age": { "minimum": "18" } } })) {
+        println!("Invalid schema: {}", err);
+    }
+}
@@ -0,0 +1,36 @@
+use rustvalidity::error::{ErrorTree, ValidationError};
+use rustvalidity::i18n::MessageCatalog;
+use rustvalidity::rules::{common, numeric, collection, Rule};
+
+fn main() {
+    // The catalog starts with the built-in English bundle
+    let mut catalog = MessageCatalog::new();
+
+    // Load additional locales from `<locale>.json` files at runtime
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/locales");
+    match catalog.load_dir(dir) {
+        Ok(locales) => println!("Loaded locales: {:?}", locales),
+        Err(err) => println!("Failed to load locales: {}", err),
+    }
+
+    // Templates can also be added in code
+    catalog.add_message("ja", "required", "値は必須です");
+
+    // Collect a few errors
+    let mut errors = ErrorTree::new();
+    if let Err(err) = common::Required.validate(&String::new()) {
+        errors.add_error("username", err);
+    }
+    if let Err(err) = (numeric::Min { value: 18 }).validate(&16) {
+        errors.add_error("age", err);
+    }
+    if let Err(err) = (collection::MinSize { min: 1 }).validate(&Vec::<String>::new()) {
+        errors.add_error("interests", err);
+    }
+    let err = ValidationError::Multiple(errors);
+
+    // Render the same error in several locales after the fact
+    for locale in ["en", "de-AT", "ja", "fr"] {
+        println!("[{}]\n{}", locale, catalog.localize(&err, locale));
+    }
+}
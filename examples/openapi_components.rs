@@ -0,0 +1,78 @@
+use rustvalidity::json_schema::openapi::{Components, OpenApiVersion};
+use rustvalidity::prelude::*;
+use serde_json::json;
+
+/// A line of an order
+#[derive(Debug, Validate)]
+struct OrderLine {
+    #[validate(required, regex = "^[A-Z]{3}-[0-9]{4}$")]
+    sku: String,
+
+    #[validate(range(min = 1, max = 99))]
+    quantity: u32,
+}
+
+/// An order placed by a customer
+#[derive(Debug, Validate)]
+struct CreateOrder {
+    #[validate(required, email)]
+    customer_email: String,
+
+    #[validate(one_of = ["standard", "express"])]
+    shipping: String,
+
+    /// Requested delivery day
+    #[validate(date)]
+    deliver_on: String,
+
+    #[validate(url)]
+    callback_url: String,
+
+    /// Instructions for the courier
+    note: Option<String>,
+
+    #[validate(min_size = 1, max_size = 50)]
+    lines: Vec<OrderLine>,
+
+    #[validate(unique, max_size = 5)]
+    coupons: Vec<String>,
+}
+
+fn main() {
+    // The spec document is usually read from a file; only its components are generated
+    let mut spec = json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Orders", "version": "1.0.0" },
+        "paths": {
+            "/orders": {
+                "post": {
+                    "requestBody": {
+                        "content": { "application/json": { "schema": Components::reference::<CreateOrder>() } },
+                    },
+                    "responses": { "201": { "description": "Created" } },
+                },
+            },
+        },
+    });
+
+    Components::new()
+        .with_version(OpenApiVersion::V3_0)
+        .schema::<CreateOrder>()
+        .merge_into(&mut spec);
+
+    println!("{}", serde_json::to_string_pretty(&spec).unwrap());
+
+    let order = CreateOrder {
+        customer_email: "jane@example.com".to_string(),
+        shipping: "overnight".to_string(),
+        deliver_on: "2024-02-30".to_string(),
+        callback_url: "https://shop.example.com/hooks/orders".to_string(),
+        note: None,
+        lines: vec![OrderLine { sku: "ABC-1234".to_string(), quantity: 1 }],
+        coupons: Vec::new(),
+    };
+    match order.validate() {
+        Ok(_) => println!("Valid order, note: {}", order.note.as_deref().unwrap_or("none")),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+}
@@ -0,0 +1,50 @@
+use rustvalidity::error::ValidationError;
+use rustvalidity::rules::collection::{MaxSize, Unique};
+use rustvalidity::rules::combinator::RuleExt;
+use rustvalidity::rules::common::{Email, Length, Required};
+use rustvalidity::rules::numeric::{Max, Min};
+use rustvalidity::rules::{Rule, RuleInfo};
+use rustvalidity::validator::Validator;
+
+struct Account {
+    username: String,
+    email: String,
+    age: u32,
+    roles: Vec<String>,
+}
+
+struct NoAdmin;
+
+impl Rule<Vec<String>> for NoAdmin {
+    fn validate(&self, _roles: &Vec<String>) -> Result<(), ValidationError> {
+        Ok(())
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("no_admin", "Roles must not include admin")
+    }
+}
+
+fn main() {
+    let schema = Validator::for_type::<Account>()
+        .field("username", |a| &a.username, vec![
+            Box::new(Required),
+            Box::new(Length { min: 3, max: Some(20) }),
+        ])
+        .field("email", |a| &a.email, vec![Box::new(Required), Box::new(Email { check_dns: false })])
+        .field("age", |a| &a.age, vec![Box::new(Min { value: 18 }.and(Max { value: 130 }))])
+        .field("roles", |a| &a.roles, vec![Box::new(MaxSize { max: 5 }), Box::new(Unique), Box::new(NoAdmin)]);
+
+    // Every rule describes itself, so the reference table never drifts from the code
+    println!("| Field | Rule | Constraint |");
+    println!("|-------|------|------------|");
+    for (path, rules) in schema.describe() {
+        for rule in rules {
+            println!("| `{}` | `{}` | {} |", path, rule.name, rule);
+        }
+    }
+
+    // Descriptions serialize to JSON, e.g. for schema exporters
+    let (_, rules) = schema.describe()[2];
+    println!("\n{}", serde_json::to_string_pretty(&rules[0]).unwrap());
+}
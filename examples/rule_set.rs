@@ -0,0 +1,67 @@
+use rustvalidity::rule_set::{RuleSet, RuleSpec};
+use rustvalidity::validator::ValidationMode;
+use serde_json::json;
+
+fn main() {
+    // Limits live in a file, so they can change without a release
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/rules/signup.json");
+    let rules = match RuleSet::from_file(path) {
+        Ok(rules) => rules,
+        Err(err) => {
+            println!("Failed to load rules: {}", err);
+            return;
+        }
+    };
+    let schema = match rules.schema() {
+        Ok(schema) => schema.with_mode(ValidationMode::CollectAll),
+        Err(err) => {
+            println!("Failed to build rules: {}", err);
+            return;
+        }
+    };
+
+    let signup = json!({
+        "username": "jane_doe",
+        "email": "jane.doe@example.com",
+        "role": "editor",
+        "age": 34,
+        "address": { "postal_code": "12345" },
+        "interests": ["rust", "hiking"]
+    });
+    match schema.validate(&signup) {
+        Ok(()) => println!("Signup is valid"),
+        Err(err) => println!("Signup is invalid: {}", err),
+    }
+
+    let signup = json!({
+        "username": "JD",
+        "email": "jane.doe",
+        "role": "owner",
+        "age": 16,
+        "address": { "postal_code": "1234" },
+        "interests": ["rust", "rust", "x"]
+    });
+    if let Err(err) = schema.validate(&signup) {
+        println!("Signup is invalid:");
+        for entry in err.entries() {
+            println!("  {} [{}] {}", entry.path, entry.violation.code, entry.violation.message);
+        }
+    }
+
+    // Unknown rules and bad parameters are reported when the rules are loaded
+    for config in [
+        r#"{ "fields": { "username": ["requird"] } }"#,
+        r#"{ "fields": { "username": [{ "length": { "min": "three" } }] } }"#,
+    ] {
+        if let Err(err) = RuleSet::from_json(config) {
+            println!("Invalid rules: {}", err);
+        }
+    }
+
+    // Rule sets can also be built in code and written back out
+    let rules = RuleSet::new().field("nickname", vec![RuleSpec::new("length").with_param("max", 12)]);
+    match rules.to_json() {
+        Ok(json) => println!("{}", json),
+        Err(err) => println!("Failed to write rules: {}", err),
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::LazyLock;
+
+use rustvalidity::error::ValidationError;
+use rustvalidity::rules::{collection, common, numeric};
+use rustvalidity::schema::Schema;
+use rustvalidity::validator::{Validate, ValidationMode, ValidationRun, Validator};
+
+struct Address {
+    street: String,
+    city: String,
+}
+
+struct User {
+    username: String,
+    email: String,
+    age: i32,
+    interests: Vec<String>,
+    address: Address,
+}
+
+// Schemas are built once and reused for every validation
+static ADDRESS_SCHEMA: LazyLock<Schema<Address>> = LazyLock::new(|| {
+    Validator::for_type::<Address>()
+        .with_mode(ValidationMode::CollectAll)
+        .field("street", |a| &a.street, vec![Box::new(common::Required)])
+        .field("city", |a| &a.city, vec![Box::new(common::Required)])
+});
+
+static USER_SCHEMA: LazyLock<Schema<User>> = LazyLock::new(|| {
+    Validator::for_type::<User>()
+        .with_mode(ValidationMode::CollectAll)
+        .bail(true)
+        .field("username", |u| &u.username, vec![
+            Box::new(common::Required),
+            Box::new(common::Length { min: 3, max: Some(20) }),
+        ])
+        .field("email", |u| &u.email, vec![
+            Box::new(common::Required),
+            Box::new(common::Email { check_dns: false }),
+        ])
+        .field("age", |u| &u.age, vec![Box::new(numeric::Min { value: 18 })])
+        .field("interests", |u| &u.interests, vec![Box::new(collection::MinSize { min: 1 })])
+        .nested("address", |u| &u.address)
+});
+
+impl Validate for Address {
+    fn validate(&self) -> Result<(), ValidationError> {
+        ADDRESS_SCHEMA.validate(self)
+    }
+}
+
+impl Validate for User {
+    fn validate(&self) -> Result<(), ValidationError> {
+        USER_SCHEMA.validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        USER_SCHEMA.apply(self, run);
+    }
+}
+
+fn main() {
+    let valid_user = User {
+        username: "johndoe".to_string(),
+        email: "john@example.com".to_string(),
+        age: 25,
+        interests: vec!["coding".to_string()],
+        address: Address {
+            street: "1 Main Street".to_string(),
+            city: "Springfield".to_string(),
+        },
+    };
+
+    match valid_user.validate() {
+        Ok(_) => println!("Valid user: {}", valid_user.username),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    let invalid_user = User {
+        username: "jo".to_string(),
+        email: "".to_string(),
+        age: 16,
+        interests: vec![],
+        address: Address {
+            street: "".to_string(),
+            city: "".to_string(),
+        },
+    };
+
+    match invalid_user.validate() {
+        Ok(_) => println!("Valid user: {}", invalid_user.username),
+        Err(err) => println!("Validation failed: {}", err),
+    }
+
+    // The same schema can be driven by a fail-fast validator
+    match Validator::new().validate(&invalid_user) {
+        Ok(_) => println!("Valid user: {}", invalid_user.username),
+        Err(err) => println!("First problem: {}", err),
+    }
+}
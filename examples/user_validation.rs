@@ -1,9 +1,6 @@
-use std::collections::HashMap;
-
-use rustvalidity::prelude::*;
 use rustvalidity::error::ValidationError;
-use rustvalidity::validator::{Validator, Validate};
-use rustvalidity::rules::{Rule, common, numeric, collection};
+use rustvalidity::validator::{Validator, Validate, ValidationMode, ValidationRun};
+use rustvalidity::rules::{common, numeric, collection};
 
 struct User {
     username: String,
@@ -12,63 +9,34 @@ struct User {
     interests: Vec<String>,
 }
 
+fn user_validator() -> Validator {
+    // Create a new validator instance that reports every failing field,
+    // stopping at the first failure of each field
+    let mut validator = Validator::new()
+        .with_mode(ValidationMode::CollectAll)
+        .bail(true);
+    
+    // Add validation rules
+    validator.add_rule("required", common::Required);
+    validator.add_rule("username_length", common::Length { min: 3, max: Some(20) });
+    validator.add_rule("email", common::Email { check_dns: false });
+    validator.add_rule("min_age", numeric::Min { value: 18 });
+    validator.add_rule("interests_required", collection::MinSize { min: 1 });
+    
+    validator
+}
+
 impl Validate for User {
     fn validate(&self) -> Result<(), ValidationError> {
-        // Create a new validator instance
-        let mut validator = Validator::new();
-        
-        // Add validation rules
-        validator.add_rule("required", common::Required);
-        validator.add_rule("username_length", common::Length { min: 3, max: Some(20) });
-        validator.add_rule("email", common::Email { check_dns: false });
-        validator.add_rule("min_age", numeric::Min { value: 18 });
-        validator.add_rule("interests_required", collection::MinSize { min: 1 });
-        
-        // Validate individual fields
-        let mut errors = HashMap::new();
-        
-        // Validate username
-        if let Err(err) = validator.get_rule("required")
-            .unwrap()
-            .validate_any(&self.username) {
-            errors.entry("username".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        } else if let Err(err) = validator.get_rule("username_length")
-            .unwrap()
-            .validate_any(&self.username) {
-            errors.entry("username".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        }
-        
-        // Validate email
-        if let Err(err) = validator.get_rule("required")
-            .unwrap()
-            .validate_any(&self.email) {
-            errors.entry("email".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        } else if let Err(err) = validator.get_rule("email")
-            .unwrap()
-            .validate_any(&self.email) {
-            errors.entry("email".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        }
-        
-        // Validate age
-        if let Err(err) = validator.get_rule("min_age")
-            .unwrap()
-            .validate_any(&self.age) {
-            errors.entry("age".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        }
-        
-        // Validate interests
-        if let Err(err) = validator.get_rule("interests_required")
-            .unwrap()
-            .validate_any(&self.interests) {
-            errors.entry("interests".to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-        }
-        
-        // Check if there are any validation errors
-        if !errors.is_empty() {
-            return Err(ValidationError::Multiple(errors));
-        }
-        
-        Ok(())
+        user_validator().validate(self)
+    }
+    
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        // Validate individual fields against the registered rules
+        run.field("username", &self.username, &["required", "username_length"])
+            .field("email", &self.email, &["required", "email"])
+            .field("age", &self.age, &["min_age"])
+            .field("interests", &self.interests, &["interests_required"]);
     }
 }
 
@@ -100,4 +68,11 @@ fn main() {
         Ok(_) => println!("Valid user: {}", invalid_user.username),
         Err(err) => println!("Validation failed: {}", err),
     }
+    
+    // Stop at the first problem instead
+    let fail_fast = user_validator().with_mode(ValidationMode::FailFast);
+    match fail_fast.validate(&invalid_user) {
+        Ok(_) => println!("Valid user: {}", invalid_user.username),
+        Err(err) => println!("First problem: {}", err),
+    }
 }
@@ -0,0 +1,46 @@
+use rustvalidity::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Validate)]
+struct Registration {
+    #[validate(required, length(min = 3, max = 20))]
+    username: String,
+
+    #[validate(required, email)]
+    email: String,
+
+    #[validate(min = 18)]
+    age: i32,
+}
+
+// The signature guarantees the registration was validated
+fn register(registration: Validated<Registration>) {
+    println!("Registered {} <{}>", registration.username, registration.email);
+}
+
+fn main() {
+    // Deserializing validates the registration
+    let body = r#"{"username": "jane", "email": "jane@example.com", "age": 30}"#;
+    match serde_json::from_str::<Validated<Registration>>(body) {
+        Ok(registration) => register(registration),
+        Err(err) => println!("Rejected: {}", err),
+    }
+
+    // Validation errors surface as deserialization errors
+    let body = r#"{"username": "j", "email": "jane", "age": 12}"#;
+    match serde_json::from_str::<Validated<Registration>>(body) {
+        Ok(registration) => register(registration),
+        Err(err) => println!("Rejected: {}", err),
+    }
+
+    // Values built in code are validated explicitly
+    let registration = Registration {
+        username: "john".to_string(),
+        email: "john@example.com".to_string(),
+        age: 41,
+    };
+    match registration.into_validated() {
+        Ok(registration) => register(registration),
+        Err(err) => println!("Rejected: {}", err),
+    }
+}
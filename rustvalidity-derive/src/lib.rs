@@ -1,297 +1,690 @@
 use proc_macro::TokenStream;
-use quote::{quote, format_ident};
-use syn::{parse_macro_input, DeriveInput, Data, Fields, Lit, Meta, NestedMeta, MetaNameValue};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parenthesized, parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, LitStr, Token, Type};
 
 /// Derive macro for implementing the Validate trait
-/// 
+///
+/// Every field is checked and every error is collected, each reported at the
+/// field's path. Fields whose type implements `Validate`, such as other derived
+/// structs or `Vec`s and `Option`s of them, are validated as well, with their
+/// errors reported below the field, e.g. `addresses[1].city`. Struct-level
+/// `compare` attributes check two fields against each other and report at the
+/// first field unless `path` says otherwise.
+///
+/// Field rules are `required`, `email`, `url`, `uuid`, `json`, `phone`,
+/// `date` or `date(format = "%d/%m/%Y")`, `regex = "^[a-z]+$"`,
+/// `one_of = ["draft", "published"]`, `length(min = 3, max = 20)`, `min = 18`,
+/// `max = 130`, `range(min = 1, max = 5)`, `positive`, `negative`, `unique`,
+/// `min_size = 1` and `max_size = 10`. Patterns are checked at compile time, and
+/// numeric bounds must fit the field's type: `min = 1.5` on a `u32` is an error.
+/// Numeric rules on an `Option` field check the value when there is one.
+///
+/// Any rule accepts a `groups = [...]` option restricting it to validation
+/// groups, e.g. `required(groups = ["create"])`, `length(min = 8, groups = ["create"])`
+/// or `min(18, groups = ["signup"])`. Rules without it belong to the default group.
+///
+/// `sanitize(...)` lists modifiers normalizing a field before validation, in
+/// order: `trim`, `lowercase`, `uppercase`, `collapse_whitespace`, `nfc`,
+/// `nfkc`, `strip_control` and `digits_only`. A struct with sanitized fields
+/// also implements `Sanitize`, so `sanitize_and_validate` applies them first.
+///
+/// The struct also implements `JsonSchema`: `User::schema()` returns a
+/// Draft 2020-12 document with a property per field, described by the field's
+/// type and rules, and doc comments as descriptions. The same description can
+/// be added to the `components` of an OpenAPI document with
+/// `Components::new().schema::<User>()`.
+///
 /// # Example
-/// 
-/// ```rust
+///
+/// ```ignore
 /// #[derive(Validate)]
+/// #[validate(compare(field = "password_confirmation", equal_to = "password"))]
+/// #[validate(compare(field = "start_date", less_than = "end_date", path = "end_date"))]
 /// struct User {
 ///     #[validate(required, length(min = 3, max = 20))]
 ///     username: String,
-///     
-///     #[validate(required, email)]
+///
+///     #[validate(sanitize(trim, lowercase), required, email)]
 ///     email: String,
-///     
+///
 ///     #[validate(min = 18)]
 ///     age: i32,
+///
+///     #[validate(required(groups = ["create"]), length(min = 8, groups = ["create", "password"]))]
+///     password: String,
+///     password_confirmation: String,
+///     start_date: NaiveDate,
+///     end_date: NaiveDate,
 /// }
 /// ```
 #[proc_macro_derive(Validate, attributes(validate))]
 pub fn derive_validate(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    
-    // Get the name of the struct
+
+    expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
     let name = &input.ident;
-    
+
     // Get the fields of the struct
     let fields = match &input.data {
-        Data::Struct(data) => {
-            match &data.fields {
-                Fields::Named(fields) => &fields.named,
-                _ => panic!("Validate derive only supports structs with named fields"),
-            }
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "Validate derive only supports structs with named fields")),
         },
-        _ => panic!("Validate derive only supports structs"),
+        _ => return Err(syn::Error::new_spanned(name, "Validate derive only supports structs")),
     };
-    
+
     // Generate validation code for each field
-    let field_validations = fields.iter().map(|field| {
-        let field_name = &field.ident;
-        let field_name_str = field_name.as_ref().unwrap().to_string();
-        
-        // Get validation attributes
-        let validations = field.attrs.iter()
-            .filter(|attr| attr.path.is_ident("validate"))
-            .flat_map(|attr| {
-                match attr.parse_meta() {
-                    Ok(Meta::List(meta_list)) => meta_list.nested,
-                    _ => panic!("Invalid validate attribute"),
+    let mut field_validations = Vec::new();
+    let mut field_sanitizers = Vec::new();
+    let mut field_schemas = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let path = ident.to_string();
+        let ty = &field.ty;
+        let description = doc_comment(&field.attrs).into_iter();
+
+        let mut rules = Vec::new();
+        let mut modifiers = Vec::new();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("sanitize") {
+                    meta.parse_nested_meta(|inner| field_modifier(inner, &mut modifiers))
+                } else {
+                    field_rule(meta, ty, &mut rules)
                 }
+            })?;
+        }
+        if !modifiers.is_empty() {
+            field_sanitizers.push(quote! {
+                #(::rustvalidity::sanitize::Sanitizable::sanitize_with(&mut self.#ident, &#modifiers);)*
             });
-        
-        // Generate validation code for each attribute
-        let validation_code = validations.map(|validation| {
-            match validation {
-                NestedMeta::Meta(Meta::Path(path)) => {
-                    let rule_name = path.get_ident().unwrap().to_string();
-                    let rule_ident = format_ident!("{}", rule_name);
-                    
-                    match rule_name.as_str() {
-                        "required" => quote! {
-                            if let Err(err) = validator.get_rule("required").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "email" => quote! {
-                            if let Err(err) = validator.get_rule("email").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "url" => quote! {
-                            if let Err(err) = validator.get_rule("url").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "uuid" => quote! {
-                            if let Err(err) = validator.get_rule("uuid").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "json" => quote! {
-                            if let Err(err) = validator.get_rule("json").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "positive" => quote! {
-                            if let Err(err) = validator.get_rule("positive").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "negative" => quote! {
-                            if let Err(err) = validator.get_rule("negative").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "unique" => quote! {
-                            if let Err(err) = validator.get_rule("unique").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        "phone" => quote! {
-                            if let Err(err) = validator.get_rule("phone").unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                        _ => quote! {
-                            // Custom rule
-                            if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                            }
-                        },
-                    }
-                },
-                NestedMeta::Meta(Meta::List(meta_list)) => {
-                    let rule_name = meta_list.path.get_ident().unwrap().to_string();
-                    let rule_ident = format_ident!("{}", rule_name);
-                    
-                    match rule_name.as_str() {
-                        "length" => {
-                            let mut min = 0;
-                            let mut max = None;
-                            
-                            for nested in meta_list.nested.iter() {
-                                if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
-                                    let name = name_value.path.get_ident().unwrap().to_string();
-                                    if let Lit::Int(lit_int) = &name_value.lit {
-                                        let value = lit_int.base10_parse::<usize>().unwrap();
-                                        if name == "min" {
-                                            min = value;
-                                        } else if name == "max" {
-                                            max = Some(value);
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            let rule_name = format!("{}_length", field_name_str);
-                            
-                            quote! {
-                                validator.add_rule(#rule_name, common::Length { min: #min, max: #max });
-                                if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                    errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                }
-                            }
-                        },
-                        "min" => {
-                            let mut value = 0;
-                            
-                            for nested in meta_list.nested.iter() {
-                                if let NestedMeta::Lit(Lit::Int(lit_int)) = nested {
-                                    value = lit_int.base10_parse::<i32>().unwrap();
-                                }
-                            }
-                            
-                            let rule_name = format!("{}_min", field_name_str);
-                            
-                            quote! {
-                                validator.add_rule(#rule_name, numeric::Min { value: #value });
-                                if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                    errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                }
-                            }
-                        },
-                        "max" => {
-                            let mut value = 0;
-                            
-                            for nested in meta_list.nested.iter() {
-                                if let NestedMeta::Lit(Lit::Int(lit_int)) = nested {
-                                    value = lit_int.base10_parse::<i32>().unwrap();
-                                }
-                            }
-                            
-                            let rule_name = format!("{}_max", field_name_str);
-                            
-                            quote! {
-                                validator.add_rule(#rule_name, numeric::Max { value: #value });
-                                if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                    errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                }
-                            }
-                        },
-                        "range" => {
-                            let mut min = 0;
-                            let mut max = 0;
-                            
-                            for nested in meta_list.nested.iter() {
-                                if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
-                                    let name = name_value.path.get_ident().unwrap().to_string();
-                                    if let Lit::Int(lit_int) = &name_value.lit {
-                                        let value = lit_int.base10_parse::<i32>().unwrap();
-                                        if name == "min" {
-                                            min = value;
-                                        } else if name == "max" {
-                                            max = value;
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            let rule_name = format!("{}_range", field_name_str);
-                            
-                            quote! {
-                                validator.add_rule(#rule_name, numeric::Range { min: #min, max: #max });
-                                if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                    errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                }
-                            }
-                        },
-                        _ => quote! {},
-                    }
-                },
-                NestedMeta::Meta(Meta::NameValue(name_value)) => {
-                    let rule_name = name_value.path.get_ident().unwrap().to_string();
-                    
-                    match rule_name.as_str() {
-                        "min" => {
-                            if let Lit::Int(lit_int) = &name_value.lit {
-                                let value = lit_int.base10_parse::<i32>().unwrap();
-                                let rule_name = format!("{}_min", field_name_str);
-                                
-                                quote! {
-                                    validator.add_rule(#rule_name, numeric::Min { value: #value });
-                                    if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                        errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                    }
-                                }
-                            } else {
-                                quote! {}
-                            }
-                        },
-                        "max" => {
-                            if let Lit::Int(lit_int) = &name_value.lit {
-                                let value = lit_int.base10_parse::<i32>().unwrap();
-                                let rule_name = format!("{}_max", field_name_str);
-                                
-                                quote! {
-                                    validator.add_rule(#rule_name, numeric::Max { value: #value });
-                                    if let Err(err) = validator.get_rule(#rule_name).unwrap().validate(&self.#field_name as &dyn Any) {
-                                        errors.entry(#field_name_str.to_string()).or_insert_with(Vec::new).push(format!("{}", err));
-                                    }
-                                }
-                            } else {
-                                quote! {}
-                            }
-                        },
-                        _ => quote! {},
-                    }
-                },
-                _ => quote! {},
+        }
+
+        field_schemas.push(quote! {
+            {
+                let rules: ::std::vec::Vec<::std::boxed::Box<dyn ::rustvalidity::rules::Rule<#ty>>> =
+                    ::std::vec![#(::std::boxed::Box::new(#rules)),*];
+                let probe = ::rustvalidity::json_schema::__private::Probe::<#ty>::new();
+                object.property(
+                    #path,
+                    (&probe).describe(gen),
+                    ::rustvalidity::json_schema::Keywords::from_rules(&rules),
+                    (&probe).optional(),
+                );
+                #(object.describe_property(#path, #description);)*
+            }
+        });
+
+        if !rules.is_empty() {
+            field_validations.push(quote! {
+                {
+                    let rules: ::std::vec::Vec<::std::boxed::Box<dyn ::rustvalidity::rules::Rule<#ty>>> =
+                        ::std::vec![#(::std::boxed::Box::new(#rules)),*];
+                    run.rules(#path, &self.#ident, &rules);
+                }
+            });
+        }
+
+        // Fields whose type implements `Validate`, such as nested derived structs, are validated below the field
+        field_validations.push(quote! {
+            {
+                use ::rustvalidity::validator::__private::{Nested as _, Unnested as _};
+
+                let probe = ::rustvalidity::validator::__private::Probe::<#ty>::new();
+                (&probe).nest(run, #path, &self.#ident);
             }
-        }).collect::<Vec<_>>();
-        
+        });
+    }
+
+    // Generate validation code for each struct-level comparison
+    let names: Vec<&Ident> = fields.iter().filter_map(|field| field.ident.as_ref()).collect();
+    let mut struct_validations = Vec::new();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("compare") {
+                let comparison = Comparison::parse(&meta)?;
+                struct_validations.push(comparison.expand(&names)?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown struct-level validation, expected `compare(...)`"))
+            }
+        })?;
+    }
+
+    // Generate the implementation of the Sanitize trait if any field is sanitized
+    let sanitize_impl = (!field_sanitizers.is_empty()).then(|| {
         quote! {
-            #(#validation_code)*
+            impl ::rustvalidity::sanitize::Sanitize for #name {
+                fn sanitize(&mut self) {
+                    #(#field_sanitizers)*
+                }
+            }
+        }
+    });
+
+    // Generate the implementation of the JsonSchema trait
+    let description = doc_comment(&input.attrs).into_iter();
+    let schema_impl = quote! {
+        impl ::rustvalidity::json_schema::JsonSchema for #name {
+            fn schema_name() -> ::core::option::Option<::std::string::String> {
+                ::core::option::Option::Some(::std::string::String::from(stringify!(#name)))
+            }
+
+            fn json_schema(gen: &mut ::rustvalidity::json_schema::SchemaGenerator) -> ::rustvalidity::json_schema::Value {
+                use ::rustvalidity::json_schema::__private::{Described as _, Undescribed as _};
+
+                let mut object = ::rustvalidity::json_schema::ObjectSchema::new();
+                #(object.description(#description);)*
+                #(#field_schemas)*
+                object.into_value()
+            }
         }
-    }).collect::<Vec<_>>();
-    
+    };
+
     // Generate the implementation of the Validate trait
-    let expanded = quote! {
-        impl Validate for #name {
-            fn validate(&self) -> Result<(), ValidationError> {
-                use std::any::Any;
-                use std::collections::HashMap;
-                
-                // Create a new validator instance
-                let mut validator = Validator::new();
-                
-                // Add common validation rules
-                validator.add_rule("required", common::Required);
-                validator.add_rule("email", common::Email { check_dns: false });
-                validator.add_rule("url", common::Url { allowed_schemes: None });
-                validator.add_rule("uuid", common::Uuid);
-                validator.add_rule("json", common::Json);
-                validator.add_rule("positive", numeric::Positive);
-                validator.add_rule("negative", numeric::Negative);
-                validator.add_rule("unique", collection::Unique);
-                validator.add_rule("phone", common::Phone { allow_empty: false });
-                
-                // Validate fields
-                let mut errors = HashMap::new();
-                
-                #(#field_validations)*
-                
-                // Check if there are any validation errors
-                if !errors.is_empty() {
-                    return Err(ValidationError::Multiple(errors));
+    Ok(quote! {
+        #sanitize_impl
+        #schema_impl
+
+        impl ::rustvalidity::validator::Validate for #name {
+            fn validate(&self) -> ::core::result::Result<(), ::rustvalidity::error::ValidationError> {
+                ::rustvalidity::validator::Validator::new()
+                    .with_mode(::rustvalidity::validator::ValidationMode::CollectAll)
+                    .validate(self)
+            }
+
+            fn validate_group(&self, group: &str) -> ::core::result::Result<(), ::rustvalidity::error::ValidationError> {
+                ::rustvalidity::validator::Validator::new()
+                    .with_mode(::rustvalidity::validator::ValidationMode::CollectAll)
+                    .validate_group(self, group)
+            }
+
+            fn validate_with<'v>(&'v self, run: &mut ::rustvalidity::validator::ValidationRun<'v>) {
+                run.scope(self, |run| {
+                    #(#field_validations)*
+                    #(#struct_validations)*
+                });
+            }
+        }
+    })
+}
+
+/// The text of the doc comments of an item, if it has any
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }),
+                ..
+            }) => Some(lit.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Parse one rule of a field attribute into the expression building it
+fn field_rule(meta: ParseNestedMeta, ty: &Type, rules: &mut Vec<TokenStream2>) -> syn::Result<()> {
+    let Some(ident) = meta.path.get_ident() else {
+        return Err(meta.error("expected a validation rule"));
+    };
+
+    let mut groups = Vec::new();
+    let rule = match ident.to_string().as_str() {
+        "required" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::Required })?,
+        "email" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::Email { check_dns: false } })?,
+        "url" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::UrlRule { allowed_schemes: None } })?,
+        "uuid" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::UuidRule })?,
+        "json" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::Json })?,
+        "phone" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::common::Phone { allow_empty: false } })?,
+        "positive" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::numeric::Positive })?,
+        "negative" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::numeric::Negative })?,
+        "unique" => plain(&meta, &mut groups, quote! { ::rustvalidity::rules::collection::Unique })?,
+        "length" => {
+            let (min, max) = limits(&meta, &mut groups)?;
+            let min = min.map_or_else(|| quote! { 0 }, |min| quote! { #min });
+            let max = max.map_or_else(|| quote! { None }, |max| quote! { Some(#max) });
+            quote! { ::rustvalidity::rules::common::Length { min: #min, max: #max } }
+        },
+        "min" => {
+            let value = typed_bound(&bound(&meta, &mut groups)?, ty)?;
+            numeric(ty, quote! { ::rustvalidity::rules::numeric::Min { value: #value } })
+        },
+        "max" => {
+            let value = typed_bound(&bound(&meta, &mut groups)?, ty)?;
+            numeric(ty, quote! { ::rustvalidity::rules::numeric::Max { value: #value } })
+        },
+        "range" => {
+            let (Some(min), Some(max)) = limits(&meta, &mut groups)? else {
+                return Err(meta.error("`range` needs both `min` and `max`"));
+            };
+            let (min, max) = (typed_bound(&min, ty)?, typed_bound(&max, ty)?);
+            numeric(ty, quote! { ::rustvalidity::rules::numeric::Range { min: #min, max: #max } })
+        },
+        "min_size" => {
+            let min = bound(&meta, &mut groups)?;
+            quote! { ::rustvalidity::rules::collection::MinSize { min: #min } }
+        },
+        "max_size" => {
+            let max = bound(&meta, &mut groups)?;
+            quote! { ::rustvalidity::rules::collection::MaxSize { max: #max } }
+        },
+        "one_of" => {
+            let values = setting(&meta, "values", &mut groups, |input| {
+                let content;
+                bracketed!(content in input);
+                Ok(Punctuated::<Expr, Token![,]>::parse_terminated(&content)?.into_iter().collect::<Vec<_>>())
+            })?;
+            quote! {
+                ::rustvalidity::rules::common::OneOf::<#ty> {
+                    values: ::std::vec![#(::core::convert::Into::into(#values)),*],
+                }
+            }
+        },
+        "regex" => {
+            let pattern = setting(&meta, "pattern", &mut groups, |input| input.parse::<LitStr>())?;
+            if let Err(err) = regex::Regex::new(&pattern.value()) {
+                return Err(syn::Error::new(pattern.span(), format!("invalid pattern: {}", err)));
+            }
+            // The pattern is compiled once and shared by every validation
+            quote! {
+                {
+                    static RULE: ::std::sync::LazyLock<::std::sync::Arc<::rustvalidity::rules::advanced::RegexRule>> =
+                        ::std::sync::LazyLock::new(|| {
+                            ::std::sync::Arc::new(
+                                ::rustvalidity::rules::advanced::RegexRule::new(#pattern).expect("pattern checked by the derive"),
+                            )
+                        });
+                    ::std::sync::Arc::clone(&RULE)
+                }
+            }
+        },
+        "date" => {
+            let mut format = None;
+            if !(meta.input.is_empty() || meta.input.peek(Token![,])) {
+                meta.parse_nested_meta(|inner| {
+                    if inner.path.is_ident("format") {
+                        format = Some(inner.value()?.parse::<LitStr>()?);
+                    } else if inner.path.is_ident("groups") {
+                        groups = parse_groups(&inner)?;
+                    } else {
+                        return Err(inner.error("expected `format` or `groups`"));
+                    }
+                    Ok(())
+                })?;
+            }
+            let format = format.map_or_else(|| quote! { "%Y-%m-%d" }, |format| quote! { #format });
+            quote! {
+                ::rustvalidity::rules::common::Date {
+                    format: ::std::string::String::from(#format),
+                    min: ::core::option::Option::None,
+                    max: ::core::option::Option::None,
+                }
+            }
+        },
+        _ => return Err(meta.error(format!("unknown validation rule `{}`", ident))),
+    };
+
+    rules.push(grouped(rule, &groups));
+    Ok(())
+}
+
+/// Parse one modifier of a `sanitize(...)` list into the value applying it
+fn field_modifier(meta: ParseNestedMeta, modifiers: &mut Vec<TokenStream2>) -> syn::Result<()> {
+    let Some(ident) = meta.path.get_ident() else {
+        return Err(meta.error("expected a modifier"));
+    };
+
+    let modifier = match ident.to_string().as_str() {
+        "trim" => quote! { ::rustvalidity::sanitize::Trim },
+        "lowercase" => quote! { ::rustvalidity::sanitize::Lowercase },
+        "uppercase" => quote! { ::rustvalidity::sanitize::Uppercase },
+        "collapse_whitespace" => quote! { ::rustvalidity::sanitize::CollapseWhitespace },
+        "nfc" => quote! { ::rustvalidity::sanitize::Nfc },
+        "nfkc" => quote! { ::rustvalidity::sanitize::Nfkc },
+        "strip_control" => quote! { ::rustvalidity::sanitize::StripControl },
+        "digits_only" => quote! { ::rustvalidity::sanitize::DigitsOnly },
+        _ => return Err(meta.error(format!("unknown modifier `{}`", ident))),
+    };
+
+    modifiers.push(modifier);
+    Ok(())
+}
+
+/// The type of the values of a field, with `Option` peeled from optional fields
+fn value_type(ty: &Type) -> (&Type, bool) {
+    if let Type::Path(syn::TypePath { qself: None, path }) = ty {
+        if let Some(segment) = path.segments.last().filter(|segment| segment.ident == "Option") {
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let [syn::GenericArgument::Type(inner)] = args.args.iter().collect::<Vec<_>>()[..] {
+                    return (inner, true);
                 }
-                
-                Ok(())
             }
         }
+    }
+    (ty, false)
+}
+
+/// Build a numeric rule checking the values of a field, skipping absent values of optional fields
+fn numeric(ty: &Type, rule: TokenStream2) -> TokenStream2 {
+    match value_type(ty) {
+        (inner, true) => quote! { ::rustvalidity::rules::combinator::RuleExt::<#inner>::optional(#rule) },
+        (_, false) => rule,
+    }
+}
+
+/// Type a bound of `min`, `max` or `range` as the values of the field it limits
+///
+/// Literals are checked against primitive types rather than cast, so `min = 1.5`
+/// on an integer field is an error instead of the bound `1`.
+fn typed_bound(value: &Expr, ty: &Type) -> syn::Result<TokenStream2> {
+    let (inner, _) = value_type(ty);
+    let primitive = match inner {
+        Type::Path(syn::TypePath { qself: None, path }) => path.get_ident().map(Ident::to_string),
+        _ => None,
     };
-    
-    TokenStream::from(expanded)
+    let integer = primitive.as_deref().is_some_and(|name| INTEGERS.contains(&name));
+    let float = matches!(primitive.as_deref(), Some("f32" | "f64"));
+    let (negative, lit) = match value {
+        Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => (true, &**expr),
+        _ => (false, value),
+    };
+    match lit {
+        Expr::Lit(syn::ExprLit { lit: syn::Lit::Float(_), .. }) if integer => Err(syn::Error::new_spanned(
+            value,
+            format!("expected an integer bound for a field of type `{}`", quote!(#inner)),
+        )),
+        Expr::Lit(_) if integer && negative && primitive.as_deref().is_some_and(|name| name.starts_with('u')) => Err(
+            syn::Error::new_spanned(value, format!("expected a bound that is not negative for a field of type `{}`", quote!(#inner))),
+        ),
+        Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) if float => {
+            let float = syn::LitFloat::new(&format!("{}.0", int.base10_digits()), int.span());
+            let sign = negative.then(|| quote! { - });
+            Ok(quote! { { let value: #inner = #sign #float; value } })
+        },
+        _ => Ok(quote! { { let value: #inner = #value; value } }),
+    }
+}
+
+/// Primitive integer types, whose bounds must be integers
+const INTEGERS: [&str; 12] = ["i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize"];
+
+/// Restrict a rule to validation groups, if any were given
+fn grouped(rule: TokenStream2, groups: &[LitStr]) -> TokenStream2 {
+    if groups.is_empty() {
+        return rule;
+    }
+    quote! { ::rustvalidity::rules::combinator::Grouped::new(#rule, [#(#groups),*]) }
+}
+
+/// Parse `groups = ["create", "update"]`
+fn parse_groups(meta: &ParseNestedMeta) -> syn::Result<Vec<LitStr>> {
+    group_list(meta.value()?)
+}
+
+/// Parse the list of group names, `["create", "update"]`
+fn group_list(input: ParseStream) -> syn::Result<Vec<LitStr>> {
+    let content;
+    bracketed!(content in input);
+    let groups = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(groups.into_iter().collect())
+}
+
+/// Parse a rule without settings, such as `required` or `required(groups = [...])`
+fn plain(meta: &ParseNestedMeta, groups: &mut Vec<LitStr>, rule: TokenStream2) -> syn::Result<TokenStream2> {
+    if meta.input.is_empty() || meta.input.peek(Token![,]) {
+        return Ok(rule);
+    }
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("groups") {
+            *groups = parse_groups(&inner)?;
+            Ok(())
+        } else {
+            Err(inner.error("expected `groups`"))
+        }
+    })?;
+    Ok(rule)
+}
+
+/// Parse the main setting of a rule, given as `regex = "..."` or `regex(pattern = "...", groups = [...])`
+fn setting<T>(
+    meta: &ParseNestedMeta,
+    name: &str,
+    groups: &mut Vec<LitStr>,
+    parse: impl Fn(ParseStream) -> syn::Result<T>,
+) -> syn::Result<T> {
+    if meta.input.peek(Token![=]) {
+        return parse(meta.value()?);
+    }
+    let mut value = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident(name) {
+            value = Some(parse(inner.value()?)?);
+        } else if inner.path.is_ident("groups") {
+            *groups = parse_groups(&inner)?;
+        } else {
+            return Err(inner.error(format!("expected `{}` or `groups`", name)));
+        }
+        Ok(())
+    })?;
+    value.ok_or_else(|| meta.error(format!("missing `{}`", name)))
+}
+
+/// Parse the `min` and `max` of `length(...)` or `range(...)`
+fn limits(meta: &ParseNestedMeta, groups: &mut Vec<LitStr>) -> syn::Result<(Option<Expr>, Option<Expr>)> {
+    let mut min = None;
+    let mut max = None;
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("min") {
+            min = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("max") {
+            max = Some(inner.value()?.parse::<Expr>()?);
+        } else if inner.path.is_ident("groups") {
+            *groups = parse_groups(&inner)?;
+        } else {
+            return Err(inner.error("expected `min`, `max` or `groups`"));
+        }
+        Ok(())
+    })?;
+    Ok((min, max))
+}
+
+/// Parse the bound of `min = 1`, `min(1)` or `min(1, groups = [...])`
+fn bound(meta: &ParseNestedMeta, groups: &mut Vec<LitStr>) -> syn::Result<Expr> {
+    if meta.input.peek(Token![=]) {
+        return meta.value()?.parse();
+    }
+    let content;
+    parenthesized!(content in meta.input);
+    let value = content.parse()?;
+    if content.parse::<Option<Token![,]>>()?.is_some() && !content.is_empty() {
+        let key: Ident = content.parse()?;
+        if key != "groups" {
+            return Err(syn::Error::new(key.span(), "expected `groups`"));
+        }
+        content.parse::<Token![=]>()?;
+        *groups = group_list(&content)?;
+    }
+    Ok(value)
+}
+
+/// Relations a `compare` attribute can require, named like the builder methods of `Compare`
+const RELATIONS: [&str; 7] = [
+    "equal_to",
+    "not_equal_to",
+    "less_than",
+    "less_or_equal",
+    "greater_than",
+    "greater_or_equal",
+    "within",
+];
+
+/// A struct-level `compare(field = "a", less_than = "b", path = "...", message = "...", groups = [...])` attribute
+struct Comparison {
+    field: LitStr,
+    relation: Ident,
+    other: LitStr,
+    tolerance: Option<Expr>,
+    path: Option<LitStr>,
+    message: Option<LitStr>,
+    groups: Vec<LitStr>,
+}
+
+impl Comparison {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let mut field = None;
+        let mut relation = None;
+        let mut tolerance = None;
+        let mut path = None;
+        let mut message = None;
+        let mut groups = Vec::new();
+
+        meta.parse_nested_meta(|inner| {
+            let Some(ident) = inner.path.get_ident() else {
+                return Err(inner.error("expected a comparison setting"));
+            };
+            let key = ident.to_string();
+            if key == "field" {
+                field = Some(inner.value()?.parse::<LitStr>()?);
+            } else if key == "path" {
+                path = Some(inner.value()?.parse::<LitStr>()?);
+            } else if key == "message" {
+                message = Some(inner.value()?.parse::<LitStr>()?);
+            } else if key == "groups" {
+                groups = parse_groups(&inner)?;
+            } else if key == "tolerance" {
+                // Strings hold expressions that are not literals, e.g. "chrono::TimeDelta::days(30)"
+                let value = inner.value()?.parse::<Expr>()?;
+                tolerance = Some(match &value {
+                    Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => lit.parse::<Expr>()?,
+                    _ => value,
+                });
+            } else if RELATIONS.contains(&key.as_str()) {
+                if relation.is_some() {
+                    return Err(inner.error("a comparison takes a single relation"));
+                }
+                relation = Some((ident.clone(), inner.value()?.parse::<LitStr>()?));
+            } else {
+                return Err(inner.error(format!("unknown comparison setting `{}`", key)));
+            }
+            Ok(())
+        })?;
+
+        let Some(field) = field else {
+            return Err(meta.error("`compare` needs the `field` to compare"));
+        };
+        let Some((relation, other)) = relation else {
+            return Err(meta.error(format!("`compare` needs one of {}", RELATIONS.join(", "))));
+        };
+        if (relation == "within") != tolerance.is_some() {
+            return Err(meta.error("`tolerance` is required by `within` and only allowed with it"));
+        }
+
+        Ok(Comparison {
+            field,
+            relation,
+            other,
+            tolerance,
+            path,
+            message,
+            groups,
+        })
+    }
+
+    fn expand(&self, fields: &[&Ident]) -> syn::Result<TokenStream2> {
+        let field = field_ident(&self.field, fields)?;
+        let other = field_ident(&self.other, fields)?;
+        let (field_name, other_name, relation) = (&self.field, &self.other, &self.relation);
+        let tolerance = self.tolerance.iter();
+        let path = self.path.iter();
+        let message = self.message.iter();
+
+        let rule = grouped(
+            quote! {
+                ::rustvalidity::rules::comparison::Compare::field(#field_name, |s: &Self| &s.#field)
+                    .#relation(#other_name, |s: &Self| &s.#other #(, #tolerance)*)
+                    #(.at(#path))*
+                    #(.with_message(#message))*
+            },
+            &self.groups,
+        );
+
+        Ok(quote! {
+            {
+                let rules: ::std::vec::Vec<::std::boxed::Box<dyn ::rustvalidity::rules::Rule<Self>>> =
+                    ::std::vec![::std::boxed::Box::new(#rule)];
+                run.rules(::rustvalidity::path::FieldPath::root(), self, &rules);
+            }
+        })
+    }
+}
+
+/// Resolve a field named in a string to its identifier
+fn field_ident(name: &LitStr, fields: &[&Ident]) -> syn::Result<Ident> {
+    let value = name.value();
+    fields
+        .iter()
+        .find(|ident| **ident == value.as_str())
+        .map(|ident| (*ident).clone())
+        .ok_or_else(|| syn::Error::new(name.span(), format!("no field named `{}`", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn error(input: DeriveInput) -> String {
+        expand(&input).expect_err("the derive should be rejected").to_string()
+    }
+
+    #[test]
+    fn fractional_bounds_on_integer_fields_are_rejected() {
+        let message = error(parse_quote! {
+            struct Order {
+                #[validate(min = 1.5)]
+                quantity: u32,
+            }
+        });
+        assert_eq!(message, "expected an integer bound for a field of type `u32`");
+
+        let message = error(parse_quote! {
+            struct Order {
+                #[validate(range(min = 0, max = 2.5))]
+                quantity: Option<i64>,
+            }
+        });
+        assert_eq!(message, "expected an integer bound for a field of type `i64`");
+    }
+
+    #[test]
+    fn negative_bounds_on_unsigned_fields_are_rejected() {
+        let message = error(parse_quote! {
+            struct Order {
+                #[validate(max = -1)]
+                quantity: usize,
+            }
+        });
+        assert_eq!(message, "expected a bound that is not negative for a field of type `usize`");
+    }
+
+    #[test]
+    fn integer_bounds_on_float_fields_are_typed_as_floats() {
+        let ty: Type = parse_quote!(Option<f64>);
+        let bound = typed_bound(&parse_quote!(-2), &ty).unwrap();
+        let expected = quote! { { let value: f64 = - 2.0; value } };
+        assert_eq!(bound.to_string(), expected.to_string());
+    }
 }
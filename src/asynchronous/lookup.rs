@@ -0,0 +1,152 @@
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures_timer::Delay;
+use thiserror::Error;
+
+use crate::asynchronous::AsyncRule;
+use crate::error::{ValidationError, Violation};
+use crate::rules::RuleInfo;
+
+/// Error raised by a [`Lookup`] backend, e.g. a failed database query
+#[derive(Debug, Clone, Error)]
+#[error("Lookup failed: {message}")]
+pub struct LookupError {
+    pub message: String,
+}
+
+impl LookupError {
+    pub fn new<M: Into<String>>(message: M) -> Self {
+        LookupError { message: message.into() }
+    }
+}
+
+/// A store that can tell whether a key exists, such as a table of usernames or coupon codes
+pub trait Lookup: Send + Sync {
+    fn exists<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>>;
+}
+
+impl<L: Lookup + ?Sized> Lookup for Arc<L> {
+    fn exists<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>> {
+        (**self).exists(key)
+    }
+}
+
+impl<L: Lookup + ?Sized> Lookup for Box<L> {
+    fn exists<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>> {
+        (**self).exists(key)
+    }
+}
+
+/// In-memory [`Lookup`] for tests and prototypes
+///
+/// An optional latency simulates a round trip to a real store.
+#[derive(Debug, Default)]
+pub struct InMemoryLookup {
+    keys: RwLock<HashSet<String>>,
+    latency: Option<Duration>,
+}
+
+impl InMemoryLookup {
+    /// Create an empty lookup
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every answer by a fixed duration
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Add a key
+    pub fn insert<K: Into<String>>(&self, key: K) {
+        self.keys.write().unwrap_or_else(|e| e.into_inner()).insert(key.into());
+    }
+
+    /// Remove a key, returning whether it was present
+    pub fn remove(&self, key: &str) -> bool {
+        self.keys.write().unwrap_or_else(|e| e.into_inner()).remove(key)
+    }
+
+    /// Check whether a key is present without waiting
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).contains(key)
+    }
+}
+
+impl<K: Into<String>> FromIterator<K> for InMemoryLookup {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        InMemoryLookup {
+            keys: RwLock::new(keys.into_iter().map(Into::into).collect()),
+            latency: None,
+        }
+    }
+}
+
+impl Lookup for InMemoryLookup {
+    fn exists<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>> {
+        Box::pin(async move {
+            if let Some(latency) = self.latency {
+                Delay::new(latency).await;
+            }
+            Ok(self.contains(key))
+        })
+    }
+}
+
+fn lookup_failed(err: LookupError) -> ValidationError {
+    Violation::new("lookup.failed", "Value could not be checked")
+        .with_param("reason", &err.message)
+        .into()
+}
+
+/// Validates that a value is not already present in a store, e.g. an unused username
+pub struct Available<L> {
+    pub lookup: L,
+}
+
+impl<T: AsRef<str> + ?Sized + Sync, L: Lookup> AsyncRule<T> for Available<L> {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        Box::pin(async move {
+            let value = value.as_ref();
+            match self.lookup.exists(value).await {
+                Ok(false) => Ok(()),
+                Ok(true) => Err(Violation::new("taken", "Value is already taken")
+                    .with_value(value)
+                    .into()),
+                Err(err) => Err(lookup_failed(err)),
+            }
+        })
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("available", "Value must not already be taken")
+    }
+}
+
+/// Validates that a value is present in a store, e.g. an existing coupon code
+pub struct Exists<L> {
+    pub lookup: L,
+}
+
+impl<T: AsRef<str> + ?Sized + Sync, L: Lookup> AsyncRule<T> for Exists<L> {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        Box::pin(async move {
+            let value = value.as_ref();
+            match self.lookup.exists(value).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(Violation::new("not_found", "Value does not exist")
+                    .with_value(value)
+                    .into()),
+                Err(err) => Err(lookup_failed(err)),
+            }
+        })
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("exists", "Value must exist")
+    }
+}
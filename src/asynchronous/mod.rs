@@ -0,0 +1,201 @@
+//! Asynchronous validation for rules that need I/O
+//!
+//! [`AsyncRule`] is the asynchronous counterpart of [`Rule`](crate::rules::Rule).
+//! An [`AsyncSchema`] field runs its synchronous built-ins first and only then
+//! the I/O-bound checks, so malformed values never reach the store. Futures only
+//! rely on the `futures` crate and timeouts on `futures-timer`, so any executor
+//! can drive them.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::{self, BoxFuture, Either};
+use futures_timer::Delay;
+
+use crate::error::{ValidationError, Violation};
+use crate::report::ValidationReport;
+use crate::rules::{short_type_name, RuleInfo};
+
+pub mod lookup;
+pub mod schema;
+
+pub use lookup::{Available, Exists, InMemoryLookup, Lookup, LookupError};
+pub use schema::AsyncSchema;
+
+/// Code reported when an async rule does not finish in time
+pub const TIMEOUT: &str = "timeout";
+
+/// Trait for rules that complete asynchronously, e.g. because they query a store
+pub trait AsyncRule<T: ?Sized>: Send + Sync {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>>;
+
+    /// What the rule checks, as for [`Rule::describe`](crate::rules::Rule::describe)
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new(short_type_name::<Self>(), "Custom asynchronous rule")
+    }
+}
+
+impl<T: ?Sized, R: AsyncRule<T> + ?Sized> AsyncRule<T> for Box<R> {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        (**self).validate_async(value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        (**self).describe()
+    }
+}
+
+impl<T: ?Sized, R: AsyncRule<T> + ?Sized> AsyncRule<T> for Arc<R> {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        (**self).validate_async(value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        (**self).describe()
+    }
+}
+
+/// Trait for types that can be validated asynchronously
+pub trait AsyncValidate: Sync {
+    /// Validate the value and return a Result
+    fn validate_async(&self) -> BoxFuture<'_, Result<(), ValidationError>>;
+
+    /// Validate the value and return every finding, including warnings
+    fn report_async(&self) -> BoxFuture<'_, ValidationReport> {
+        Box::pin(async move { ValidationReport::from_result(self.validate_async().await) })
+    }
+}
+
+/// Fails with the [`TIMEOUT`] code when the wrapped rule takes longer than `duration`
+pub struct Timeout<R> {
+    pub rule: R,
+    pub duration: Duration,
+}
+
+impl<R> Timeout<R> {
+    /// Limit the time a rule may take
+    pub fn new(rule: R, duration: Duration) -> Self {
+        Timeout { rule, duration }
+    }
+}
+
+impl<T: ?Sized + Sync, R: AsyncRule<T>> AsyncRule<T> for Timeout<R> {
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        Box::pin(async move {
+            match future::select(self.rule.validate_async(value), Delay::new(self.duration)).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(Violation::new(TIMEOUT, "Validation did not complete in time")
+                    .with_param("timeout_ms", &(self.duration.as_millis() as u64))
+                    .into()),
+            }
+        })
+    }
+
+    fn describe(&self) -> RuleInfo {
+        let timeout_ms = self.duration.as_millis() as u64;
+        RuleInfo::new("timeout", format!("Rule must complete within {} ms", timeout_ms))
+            .with_param("timeout_ms", &timeout_ms)
+            .with_rule(self.rule.describe())
+    }
+}
+
+/// Async validation rule using a closure over an owned copy of the value
+///
+/// ```
+/// # use futures::executor::block_on;
+/// # use rustvalidity::asynchronous::{AsyncCustom, AsyncRule};
+/// # use rustvalidity::error::ValidationError;
+/// let coupon = AsyncCustom::<_, str>::new(|code: String| async move {
+///     if code.starts_with("WELCOME") { Ok(()) } else { Err(ValidationError::new("Unknown coupon")) }
+/// });
+///
+/// assert!(block_on(coupon.validate_async("WELCOME10")).is_ok());
+/// assert!(block_on(coupon.validate_async("EXPIRED")).is_err());
+/// ```
+pub struct AsyncCustom<F, T: ?Sized> {
+    pub validator: F,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<F, T: ?Sized> AsyncCustom<F, T> {
+    /// Create a rule from a closure returning a future
+    pub fn new(validator: F) -> Self {
+        AsyncCustom {
+            validator,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F, Fut> AsyncRule<T> for AsyncCustom<F, T>
+where
+    T: ToOwned + ?Sized + Sync,
+    F: Fn(T::Owned) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<(), ValidationError>> + Send + 'static,
+{
+    fn validate_async<'a>(&'a self, value: &'a T) -> BoxFuture<'a, Result<(), ValidationError>> {
+        Box::pin((self.validator)(value.to_owned()))
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("custom", "Custom asynchronous check")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    #[test]
+    fn slow_rules_time_out() {
+        let slow = InMemoryLookup::new().with_latency(Duration::from_millis(200));
+        let rule = Timeout::new(Exists { lookup: slow }, Duration::from_millis(10));
+
+        let err = block_on(rule.validate_async("WELCOME10")).unwrap_err();
+        assert_eq!(err.code(), Some(TIMEOUT));
+        assert_eq!(err.leaves()[0].1.params["timeout_ms"], 10);
+        assert_eq!(AsyncRule::<str>::describe(&rule).rules[0].name, "exists");
+    }
+
+    #[test]
+    fn fast_rules_keep_their_result() {
+        let lookup: InMemoryLookup = ["WELCOME10"].into_iter().collect();
+        let rule = Timeout::new(Exists { lookup }, Duration::from_secs(5));
+
+        assert!(block_on(rule.validate_async("WELCOME10")).is_ok());
+        let err = block_on(rule.validate_async("EXPIRED")).unwrap_err();
+        assert_eq!(err.code(), Some("not_found"));
+    }
+
+    #[test]
+    fn lookups_tell_taken_values_apart() {
+        let lookup = Arc::new(InMemoryLookup::new());
+        lookup.insert("johndoe");
+        let available = Available { lookup: lookup.clone() };
+
+        assert!(block_on(available.validate_async("janedoe")).is_ok());
+        let err = block_on(available.validate_async("johndoe")).unwrap_err();
+        assert_eq!(err.code(), Some("taken"));
+
+        assert!(lookup.remove("johndoe"));
+        assert!(block_on(available.validate_async("johndoe")).is_ok());
+    }
+
+    struct Broken;
+
+    impl Lookup for Broken {
+        fn exists<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>> {
+            Box::pin(async { Err(LookupError::new("connection refused")) })
+        }
+    }
+
+    #[test]
+    fn lookup_failures_are_reported() {
+        let err = block_on(Exists { lookup: Broken }.validate_async("WELCOME10")).unwrap_err();
+        assert_eq!(err.code(), Some("lookup.failed"));
+        assert_eq!(err.leaves()[0].1.params["reason"], "connection refused");
+    }
+}
@@ -0,0 +1,353 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use futures::stream::{FuturesUnordered, StreamExt};
+
+use crate::asynchronous::{AsyncRule, AsyncValidate};
+use crate::context::{Clock, ContextData, SystemClock, ValidationContext};
+use crate::error::ValidationError;
+use crate::path::FieldPath;
+use crate::report::ValidationReport;
+use crate::rules::Rule;
+use crate::validator::ValidationMode;
+
+/// The check of a single field of `T`
+trait FieldCheck<T>: Send + Sync {
+    fn path(&self) -> &FieldPath;
+
+    /// Run the sync rules of the field, telling whether its async rules should run
+    fn check(&self, value: &T, ctx: &ValidationContext<'_>, stop: bool, report: &mut ValidationReport) -> bool;
+
+    /// Run the async rules of the field; with `stop` set, rules after the first error are skipped
+    fn check_async<'a>(&'a self, value: &'a T, stop: bool) -> BoxFuture<'a, ValidationReport>;
+}
+
+struct RuleField<A, F: ?Sized> {
+    path: FieldPath,
+    accessor: A,
+    rules: Vec<Box<dyn Rule<F>>>,
+    checks: Vec<Box<dyn AsyncRule<F>>>,
+}
+
+impl<T, F, A> FieldCheck<T> for RuleField<A, F>
+where
+    T: Sync,
+    F: ?Sized + Sync,
+    A: Fn(&T) -> &F + Send + Sync,
+{
+    fn path(&self) -> &FieldPath {
+        &self.path
+    }
+
+    fn check(&self, value: &T, ctx: &ValidationContext<'_>, stop: bool, report: &mut ValidationReport) -> bool {
+        let field = (self.accessor)(value);
+        let ctx = ctx.clone().with_path(self.path.clone());
+        for rule in &self.rules {
+            if let Err(err) = rule.validate_in(field, &ctx) {
+                let fatal = err.is_fatal();
+                report.record_at(&self.path, err);
+                if fatal && stop {
+                    return false;
+                }
+            }
+        }
+        !self.checks.is_empty()
+    }
+
+    fn check_async<'a>(&'a self, value: &'a T, stop: bool) -> BoxFuture<'a, ValidationReport> {
+        Box::pin(async move {
+            let field = (self.accessor)(value);
+            let mut report = ValidationReport::new();
+            for check in &self.checks {
+                if let Err(err) = check.validate_async(field).await {
+                    let fatal = err.is_fatal();
+                    report.record_at(&self.path, err);
+                    if fatal && stop {
+                        break;
+                    }
+                }
+            }
+            report
+        })
+    }
+}
+
+struct NestedField<A> {
+    path: FieldPath,
+    accessor: A,
+}
+
+impl<T, F, A> FieldCheck<T> for NestedField<A>
+where
+    T: Sync,
+    F: AsyncValidate + ?Sized,
+    A: Fn(&T) -> &F + Send + Sync,
+{
+    fn path(&self) -> &FieldPath {
+        &self.path
+    }
+
+    fn check(&self, _value: &T, _ctx: &ValidationContext<'_>, _stop: bool, _report: &mut ValidationReport) -> bool {
+        true
+    }
+
+    fn check_async<'a>(&'a self, value: &'a T, _stop: bool) -> BoxFuture<'a, ValidationReport> {
+        Box::pin(async move {
+            let nested = (self.accessor)(value).report_async().await;
+            let mut report = ValidationReport::new();
+            for (path, violation) in nested.findings().leaves() {
+                report.add(self.path.concat(&path), violation.clone());
+            }
+            report
+        })
+    }
+}
+
+/// Rules bound to the fields of a type that may complete asynchronously
+///
+/// The asynchronous counterpart of [`Schema`](crate::schema::Schema). The sync
+/// rules of every field run first, then the [`AsyncRule`]s of different fields
+/// run concurrently, each field's in declaration order. With fail-fast or bail a
+/// value rejected by a built-in never reaches the store, and in fail-fast mode
+/// the remaining checks are dropped as soon as one field reports an error.
+///
+/// Sync rules see the validated value as the root and parent in their
+/// [`ValidationContext`].
+pub struct AsyncSchema<T> {
+    mode: ValidationMode,
+    bail: bool,
+    data: ContextData,
+    clock: Arc<dyn Clock>,
+    fields: Vec<Box<dyn FieldCheck<T>>>,
+}
+
+impl<T: Sync + 'static> AsyncSchema<T> {
+    /// Create a schema without any field
+    pub fn new() -> Self {
+        AsyncSchema {
+            mode: ValidationMode::default(),
+            bail: false,
+            data: ContextData::new(),
+            clock: Arc::new(SystemClock),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set how far validation goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Stop checking a field after its first error while still checking the other fields
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.bail = bail;
+        self
+    }
+
+    /// Add data available to every sync rule through its context
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.data.insert(data);
+        self
+    }
+
+    /// Set the clock sync rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Bind sync rules to a field read through an accessor
+    pub fn field<P, F, A>(self, path: P, accessor: A, rules: Vec<Box<dyn Rule<F>>>) -> Self
+    where
+        P: Into<FieldPath>,
+        F: ?Sized + Sync + 'static,
+        A: Fn(&T) -> &F + Send + Sync + 'static,
+    {
+        self.field_async(path, accessor, rules, Vec::new())
+    }
+
+    /// Bind sync rules and async rules to a field read through an accessor
+    pub fn field_async<P, F, A>(
+        mut self,
+        path: P,
+        accessor: A,
+        rules: Vec<Box<dyn Rule<F>>>,
+        checks: Vec<Box<dyn AsyncRule<F>>>,
+    ) -> Self
+    where
+        P: Into<FieldPath>,
+        F: ?Sized + Sync + 'static,
+        A: Fn(&T) -> &F + Send + Sync + 'static,
+    {
+        self.fields.push(Box::new(RuleField {
+            path: path.into(),
+            accessor,
+            rules,
+            checks,
+        }));
+        self
+    }
+
+    /// Validate a nested value with its own [`AsyncValidate`] implementation, reporting below the field
+    pub fn nested<P, F, A>(mut self, path: P, accessor: A) -> Self
+    where
+        P: Into<FieldPath>,
+        F: AsyncValidate + ?Sized + 'static,
+        A: Fn(&T) -> &F + Send + Sync + 'static,
+    {
+        self.fields.push(Box::new(NestedField {
+            path: path.into(),
+            accessor,
+        }));
+        self
+    }
+
+    /// The paths of the bound fields in declaration order
+    pub fn fields(&self) -> Vec<&FieldPath> {
+        self.fields.iter().map(|field| field.path()).collect()
+    }
+
+    /// Validate a value in the schema's mode
+    pub async fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.report(value).await.into_result()
+    }
+
+    /// Validate a value and collect every error
+    pub async fn validate_all(&self, value: &T) -> Result<(), ValidationError> {
+        self.run(value, ValidationMode::CollectAll).await.into_result()
+    }
+
+    /// Validate a value in the schema's mode and return every finding, including warnings
+    pub async fn report(&self, value: &T) -> ValidationReport {
+        self.run(value, self.mode).await
+    }
+
+    async fn run(&self, value: &T, mode: ValidationMode) -> ValidationReport {
+        let fail_fast = mode == ValidationMode::FailFast;
+        let stop = fail_fast || self.bail;
+
+        // Sync rules run up front; the context is not shared with the futures
+        let mut report = ValidationReport::new();
+        let mut pending = FuturesUnordered::new();
+        {
+            let ctx = ValidationContext::new()
+                .with_root(value)
+                .with_parent(value)
+                .with_data(&self.data)
+                .with_clock(self.clock.as_ref());
+            for field in &self.fields {
+                let proceed = field.check(value, &ctx, stop, &mut report);
+                if fail_fast && !report.is_valid() {
+                    return report;
+                }
+                if proceed {
+                    pending.push(field.check_async(value, stop));
+                }
+            }
+        }
+
+        while let Some(field_report) = pending.next().await {
+            let failed = !field_report.is_valid();
+            report.merge(field_report);
+            if failed && fail_fast {
+                break;
+            }
+        }
+        report
+    }
+}
+
+impl<T: Sync + 'static> Default for AsyncSchema<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asynchronous::{Available, InMemoryLookup, Lookup, LookupError};
+    use crate::rules::common::{Length, Required};
+    use futures::executor::block_on;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Signup {
+        username: String,
+        nickname: String,
+    }
+
+    /// Counts the queries that reach the store
+    #[derive(Default)]
+    struct Counting {
+        keys: InMemoryLookup,
+        queries: AtomicUsize,
+    }
+
+    impl Lookup for Counting {
+        fn exists<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<bool, LookupError>> {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            self.keys.exists(key)
+        }
+    }
+
+    fn schema(store: &Arc<Counting>) -> AsyncSchema<Signup> {
+        AsyncSchema::new()
+            .field_async(
+                "username",
+                |s: &Signup| &s.username,
+                vec![Box::new(Required), Box::new(Length { min: 3, max: Some(20) })],
+                vec![Box::new(Available { lookup: store.clone() })],
+            )
+            .field_async(
+                "nickname",
+                |s: &Signup| &s.nickname,
+                vec![],
+                vec![Box::new(Available { lookup: store.clone() })],
+            )
+    }
+
+    fn signup(username: &str, nickname: &str) -> Signup {
+        Signup {
+            username: username.to_string(),
+            nickname: nickname.to_string(),
+        }
+    }
+
+    #[test]
+    fn sync_rules_guard_the_store() {
+        let store = Arc::new(Counting::default());
+        let schema = schema(&store).bail(true);
+
+        let err = block_on(schema.validate_all(&signup("jd", "jd"))).unwrap_err();
+        assert_eq!(err.field_violations("username")[0].code, "length.min");
+        // Only the nickname, which has no sync rule, was looked up
+        assert_eq!(store.queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fail_fast_stops_at_the_first_failing_field() {
+        let store = Arc::new(Counting::default());
+        let schema = schema(&store).with_mode(ValidationMode::FailFast);
+
+        let err = block_on(schema.validate(&signup("", "janedoe"))).unwrap_err();
+        assert_eq!(err.leaves().len(), 1);
+        assert_eq!(store.queries.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn async_errors_are_reported_below_their_field() {
+        let store = Arc::new(Counting::default());
+        store.keys.insert("johndoe");
+        let schema = schema(&store);
+
+        assert!(block_on(schema.validate(&signup("janedoe", "jane"))).is_ok());
+        let err = block_on(schema.validate_all(&signup("janedoe", "johndoe"))).unwrap_err();
+        let leaves = err.leaves();
+        assert_eq!(leaves.len(), 1);
+        assert_eq!(leaves[0].0.to_string(), "nickname");
+        assert_eq!(leaves[0].1.code, "taken");
+        assert_eq!(schema.fields().len(), 2);
+    }
+}
@@ -0,0 +1,394 @@
+//! Validate the records of data files against a rule set
+//!
+//! ```text
+//! rustvalidity --rules rules/order.yaml orders.ndjson exports/*.csv
+//! rustvalidity --rules rules/order.json --format json --max-errors 100 orders.json
+//! ```
+//!
+//! Each record is checked with the rules of the [`RuleSet`] and every failure
+//! is reported with the file, the record's line or position and the field. The
+//! exit code is 0 when every record is valid, 1 when a record is invalid and 2
+//! when a rule set or data file cannot be read.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use rustvalidity::error::{ErrorEntry, Severity, ValidationError, Violation};
+use rustvalidity::path::{FieldPath, PathSegment};
+use rustvalidity::report::ValidationReport;
+use rustvalidity::rule_set::RuleSet;
+use rustvalidity::schema::Schema;
+use rustvalidity::validator::ValidationMode;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// Validate JSON, NDJSON, YAML and CSV records against a rule set
+#[derive(Parser)]
+#[command(name = "rustvalidity", version)]
+struct Args {
+    /// Rule set file (.json, .yaml, .yml or .toml)
+    #[arg(short, long, value_name = "FILE")]
+    rules: PathBuf,
+
+    /// Data files to validate, in the format given by their extension; `-` reads standard input
+    #[arg(required = true, value_name = "DATA")]
+    files: Vec<PathBuf>,
+
+    /// Read every data file in this format instead of the one given by its extension
+    #[arg(short, long, value_enum, value_name = "FORMAT")]
+    input: Option<InputFormat>,
+
+    /// Report format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Only check the rules of a validation group
+    #[arg(short, long)]
+    group: Option<String>,
+
+    /// Stop at the first error
+    #[arg(long)]
+    fail_fast: bool,
+
+    /// Stop once this many errors were reported
+    #[arg(long, value_name = "N")]
+    max_errors: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// A single record, or an array of records
+    Json,
+    /// One JSON record per line
+    Ndjson,
+    /// One record, an array of records, or one record per document
+    Yaml,
+    /// One record per row, with field paths in the header row
+    Csv,
+}
+
+impl InputFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(InputFormat::Json),
+            "ndjson" | "jsonl" => Some(InputFormat::Ndjson),
+            "yaml" | "yml" => Some(InputFormat::Yaml),
+            "csv" => Some(InputFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// One line per finding, followed by a summary
+    Text,
+    /// A single JSON document
+    Json,
+}
+
+/// A record of a data file, or the reason it could not be parsed
+struct Record {
+    /// Where the record is in its file, e.g. `line 4` or `record 2`
+    location: String,
+    value: Result<Value, String>,
+}
+
+/// The findings of an invalid record, or of a file that could not be read
+#[derive(Serialize)]
+struct Outcome {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    record: Option<String>,
+    findings: Vec<ErrorEntry>,
+}
+
+#[derive(Default, Serialize)]
+struct Summary {
+    valid: bool,
+    files: usize,
+    records: usize,
+    invalid: usize,
+    errors: usize,
+    warnings: usize,
+    stopped: bool,
+    unreadable: usize,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let rule_set = match RuleSet::from_file(&args.rules) {
+        Ok(rule_set) => rule_set,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::from(2);
+        },
+    };
+    let mode = if args.fail_fast { ValidationMode::FailFast } else { ValidationMode::CollectAll };
+    let schema = match rule_set.schema() {
+        Ok(schema) => schema.with_mode(mode),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::from(2);
+        },
+    };
+    let max_errors = if args.fail_fast { Some(1) } else { args.max_errors };
+
+    let mut summary = Summary::default();
+    let mut outcomes = Vec::new();
+    'files: for path in &args.files {
+        let file = path.display().to_string();
+        summary.files += 1;
+        let records = match read_records(path, args.input, &rule_set) {
+            Ok(records) => records,
+            Err(message) => {
+                summary.unreadable += 1;
+                if args.format == OutputFormat::Text {
+                    eprintln!("error: {}: {}", file, message);
+                }
+                outcomes.push(Outcome {
+                    file,
+                    record: None,
+                    findings: vec![ErrorEntry::new(FieldPath::root(), Violation::new("unreadable", message))],
+                });
+                continue;
+            },
+        };
+
+        for record in records {
+            summary.records += 1;
+            let report = match &record.value {
+                Ok(value) => check(&schema, value, args.group.as_deref()),
+                Err(message) => ValidationReport::from(ValidationError::from(Violation::new("parse_error", message.clone()))),
+            };
+            if report.is_empty() {
+                continue;
+            }
+
+            let mut findings = report.entries();
+            if !report.is_valid() {
+                summary.invalid += 1;
+            }
+            if let Some(max) = max_errors {
+                // Keep the errors that fit within the limit, and the warnings among them
+                let mut budget = max - summary.errors;
+                findings.retain(|entry| {
+                    if !entry.violation.is_error() {
+                        return true;
+                    }
+                    let kept = budget > 0;
+                    budget = budget.saturating_sub(1);
+                    kept
+                });
+            }
+            summary.errors += findings.iter().filter(|entry| entry.violation.is_error()).count();
+            summary.warnings += findings.iter().filter(|entry| entry.violation.severity == Severity::Warning).count();
+
+            if args.format == OutputFormat::Text {
+                for entry in &findings {
+                    print_finding(&file, &record.location, entry);
+                }
+            }
+            outcomes.push(Outcome {
+                file: file.clone(),
+                record: Some(record.location),
+                findings,
+            });
+
+            if max_errors.is_some_and(|max| summary.errors >= max) {
+                summary.stopped = true;
+                break 'files;
+            }
+        }
+    }
+    summary.valid = summary.invalid == 0 && summary.unreadable == 0;
+
+    match args.format {
+        OutputFormat::Text => print_summary(&summary),
+        OutputFormat::Json => {
+            let document = json!({ "summary": summary, "results": outcomes });
+            println!("{}", serde_json::to_string_pretty(&document).unwrap_or_default());
+        },
+    }
+
+    if summary.unreadable > 0 {
+        ExitCode::from(2)
+    } else if summary.invalid > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check(schema: &Schema<Value>, value: &Value, group: Option<&str>) -> ValidationReport {
+    match group {
+        Some(group) => schema.report_group(value, group),
+        None => schema.report(value),
+    }
+}
+
+fn print_finding(file: &str, location: &str, entry: &ErrorEntry) {
+    let severity = match entry.violation.severity {
+        Severity::Error => String::new(),
+        severity => format!("{}: ", severity),
+    };
+    if entry.path.is_root() {
+        println!("{}: {}: {}{}", file, location, severity, entry.violation);
+    } else {
+        println!("{}: {}: {}: {}{}", file, location, entry.path, severity, entry.violation);
+    }
+}
+
+fn print_summary(summary: &Summary) {
+    let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+    let mut line = format!(
+        "Checked {} in {}: {} invalid, {}",
+        plural(summary.records, "record"),
+        plural(summary.files, "file"),
+        summary.invalid,
+        plural(summary.errors, "error"),
+    );
+    if summary.warnings > 0 {
+        line.push_str(&format!(", {}", plural(summary.warnings, "warning")));
+    }
+    if summary.unreadable > 0 {
+        line.push_str(&format!(", {} unreadable", plural(summary.unreadable, "file")));
+    }
+    println!("{}", line);
+    if summary.stopped {
+        println!("Stopped after {}", plural(summary.errors, "error"));
+    }
+}
+
+/// Open a data file, or standard input for `-`
+fn open(path: &Path) -> Result<Box<dyn Read>, String> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin()));
+    }
+    File::open(path)
+        .map(|file| Box::new(file) as Box<dyn Read>)
+        .map_err(|err| err.to_string())
+}
+
+/// Read the records of a data file; NDJSON and CSV files are read one record at a time
+fn read_records<'a>(
+    path: &Path,
+    format: Option<InputFormat>,
+    rule_set: &'a RuleSet,
+) -> Result<Box<dyn Iterator<Item = Record> + 'a>, String> {
+    let Some(format) = format.or_else(|| InputFormat::from_path(path)) else {
+        return Err("unknown data format, use --input".to_string());
+    };
+    let mut reader = open(path)?;
+
+    match format {
+        InputFormat::Json => {
+            let document: Value = serde_json::from_reader(reader).map_err(|err| err.to_string())?;
+            Ok(Box::new(split(document, "")))
+        },
+        InputFormat::Ndjson => {
+            let lines = BufReader::new(reader).lines().enumerate();
+            Ok(Box::new(lines.filter_map(|(index, line)| {
+                let value = match line {
+                    Ok(line) if line.trim().is_empty() => return None,
+                    Ok(line) => serde_json::from_str(&line).map_err(|err| format!("Invalid JSON: {}", err)),
+                    Err(err) => Err(err.to_string()),
+                };
+                Some(Record { location: format!("line {}", index + 1), value })
+            })))
+        },
+        InputFormat::Yaml => {
+            let mut content = String::new();
+            reader.read_to_string(&mut content).map_err(|err| err.to_string())?;
+            let mut documents = Vec::new();
+            for document in serde_yaml::Deserializer::from_str(&content) {
+                match Value::deserialize(document).map_err(|err| err.to_string())? {
+                    Value::Null => {},
+                    document => documents.push(document),
+                }
+            }
+            if documents.len() == 1 {
+                return Ok(Box::new(split(documents.remove(0), "")));
+            }
+            Ok(Box::new(documents.into_iter().enumerate().flat_map(|(index, document)| {
+                split(document, &format!("document {}", index + 1))
+            })))
+        },
+        InputFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(reader);
+            let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+            Ok(Box::new(reader.into_records().map(move |row| match row {
+                Ok(row) => Record {
+                    location: format!("line {}", row.position().map_or(0, |position| position.line())),
+                    value: Ok(csv_record(&headers, &row, rule_set)),
+                },
+                Err(err) => Record {
+                    location: format!("line {}", err.position().map_or(0, |position| position.line())),
+                    value: Err(err.to_string()),
+                },
+            })))
+        },
+    }
+}
+
+/// The records of a JSON or YAML document: the items of an array, or the document itself
+fn split(document: Value, location: &str) -> impl Iterator<Item = Record> {
+    let records: Vec<Record> = match document {
+        Value::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| Record {
+                location: match location {
+                    "" => format!("record {}", index + 1),
+                    location => format!("{}, record {}", location, index + 1),
+                },
+                value: Ok(value),
+            })
+            .collect(),
+        value => vec![Record {
+            location: match location {
+                "" => "document".to_string(),
+                location => location.to_string(),
+            },
+            value: Ok(value),
+        }],
+    };
+    records.into_iter()
+}
+
+/// Build a record from a CSV row, nesting columns whose header is a path of fields, e.g. `address.city`
+fn csv_record(headers: &csv::StringRecord, row: &csv::StringRecord, rule_set: &RuleSet) -> Value {
+    let mut record = Map::new();
+    for (header, cell) in headers.iter().zip(row.iter()) {
+        let value = rule_set.value_from_text(header, cell);
+        let names: Option<Vec<String>> = FieldPath::parse(header).ok().and_then(|path| {
+            path.segments()
+                .iter()
+                .map(|segment| match segment {
+                    PathSegment::Field(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect()
+        });
+        match names.as_deref() {
+            Some([parents @ .., name]) => {
+                let object = parents.iter().fold(&mut record, |object, parent| {
+                    let child = object.entry(parent.as_str()).or_insert_with(|| json!({}));
+                    if !child.is_object() {
+                        *child = json!({});
+                    }
+                    child.as_object_mut().expect("child is an object")
+                });
+                object.insert(name.clone(), value);
+            },
+            _ => {
+                record.insert(header.to_string(), value);
+            },
+        }
+    }
+    Value::Object(record)
+}
@@ -0,0 +1,325 @@
+//! Context available to rules while a value is validated
+//!
+//! A [`ValidationContext`] tells a rule where it is being applied: the root
+//! value, the struct holding the field, the field's path, data supplied by the
+//! caller and the clock to read the current time from. Rules receive it through
+//! [`Rule::validate_in`](crate::rules::Rule::validate_in).
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::path::{FieldPath, PathSegment};
+use crate::validator::DEFAULT_GROUP;
+
+/// Source of the current time, injectable so time-dependent rules can be tested
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock reading the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock stopped at a fixed instant
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+static SYSTEM_CLOCK: SystemClock = SystemClock;
+
+/// Data supplied by the caller, such as the current user, tenant or feature flags
+///
+/// Values are stored by type, so each type can be held once:
+///
+/// ```
+/// # use rustvalidity::context::{ContextData, ValidationContext};
+/// struct CurrentUser { id: u64, admin: bool }
+/// struct Tenant(String);
+///
+/// let data = ContextData::new().with(CurrentUser { id: 7, admin: false }).with(Tenant("acme".into()));
+/// assert_eq!(data.get::<Tenant>().map(|tenant| tenant.0.as_str()), Some("acme"));
+///
+/// // Rules read the data from the context a schema passes them
+/// let ctx = ValidationContext::new().with_data(&data);
+/// let user = ctx.data::<CurrentUser>().unwrap();
+/// assert_eq!((user.id, user.admin), (7, false));
+/// ```
+#[derive(Default)]
+pub struct ContextData {
+    values: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl ContextData {
+    /// Create an empty set of data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a value, replacing any previous value of the same type
+    pub fn insert<D: Any + Send + Sync>(&mut self, value: D) {
+        self.values.insert(TypeId::of::<D>(), Box::new(value));
+    }
+
+    /// Add a value and return the data, for chaining
+    pub fn with<D: Any + Send + Sync>(mut self, value: D) -> Self {
+        self.insert(value);
+        self
+    }
+
+    /// Get the value of a type, if one was added
+    pub fn get<D: Any>(&self) -> Option<&D> {
+        self.values.get(&TypeId::of::<D>()).and_then(|value| value.downcast_ref::<D>())
+    }
+
+    /// Check whether a value of a type was added
+    pub fn contains<D: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<D>())
+    }
+
+    /// Check whether no value was added
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl fmt::Debug for ContextData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextData").field("len", &self.values.len()).finish()
+    }
+}
+
+/// Where a rule is being applied
+///
+/// Contexts are built by validation runs and schemas. A rule called through
+/// [`Rule::validate`](crate::rules::Rule::validate) sees an empty context: no
+/// root, parent or data, the root path, the system clock and only the default
+/// group.
+#[derive(Clone)]
+pub struct ValidationContext<'a> {
+    root: Option<&'a dyn Any>,
+    parent: Option<&'a dyn Any>,
+    path: FieldPath,
+    data: Option<&'a ContextData>,
+    clock: &'a dyn Clock,
+    groups: Option<Arc<[String]>>,
+}
+
+impl<'a> ValidationContext<'a> {
+    /// Create an empty context
+    pub fn new() -> Self {
+        ValidationContext {
+            root: None,
+            parent: None,
+            path: FieldPath::root(),
+            data: None,
+            clock: &SYSTEM_CLOCK,
+            groups: None,
+        }
+    }
+
+    /// Set the value validation started from
+    pub fn with_root(mut self, root: &'a dyn Any) -> Self {
+        self.root = Some(root);
+        self
+    }
+
+    /// Set the value holding the field being checked
+    pub fn with_parent(mut self, parent: &'a dyn Any) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Set the path of the field being checked
+    pub fn with_path<P: Into<FieldPath>>(mut self, path: P) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set the data supplied by the caller
+    pub fn with_data(mut self, data: &'a ContextData) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the validation groups being checked, including the groups they inherit
+    pub fn with_groups<I, S>(mut self, groups: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.groups = Some(groups.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Share the groups resolved by a validation run
+    pub(crate) fn with_group_set(mut self, groups: Arc<[String]>) -> Self {
+        self.groups = Some(groups);
+        self
+    }
+
+    /// The context of an element below the current path, e.g. an item checked by [`Each`](crate::rules::collection::Each)
+    pub fn at<S: Into<PathSegment>>(&self, segment: S) -> Self {
+        ValidationContext {
+            path: self.path.join(segment),
+            ..self.clone()
+        }
+    }
+
+    /// The value validation started from, if it is an `R`
+    pub fn root<R: Any>(&self) -> Option<&'a R> {
+        self.root.and_then(|root| root.downcast_ref::<R>())
+    }
+
+    /// The value holding the field being checked, if it is a `P`
+    pub fn parent<P: Any>(&self) -> Option<&'a P> {
+        self.parent.and_then(|parent| parent.downcast_ref::<P>())
+    }
+
+    /// The path of the value being checked
+    pub fn path(&self) -> &FieldPath {
+        &self.path
+    }
+
+    /// Data of a type supplied by the caller
+    pub fn data<D: Any>(&self) -> Option<&'a D> {
+        self.data.and_then(|data| data.get::<D>())
+    }
+
+    /// The clock of the validation
+    pub fn clock(&self) -> &'a dyn Clock {
+        self.clock
+    }
+
+    /// The current time according to the clock
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Check whether a validation group is being checked
+    pub fn in_group(&self, group: &str) -> bool {
+        match &self.groups {
+            Some(groups) => groups.iter().any(|active| active == group),
+            None => group == DEFAULT_GROUP,
+        }
+    }
+
+    /// Check whether a rule belonging to some groups applies; `None` stands for the default group
+    pub fn selects(&self, groups: Option<&[String]>) -> bool {
+        match groups {
+            Some(groups) => groups.iter().any(|group| self.in_group(group)),
+            None => self.in_group(DEFAULT_GROUP),
+        }
+    }
+}
+
+impl Default for ValidationContext<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ValidationContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationContext")
+            .field("path", &self.path)
+            .field("has_root", &self.root.is_some())
+            .field("has_parent", &self.parent.is_some())
+            .field("data", &self.data)
+            .field("groups", &self.groups)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct Order {
+        id: u32,
+    }
+
+    #[test]
+    fn data_is_stored_by_type() {
+        let mut data = ContextData::new();
+        assert!(data.is_empty());
+        data.insert(7u32);
+        data.insert(8u32);
+        data.insert("acme");
+
+        assert_eq!(data.get::<u32>(), Some(&8));
+        assert_eq!(data.get::<&str>(), Some(&"acme"));
+        assert!(data.contains::<u32>());
+        assert!(!data.contains::<u64>());
+        assert_eq!(data.get::<u64>(), None);
+    }
+
+    #[test]
+    fn empty_contexts_see_nothing() {
+        let ctx = ValidationContext::new();
+        assert!(ctx.root::<Order>().is_none());
+        assert!(ctx.parent::<Order>().is_none());
+        assert!(ctx.data::<u32>().is_none());
+        assert!(ctx.path().is_root());
+        assert!(ctx.in_group(DEFAULT_GROUP));
+        assert!(ctx.selects(None));
+        assert!(!ctx.selects(Some(&["admin".to_string()])));
+    }
+
+    #[test]
+    fn values_are_downcast_to_their_type() {
+        let order = Order { id: 1 };
+        let data = ContextData::new().with(42u32);
+        let ctx = ValidationContext::new().with_root(&order).with_parent(&order).with_data(&data);
+
+        assert_eq!(ctx.root::<Order>().map(|o| o.id), Some(1));
+        assert_eq!(ctx.parent::<Order>().map(|o| o.id), Some(1));
+        assert!(ctx.parent::<String>().is_none());
+        assert_eq!(ctx.data::<u32>(), Some(&42));
+    }
+
+    #[test]
+    fn elements_extend_the_path() {
+        let order = Order { id: 1 };
+        let ctx = ValidationContext::new().with_parent(&order).with_path("lines");
+        let item = ctx.at(2).at("sku");
+
+        assert_eq!(item.path().to_string(), "lines[2].sku");
+        assert_eq!(ctx.path().to_string(), "lines");
+        assert_eq!(item.parent::<Order>().map(|o| o.id), Some(1));
+    }
+
+    #[test]
+    fn clocks_and_groups_are_injected() {
+        let noon = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        let clock = FixedClock(noon);
+        let ctx = ValidationContext::new().with_clock(&clock).with_groups(["create", DEFAULT_GROUP]);
+
+        assert_eq!(ctx.now(), noon);
+        assert_eq!(ctx.clock().now(), noon);
+        assert!(ctx.in_group("create"));
+        assert!(!ctx.in_group("update"));
+        assert!(ctx.selects(Some(&["update".to_string(), "create".to_string()])));
+    }
+}
@@ -0,0 +1,625 @@
+//! Validation of untyped data by path
+//!
+//! A [`DynamicSchema`] attaches rules to paths into JSON data, so payloads
+//! handled as [`serde_json::Value`] or `HashMap<String, Value>` can be checked
+//! without defining a struct for each of them:
+//!
+//! ```
+//! # use rustvalidity::DynamicSchema;
+//! # use rustvalidity::rules::collection::{Each, MinSize};
+//! # use rustvalidity::rules::common::{Email, Length};
+//! # use rustvalidity::rules::numeric::Range;
+//! # use rustvalidity::validator::ValidationMode;
+//! # use serde_json::{json, Value};
+//! let schema = DynamicSchema::new()
+//!     .with_mode(ValidationMode::CollectAll)
+//!     .required("customer.email")
+//!     .field::<String>("customer.email", vec![Box::new(Email { check_dns: false })])
+//!     .field::<Vec<Value>>("items", vec![Box::new(MinSize { min: 1 })])
+//!     .field::<f64>("items[*].price", vec![Box::new(Range { min: 0.0, max: 10_000.0 })])
+//!     .field::<Vec<String>>("items[*].tags", vec![Box::new(Each { rule: Box::new(Length { min: 1, max: Some(20) }) })]);
+//!
+//! let payload = json!({
+//!     "customer": { "email": "ada" },
+//!     "items": [{ "price": "9.99", "tags": ["new"] }, { "price": -1, "tags": [""] }],
+//! });
+//! let err = schema.validate(&payload).unwrap_err();
+//! let paths: Vec<String> = err.entries().iter().map(|entry| entry.path.to_string()).collect();
+//! assert_eq!(paths, ["customer.email", "items[1].price", "items[1].tags[0]"]);
+//! ```
+//!
+//! Values are converted with [`FromValue`] to the type the rules are written
+//! for, so a `Range<f64>` sees a number even when the payload holds `"9.99"`.
+//! A value that cannot be converted is reported with the `invalid_type` code
+//! at its path, and its rules do not run.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use crate::context::{Clock, ContextData};
+use crate::error::{ErrorTree, ValidationError};
+use crate::path::{FieldPath, PathPattern, PathSegment, PatternSegment};
+use crate::report::ValidationReport;
+use crate::rules::common::Required;
+use crate::rules::{invalid_type, Rule, RuleInfo};
+use crate::validator::{ValidationMode, ValidationRun, Validator};
+
+/// Conversion of a JSON value to the type a rule is written for
+///
+/// Conversions are lenient where payloads commonly differ from the expected
+/// type: numbers and booleans are read from strings holding them, integral
+/// floats such as `3.0` convert to integers and scalars convert to strings.
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, ValidationError>;
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            Value::Number(n) => Ok(n.to_string()),
+            Value::Bool(b) => Ok(b.to_string()),
+            _ => Err(invalid_type("Value must be a string", "string")),
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            Value::String(s) if s == "true" => Ok(true),
+            Value::String(s) if s == "false" => Ok(false),
+            _ => Err(invalid_type("Value must be a boolean", "boolean")),
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        let number = match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+            _ => None,
+        };
+        number.ok_or_else(|| invalid_type("Value is not a number", "number"))
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        f64::from_value(value).map(|n| n as f32)
+    }
+}
+
+macro_rules! impl_from_value_for_integer {
+    ($($t:ty),*) => {
+        $(
+            impl FromValue for $t {
+                fn from_value(value: &Value) -> Result<Self, ValidationError> {
+                    // Read as the widest integer first, so out of range values are told apart from non-integers
+                    let number = match value {
+                        Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                            (Some(n), _, _) => Some(i128::from(n)),
+                            (_, Some(n), _) => Some(i128::from(n)),
+                            (_, _, Some(n)) if n.fract() == 0.0 && n.abs() < 1e38 => Some(n as i128),
+                            _ => None,
+                        },
+                        Value::String(s) => s.trim().parse::<i128>().ok(),
+                        _ => None,
+                    };
+                    let number = number.ok_or_else(|| invalid_type("Value is not an integer", "integer"))?;
+                    <$t>::try_from(number).map_err(|_| {
+                        let message = format!("Value must be between {} and {}", <$t>::MIN, <$t>::MAX);
+                        invalid_type(&message, "integer")
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value_for_integer!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        let Value::Array(items) = value else {
+            return Err(invalid_type("Value must be an array", "array"));
+        };
+        let mut errors = ErrorTree::new();
+        let mut converted = Vec::with_capacity(items.len());
+        for (i, item) in items.iter().enumerate() {
+            match T::from_value(item) {
+                Ok(item) => converted.push(item),
+                Err(err) => errors.add_error(FieldPath::from(i), err),
+            }
+        }
+        if errors.is_empty() {
+            Ok(converted)
+        } else {
+            Err(errors.into())
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: &Value) -> Result<Self, ValidationError> {
+        let Value::Object(members) = value else {
+            return Err(invalid_type("Value must be an object", "object"));
+        };
+        let mut errors = ErrorTree::new();
+        let mut converted = HashMap::with_capacity(members.len());
+        for (key, member) in members {
+            match T::from_value(member) {
+                Ok(member) => {
+                    converted.insert(key.clone(), member);
+                },
+                Err(err) => errors.add_error(FieldPath::root().key(key.as_str()), err),
+            }
+        }
+        if errors.is_empty() {
+            Ok(converted)
+        } else {
+            Err(errors.into())
+        }
+    }
+}
+
+/// Untyped data whose members can be looked up by path
+///
+/// Implemented for JSON values and for maps of JSON values, the usual shapes
+/// of decoded payloads.
+pub trait Document: Send + Sync + 'static {
+    /// The member with a name, if the document is an object holding it
+    fn member(&self, name: &str) -> Option<&Value>;
+
+    /// The element at an index, if the document is an array holding it
+    fn element(&self, index: usize) -> Option<&Value>;
+
+    /// Every member or element, or `None` if the document is neither an object nor an array
+    fn children(&self) -> Option<Vec<(PathSegment, &Value)>>;
+
+    /// The document as a single JSON value, if it is one
+    fn as_value(&self) -> Option<&Value> {
+        None
+    }
+}
+
+impl Document for Value {
+    fn member(&self, name: &str) -> Option<&Value> {
+        self.as_object()?.get(name)
+    }
+
+    fn element(&self, index: usize) -> Option<&Value> {
+        self.as_array()?.get(index)
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Value)>> {
+        match self {
+            Value::Object(members) => members.children(),
+            Value::Array(items) => Some(items.iter().enumerate().map(|(i, item)| (PathSegment::Index(i), item)).collect()),
+            _ => None,
+        }
+    }
+
+    fn as_value(&self) -> Option<&Value> {
+        Some(self)
+    }
+}
+
+impl Document for Map<String, Value> {
+    fn member(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+
+    fn element(&self, _index: usize) -> Option<&Value> {
+        None
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Value)>> {
+        Some(self.iter().map(|(name, value)| (PathSegment::Field(name.clone()), value)).collect())
+    }
+}
+
+impl Document for HashMap<String, Value> {
+    fn member(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+
+    fn element(&self, _index: usize) -> Option<&Value> {
+        None
+    }
+
+    /// Members are ordered by name, so errors are reported in a stable order
+    fn children(&self) -> Option<Vec<(PathSegment, &Value)>> {
+        let members: BTreeMap<&String, &Value> = self.iter().collect();
+        Some(members.into_iter().map(|(name, value)| (PathSegment::Field(name.clone()), value)).collect())
+    }
+}
+
+impl Document for BTreeMap<String, Value> {
+    fn member(&self, name: &str) -> Option<&Value> {
+        self.get(name)
+    }
+
+    fn element(&self, _index: usize) -> Option<&Value> {
+        None
+    }
+
+    fn children(&self) -> Option<Vec<(PathSegment, &Value)>> {
+        Some(self.iter().map(|(name, value)| (PathSegment::Field(name.clone()), value)).collect())
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// A value selected by a pattern, or the wildcard that could not be expanded
+enum Selected<'v> {
+    Value(FieldPath, &'v Value),
+    NotAContainer(FieldPath, &'static str),
+}
+
+/// Select the values matched by a pattern
+///
+/// Missing members are selected as null, so rules on values see them.
+/// Wildcards expand to the members present; applied to null they select
+/// nothing, applied to anything else but a container they are a type error.
+fn select<'v, D: Document + ?Sized>(document: &'v D, pattern: &[PatternSegment], path: FieldPath, out: &mut Vec<Selected<'v>>) {
+    let Some((segment, rest)) = pattern.split_first() else {
+        return;
+    };
+    let next = |path: FieldPath, value: &'v Value, out: &mut Vec<Selected<'v>>| {
+        if rest.is_empty() {
+            out.push(Selected::Value(path, value));
+        } else {
+            select(value, rest, path, out);
+        }
+    };
+    match segment {
+        PatternSegment::Exact(segment) => {
+            let value = match segment {
+                PathSegment::Field(name) | PathSegment::Key(name) => document.member(name),
+                PathSegment::Index(index) => document.element(*index),
+            };
+            next(path.join(segment.clone()), value.unwrap_or(&NULL), out);
+        },
+        PatternSegment::AnyIndex | PatternSegment::AnyField => {
+            let expected = match segment {
+                PatternSegment::AnyIndex => "array",
+                _ => "object",
+            };
+            let children = match document.children() {
+                Some(children) => children,
+                None if document.as_value().is_some_and(Value::is_null) => return,
+                None => return out.push(Selected::NotAContainer(path, expected)),
+            };
+            for (child, value) in children {
+                let selected = matches!(
+                    (segment, &child),
+                    (PatternSegment::AnyIndex, PathSegment::Index(_)) | (PatternSegment::AnyField, PathSegment::Field(_))
+                );
+                if !selected {
+                    return out.push(Selected::NotAContainer(path, expected));
+                }
+                next(path.join(child), value, out);
+            }
+        },
+    }
+}
+
+/// Checks a selected value; values of the wrong type are reported once per path across all checks
+type Check = Box<dyn for<'v> Fn(FieldPath, &'v Value, &mut ValidationRun<'v>, &mut HashSet<FieldPath>) + Send + Sync>;
+
+/// Rules bound to paths into untyped data
+///
+/// Paths may contain wildcards (see [`PathPattern`]); every rule runs once for
+/// each value its pattern selects and failures are reported at the concrete
+/// path, e.g. `items[2].price`. Like [`Schema`](crate::schema::Schema), a
+/// dynamic schema is built once, runs in a [`ValidationMode`] and supports
+/// validation groups, data and clocks.
+pub struct DynamicSchema {
+    validator: Validator,
+    checks: Vec<(PathPattern, Vec<RuleInfo>, Check)>,
+}
+
+impl DynamicSchema {
+    /// Create a schema without any rule
+    pub fn new() -> Self {
+        DynamicSchema {
+            validator: Validator::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Set how far validation goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.validator = self.validator.with_mode(mode);
+        self
+    }
+
+    /// Stop checking a path after its first error while still checking the other paths
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.validator = self.validator.bail(bail);
+        self
+    }
+
+    /// Add data available to every rule through its context
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.validator = self.validator.with_data(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.validator = self.validator.with_clock(clock);
+        self
+    }
+
+    /// Declare a validation group with the groups it inherits instead of the default group
+    pub fn with_group<I, S>(mut self, group: &str, parents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.validator = self.validator.with_group(group, parents);
+        self
+    }
+
+    /// Require the values selected by a pattern to be present: not missing, null or empty
+    pub fn required(self, pattern: impl Into<PathPattern>) -> Self {
+        self.value(pattern, vec![Box::new(Required)])
+    }
+
+    /// Bind rules on JSON values to a pattern
+    ///
+    /// The rules see the values as they are, with missing members as null.
+    /// A pattern given as a string panics if it does not parse; parse patterns
+    /// that come from input with [`PathPattern::parse`] first.
+    pub fn value(mut self, pattern: impl Into<PathPattern>, rules: Vec<Box<dyn Rule<Value>>>) -> Self {
+        self.checks.push((
+            pattern.into(),
+            rules.iter().map(|rule| rule.describe()).collect(),
+            Box::new(move |path, value, run, _| {
+                run.rules(path, value, &rules);
+            }),
+        ));
+        self
+    }
+
+    /// Bind rules on a type to a pattern, converting the selected values with [`FromValue`]
+    ///
+    /// Missing and null values are skipped, so the rules only check values
+    /// that are present; combine with [`DynamicSchema::required`] for values
+    /// that must be.
+    ///
+    /// The pattern is taken as `impl Into<PathPattern>` so the type can be
+    /// given alone, as in `field::<f64>("items[*].price", rules)`.
+    pub fn field<T: FromValue + 'static>(mut self, pattern: impl Into<PathPattern>, rules: Vec<Box<dyn Rule<T>>>) -> Self {
+        self.checks.push((
+            pattern.into(),
+            rules.iter().map(|rule| rule.describe()).collect(),
+            Box::new(move |path, value, run, mistyped| {
+                if value.is_null() {
+                    return;
+                }
+                match T::from_value(value) {
+                    Ok(value) => {
+                        run.rules(path, &value, &rules);
+                    },
+                    Err(err) => {
+                        if mistyped.insert(path.clone()) {
+                            run.result(path, Err(err));
+                        }
+                    },
+                }
+            }),
+        ));
+        self
+    }
+
+    /// The patterns rules are bound to, in declaration order
+    pub fn patterns(&self) -> Vec<&PathPattern> {
+        self.checks.iter().map(|(pattern, _, _)| pattern).collect()
+    }
+
+    /// Describe the rules bound to each pattern, in declaration order
+    pub fn describe(&self) -> Vec<(&PathPattern, &[RuleInfo])> {
+        self.checks.iter().map(|(pattern, rules, _)| (pattern, rules.as_slice())).collect()
+    }
+
+    /// Check a document as part of a validation run
+    pub fn apply<'v, D: Document>(&self, document: &'v D, run: &mut ValidationRun<'v>) {
+        let mut mistyped = HashSet::new();
+        run.scope(document, |run| {
+            for (pattern, _, check) in &self.checks {
+                let mut selected = Vec::new();
+                match pattern.segments() {
+                    [] => {
+                        if let Some(value) = document.as_value() {
+                            selected.push(Selected::Value(FieldPath::root(), value));
+                        }
+                    },
+                    segments => select(document, segments, FieldPath::root(), &mut selected),
+                }
+                for selected in selected {
+                    if run.is_stopped() {
+                        return;
+                    }
+                    match selected {
+                        Selected::Value(path, value) => check(path, value, run, &mut mistyped),
+                        Selected::NotAContainer(path, expected) if mistyped.insert(path.clone()) => {
+                            let message = format!("Value must be an {}", expected);
+                            run.result(path, Err(invalid_type(&message, expected)));
+                        },
+                        Selected::NotAContainer(..) => {},
+                    }
+                }
+            }
+        });
+    }
+
+    /// Validate a document in the schema's mode
+    pub fn validate<D: Document>(&self, document: &D) -> Result<(), ValidationError> {
+        self.report(document).into_result()
+    }
+
+    /// Validate a document and collect every error
+    pub fn validate_all<D: Document>(&self, document: &D) -> Result<(), ValidationError> {
+        let mut run = self.validator.start_with(ValidationMode::CollectAll);
+        self.apply(document, &mut run);
+        run.into_result()
+    }
+
+    /// Validate a document in the schema's mode with data supplied for this call, e.g. the current user
+    pub fn validate_with_data<D: Document>(&self, document: &D, data: &ContextData) -> Result<(), ValidationError> {
+        let mut run = self.validator.start().with_data(data);
+        self.apply(document, &mut run);
+        run.into_result()
+    }
+
+    /// Validate a document against the rules of a group, e.g. `"create"`, in the schema's mode
+    pub fn validate_group<D: Document>(&self, document: &D, group: &str) -> Result<(), ValidationError> {
+        self.report_group(document, group).into_result()
+    }
+
+    /// Validate a document against the rules of a group and return every finding, including warnings
+    pub fn report_group<D: Document>(&self, document: &D, group: &str) -> ValidationReport {
+        let mut run = self.validator.start().with_group(group);
+        self.apply(document, &mut run);
+        run.finish()
+    }
+
+    /// Validate a document in the schema's mode and return every finding, including warnings
+    pub fn report<D: Document>(&self, document: &D) -> ValidationReport {
+        let mut run = self.validator.start();
+        self.apply(document, &mut run);
+        run.finish()
+    }
+}
+
+impl Default for DynamicSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A dynamic schema checks JSON fields of typed values, reporting below the field
+impl Rule<Value> for DynamicSchema {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        DynamicSchema::validate(self, value)
+    }
+
+    /// Lists the bound rules, each with a `path` parameter holding its pattern
+    fn describe(&self) -> RuleInfo {
+        let mut info = RuleInfo::new("dynamic_schema", "Value must satisfy the rules bound to its paths");
+        for (pattern, rules, _) in &self.checks {
+            for rule in rules {
+                info = info.with_rule(rule.clone().with_param("path", &pattern.to_string()));
+            }
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::common::Length;
+    use crate::rules::numeric::{Min, Range};
+    use serde_json::json;
+
+    fn paths(result: Result<(), ValidationError>) -> Vec<String> {
+        match result {
+            Ok(()) => Vec::new(),
+            Err(err) => err.entries().iter().map(|entry| entry.path.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn wildcards_select_every_element_and_member() {
+        let schema = DynamicSchema::new()
+            .field::<i64>("orders[*].lines[*].quantity", vec![Box::new(Min { value: 1 })])
+            .field::<String>("labels.*", vec![Box::new(Length { min: 1, max: None })]);
+        let payload = json!({
+            "orders": [
+                { "lines": [{ "quantity": 1 }, { "quantity": 0 }] },
+                { "lines": [] },
+                { "lines": [{ "quantity": -2 }] },
+            ],
+            "labels": { "en": "Shoes", "fr": "" },
+        });
+        assert_eq!(
+            paths(schema.validate_all(&payload)),
+            ["labels.fr", "orders[0].lines[1].quantity", "orders[2].lines[0].quantity"]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid field path")]
+    fn malformed_patterns_are_not_taken_as_field_names() {
+        let _ = DynamicSchema::new().value("users[*.email", vec![Box::new(Required)]);
+    }
+
+    #[test]
+    fn wildcards_over_null_select_nothing() {
+        let schema = DynamicSchema::new().field::<i64>("items[*]", vec![Box::new(Min { value: 1 })]);
+        assert!(schema.validate_all(&json!({ "items": null })).is_ok());
+        assert!(schema.validate_all(&json!({})).is_ok());
+    }
+
+    #[test]
+    fn wildcards_over_scalars_are_reported_once() {
+        let schema = DynamicSchema::new()
+            .field::<i64>("items[*]", vec![Box::new(Min { value: 1 })])
+            .field::<i64>("items[*]", vec![Box::new(Range { min: 1, max: 9 })]);
+        let err = schema.validate_all(&json!({ "items": 3 })).unwrap_err();
+        let entries = err.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path.to_string(), "items");
+        assert_eq!(entries[0].violation.code, crate::rules::INVALID_TYPE);
+    }
+
+    #[test]
+    fn members_of_maps_are_found_by_name() {
+        let schema = DynamicSchema::new().required("name").field::<u8>("age", vec![Box::new(Range { min: 18, max: 99 })]);
+        let document = HashMap::from([("age".to_string(), json!("17"))]);
+        assert_eq!(paths(schema.validate_all(&document)), ["age", "name"]);
+    }
+
+    #[test]
+    fn values_are_converted_leniently() {
+        assert_eq!(i32::from_value(&json!("42")).unwrap(), 42);
+        assert_eq!(i32::from_value(&json!(3.0)).unwrap(), 3);
+        assert!(i32::from_value(&json!(3.5)).is_err());
+        assert!(u8::from_value(&json!(300)).is_err());
+        assert_eq!(f64::from_value(&json!(" 9.99 ")).unwrap(), 9.99);
+        assert!(f64::from_value(&json!("NaN")).is_err());
+        assert!(bool::from_value(&json!("true")).unwrap());
+        assert_eq!(String::from_value(&json!(7)).unwrap(), "7");
+        assert_eq!(Option::<i32>::from_value(&Value::Null).unwrap(), None);
+    }
+
+    #[test]
+    fn conversion_errors_are_reported_at_the_element() {
+        let err = Vec::<i32>::from_value(&json!([1, "two", 3, {}])).unwrap_err();
+        let paths: Vec<String> = err.entries().iter().map(|entry| entry.path.to_string()).collect();
+        assert_eq!(paths, ["[1]", "[3]"]);
+    }
+}
@@ -1,68 +1,505 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt;
-use thiserror::Error;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::path::{FieldPath, PathSegment};
+
+/// Code used for violations created from a bare message
+pub const CUSTOM_CODE: &str = "custom";
+
+/// How serious a violation is
+///
+/// Only `Error` violations make validation fail; `Warning` and `Info` are
+/// advisory findings reported alongside the outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A hard failure
+    #[default]
+    Error,
+
+    /// A non-fatal problem, e.g. a weak but acceptable password
+    Warning,
+
+    /// Purely informational, e.g. use of a deprecated field
+    Info,
+}
+
+impl Severity {
+    /// Check whether the severity makes validation fail
+    pub fn is_error(&self) -> bool {
+        *self == Severity::Error
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+/// A single rule violation with a stable, machine-readable code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Machine-readable code such as `length.min` or `email.format`
+    pub code: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Parameters of the rule that produced the violation (min, max, pattern, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, Value>,
+
+    /// The offending value, if the rule chose to report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+
+    /// Severity of the violation; omitted on the wire for errors
+    #[serde(default, skip_serializing_if = "Severity::is_error")]
+    pub severity: Severity,
+}
+
+impl Violation {
+    /// Create a new violation with a code and a message
+    pub fn new<C: Into<String>, M: Into<String>>(code: C, message: M) -> Self {
+        Violation {
+            code: code.into(),
+            message: message.into(),
+            params: BTreeMap::new(),
+            value: None,
+            severity: Severity::Error,
+        }
+    }
+
+    /// Create a new non-fatal violation
+    pub fn warning<C: Into<String>, M: Into<String>>(code: C, message: M) -> Self {
+        Violation::new(code, message).with_severity(Severity::Warning)
+    }
+
+    /// Set the severity of the violation
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Check whether the violation makes validation fail
+    pub fn is_error(&self) -> bool {
+        self.severity.is_error()
+    }
+
+    /// Attach a rule parameter to the violation
+    pub fn with_param<K: Into<String>, V: Serialize + ?Sized>(mut self, name: K, value: &V) -> Self {
+        self.params.insert(name.into(), to_value(value));
+        self
+    }
+
+    /// Attach the offending value to the violation
+    pub fn with_value<V: Serialize + ?Sized>(mut self, value: &V) -> Self {
+        self.value = Some(to_value(value));
+        self
+    }
+
+    /// Get a rule parameter by name
+    pub fn param(&self, name: &str) -> Option<&Value> {
+        self.params.get(name)
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+fn to_value<V: Serialize + ?Sized>(value: &V) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+/// Violations arranged by the path of the value they belong to
+///
+/// Violations attached to the root of the tree belong to the value that was
+/// validated as a whole; children are addressed by field name, sequence index
+/// or map key. Children are kept in a deterministic order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorTree {
+    violations: Vec<Violation>,
+    children: BTreeMap<PathSegment, ErrorTree>,
+}
+
+impl ErrorTree {
+    /// Create an empty tree
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether the tree holds no violations at all
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty() && self.children.values().all(ErrorTree::is_empty)
+    }
+
+    /// Count the violations in the whole tree
+    pub fn len(&self) -> usize {
+        self.violations.len() + self.children.values().map(ErrorTree::len).sum::<usize>()
+    }
+
+    /// Violations attached directly to this node
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Child nodes keyed by path segment
+    pub fn children(&self) -> &BTreeMap<PathSegment, ErrorTree> {
+        &self.children
+    }
+
+    /// Get the subtree at a path
+    pub fn get<P: Into<FieldPath>>(&self, path: P) -> Option<&ErrorTree> {
+        let path = path.into();
+        let mut node = self;
+        for segment in path.segments() {
+            node = node.children.get(segment)?;
+        }
+        Some(node)
+    }
+
+    fn node_mut(&mut self, path: &FieldPath) -> &mut ErrorTree {
+        let mut node = self;
+        for segment in path.segments() {
+            node = node.children.entry(segment.clone()).or_default();
+        }
+        node
+    }
+
+    /// Add a violation at a path
+    pub fn add<P: Into<FieldPath>>(&mut self, path: P, violation: Violation) {
+        self.node_mut(&path.into()).violations.push(violation);
+    }
+
+    /// Add every violation of a validation error below a path
+    pub fn add_error<P: Into<FieldPath>>(&mut self, path: P, error: ValidationError) {
+        self.merge_at(path, error.into_tree());
+    }
+
+    /// Merge another tree into this one, keeping the structure of both
+    pub fn merge(&mut self, other: ErrorTree) {
+        self.violations.extend(other.violations);
+        for (segment, child) in other.children {
+            self.children.entry(segment).or_default().merge(child);
+        }
+    }
+
+    /// Merge another tree into this one below a path
+    pub fn merge_at<P: Into<FieldPath>>(&mut self, path: P, other: ErrorTree) {
+        self.node_mut(&path.into()).merge(other);
+    }
+
+    /// Return this tree nested below a path
+    pub fn nest<P: Into<FieldPath>>(self, path: P) -> ErrorTree {
+        let mut tree = ErrorTree::new();
+        tree.merge_at(path, self);
+        tree
+    }
+
+    /// Every violation together with its full path, depth first
+    pub fn leaves(&self) -> Vec<(FieldPath, &Violation)> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&FieldPath::root(), &mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, path: &FieldPath, leaves: &mut Vec<(FieldPath, &'a Violation)>) {
+        for violation in &self.violations {
+            leaves.push((path.clone(), violation));
+        }
+        for (segment, child) in &self.children {
+            child.collect_leaves(&path.join(segment.clone()), leaves);
+        }
+    }
+
+    /// Consume the tree and return every violation together with its full path
+    pub fn into_leaves(self) -> Vec<(FieldPath, Violation)> {
+        let mut leaves = Vec::new();
+        self.collect_into_leaves(FieldPath::root(), &mut leaves);
+        leaves
+    }
+
+    fn collect_into_leaves(self, path: FieldPath, leaves: &mut Vec<(FieldPath, Violation)>) {
+        for violation in self.violations {
+            leaves.push((path.clone(), violation));
+        }
+        for (segment, child) in self.children {
+            child.collect_into_leaves(path.join(segment), leaves);
+        }
+    }
+
+    /// Keep only the violations matching a predicate, dropping emptied children
+    pub fn retain<F: FnMut(&Violation) -> bool>(&mut self, f: &mut F) {
+        self.violations.retain(|violation| f(violation));
+        for child in self.children.values_mut() {
+            child.retain(f);
+        }
+        self.children.retain(|_, child| !child.is_empty());
+    }
+
+    /// Apply a function to every violation in the tree
+    pub fn map_violations<F: FnMut(&mut Violation)>(&mut self, f: &mut F) {
+        self.violations.iter_mut().for_each(&mut *f);
+        for child in self.children.values_mut() {
+            child.map_violations(f);
+        }
+    }
+}
+
+/// A violation together with the path it belongs to, as it appears on the wire
+///
+/// ```json
+/// {
+///   "path": "items[3].sku",
+///   "pointer": "/items/3/sku",
+///   "code": "length.min",
+///   "message": "Length must be at least 3",
+///   "params": { "actual": 2, "max": null, "min": 3 },
+///   "value": "ab"
+/// }
+/// ```
+///
+/// `path` is empty for violations of the validated value as a whole. `pointer`
+/// is the same location as an RFC 6901 JSON Pointer; it is informational and
+/// ignored when deserializing. `params` and `value` are omitted when empty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorEntry {
+    /// Dotted path of the offending value
+    pub path: FieldPath,
+
+    /// JSON Pointer of the offending value
+    #[serde(default)]
+    pub pointer: String,
+
+    /// The violation itself
+    #[serde(flatten)]
+    pub violation: Violation,
+}
+
+impl ErrorEntry {
+    /// Create an entry for a violation at a path
+    pub fn new(path: FieldPath, violation: Violation) -> Self {
+        ErrorEntry {
+            pointer: path.to_json_pointer(),
+            path,
+            violation,
+        }
+    }
+}
 
 /// Represents validation errors that can occur during validation
 #[derive(Debug, Clone)]
 pub enum ValidationError {
-    /// A single validation error with a message
-    Single(String),
-    
-    /// Multiple validation errors grouped by field
-    Multiple(HashMap<String, Vec<String>>),
+    /// A single validation error
+    Single(Violation),
+
+    /// Multiple validation errors arranged by field path
+    Multiple(ErrorTree),
 }
 
 impl ValidationError {
-    /// Create a new single validation error
+    /// Create a new single validation error with the `custom` code
     pub fn new<S: Into<String>>(message: S) -> Self {
-        ValidationError::Single(message.into())
+        ValidationError::Single(Violation::new(CUSTOM_CODE, message))
     }
-    
-    /// Create a new validation error for a specific field
-    pub fn field<S: Into<String>, M: Into<String>>(field: S, message: M) -> Self {
-        let mut errors = HashMap::new();
-        errors.insert(field.into(), vec![message.into()]);
+
+    /// Create a new validation error for a specific field with the `custom` code
+    ///
+    /// The field may be a nested path such as `address.street` or `items[3].sku`.
+    pub fn field<P: Into<FieldPath>, M: Into<String>>(field: P, message: M) -> Self {
+        let mut errors = ErrorTree::new();
+        errors.add(field, Violation::new(CUSTOM_CODE, message));
         ValidationError::Multiple(errors)
     }
-    
-    /// Merge multiple validation errors
-    pub fn merge(self, other: ValidationError) -> ValidationError {
-        match (self, other) {
-            (ValidationError::Single(msg1), ValidationError::Single(msg2)) => {
-                let mut errors = HashMap::new();
-                errors.insert("_".to_string(), vec![msg1, msg2]);
-                ValidationError::Multiple(errors)
+
+    /// Return this error nested below a path
+    pub fn at<P: Into<FieldPath>>(self, path: P) -> ValidationError {
+        ValidationError::Multiple(self.into_tree().nest(path))
+    }
+
+    /// Return this error nested below a sequence index
+    pub fn at_index(self, index: usize) -> ValidationError {
+        self.at(PathSegment::Index(index))
+    }
+
+    /// Return this error nested below a map key
+    pub fn at_key<S: Into<String>>(self, key: S) -> ValidationError {
+        self.at(PathSegment::Key(key.into()))
+    }
+
+    /// Get the code of a single validation error
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            ValidationError::Single(violation) => Some(&violation.code),
+            ValidationError::Multiple(_) => None,
+        }
+    }
+
+    /// Iterate over every violation, regardless of the field it belongs to
+    pub fn violations(&self) -> Vec<&Violation> {
+        self.leaves().into_iter().map(|(_, violation)| violation).collect()
+    }
+
+    /// Consume the error and return every violation it holds
+    pub fn into_violations(self) -> Vec<Violation> {
+        self.into_tree().into_leaves().into_iter().map(|(_, violation)| violation).collect()
+    }
+
+    /// Every violation together with its full path
+    pub fn leaves(&self) -> Vec<(FieldPath, &Violation)> {
+        match self {
+            ValidationError::Single(violation) => vec![(FieldPath::root(), violation)],
+            ValidationError::Multiple(errors) => errors.leaves(),
+        }
+    }
+
+    /// Get the violations recorded at exactly the given path
+    pub fn field_violations<P: Into<FieldPath>>(&self, path: P) -> Vec<&Violation> {
+        let path = path.into();
+        match self {
+            ValidationError::Single(violation) if path.is_root() => vec![violation],
+            ValidationError::Single(_) => Vec::new(),
+            ValidationError::Multiple(errors) => errors
+                .get(path)
+                .map(|node| node.violations().iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Check whether any violation carries the given code
+    pub fn has_code(&self, code: &str) -> bool {
+        self.violations().iter().any(|violation| violation.code == code)
+    }
+
+    /// Check whether any violation has the `Error` severity
+    pub fn is_fatal(&self) -> bool {
+        self.violations().iter().any(|violation| violation.is_error())
+    }
+
+    /// Set the severity of every violation in the error
+    pub fn with_severity(self, severity: Severity) -> ValidationError {
+        self.map_violations(|violation| violation.severity = severity)
+    }
+
+    /// Convert the error into a tree; a single error becomes a root violation
+    pub fn into_tree(self) -> ErrorTree {
+        match self {
+            ValidationError::Single(violation) => {
+                let mut tree = ErrorTree::new();
+                tree.violations.push(violation);
+                tree
             },
-            (ValidationError::Single(msg), ValidationError::Multiple(mut errs)) => {
-                let entry = errs.entry("_".to_string()).or_insert_with(Vec::new);
-                entry.push(msg);
-                ValidationError::Multiple(errs)
+            ValidationError::Multiple(errors) => errors,
+        }
+    }
+
+    /// Apply a function to every violation in the error
+    pub fn map_violations<F: FnMut(&mut Violation)>(self, mut f: F) -> ValidationError {
+        match self {
+            ValidationError::Single(mut violation) => {
+                f(&mut violation);
+                ValidationError::Single(violation)
             },
-            (ValidationError::Multiple(mut errs), ValidationError::Single(msg)) => {
-                let entry = errs.entry("_".to_string()).or_insert_with(Vec::new);
-                entry.push(msg);
-                ValidationError::Multiple(errs)
+            ValidationError::Multiple(mut errors) => {
+                errors.map_violations(&mut f);
+                ValidationError::Multiple(errors)
             },
-            (ValidationError::Multiple(mut errs1), ValidationError::Multiple(errs2)) => {
-                for (field, messages) in errs2 {
-                    let entry = errs1.entry(field).or_insert_with(Vec::new);
-                    entry.extend(messages);
-                }
-                ValidationError::Multiple(errs1)
-            }
         }
     }
+
+    /// Merge multiple validation errors, preserving the structure of both
+    pub fn merge(self, other: ValidationError) -> ValidationError {
+        let mut errors = self.into_tree();
+        errors.merge(other.into_tree());
+        ValidationError::Multiple(errors)
+    }
+}
+
+impl ValidationError {
+    /// Flatten the error into wire entries in deterministic order
+    ///
+    /// Entries are ordered by path: violations of a value come before those of
+    /// its children, fields and map keys sort lexicographically and sequence
+    /// indices numerically. Violations at the same path keep the order in which
+    /// they were reported.
+    pub fn entries(&self) -> Vec<ErrorEntry> {
+        self.leaves()
+            .into_iter()
+            .map(|(path, violation)| ErrorEntry::new(path, violation.clone()))
+            .collect()
+    }
+
+    /// Rebuild an error from wire entries
+    ///
+    /// A single entry at the root path becomes `ValidationError::Single`; anything
+    /// else becomes `ValidationError::Multiple`.
+    pub fn from_entries<I: IntoIterator<Item = ErrorEntry>>(entries: I) -> ValidationError {
+        let mut entries: Vec<ErrorEntry> = entries.into_iter().collect();
+        if entries.len() == 1 && entries[0].path.is_root() {
+            return ValidationError::Single(entries.remove(0).violation);
+        }
+
+        let mut errors = ErrorTree::new();
+        for entry in entries {
+            errors.add(entry.path, entry.violation);
+        }
+        ValidationError::Multiple(errors)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireErrors {
+    errors: Vec<ErrorEntry>,
+}
+
+/// Serializes as `{"errors": [ErrorEntry, ...]}` in the order of [`ValidationError::entries`]
+impl Serialize for ValidationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WireErrors { errors: self.entries() }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidationError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let wire = WireErrors::deserialize(deserializer)?;
+        Ok(ValidationError::from_entries(wire.errors))
+    }
+}
+
+impl From<Violation> for ValidationError {
+    fn from(violation: Violation) -> Self {
+        ValidationError::Single(violation)
+    }
+}
+
+impl From<ErrorTree> for ValidationError {
+    fn from(errors: ErrorTree) -> Self {
+        ValidationError::Multiple(errors)
+    }
 }
 
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            ValidationError::Single(msg) => write!(f, "{}", msg),
+            ValidationError::Single(violation) => write!(f, "{}", violation),
             ValidationError::Multiple(errors) => {
                 writeln!(f, "Validation errors:")?;
-                for (field, messages) in errors {
-                    for msg in messages {
-                        writeln!(f, "  {}: {}", field, msg)?;
+                for (path, violation) in errors.leaves() {
+                    if path.is_root() {
+                        writeln!(f, "  {}", violation)?;
+                    } else {
+                        writeln!(f, "  {}: {}", path, violation)?;
                     }
                 }
                 Ok(())
@@ -70,3 +507,116 @@ impl fmt::Display for ValidationError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn violations_carry_codes_params_and_values() {
+        let violation = Violation::new("length.min", "Length must be at least 3")
+            .with_param("min", &3)
+            .with_param("max", &Option::<usize>::None)
+            .with_value("ab");
+        assert_eq!(violation.code, "length.min");
+        assert_eq!(violation.param("min"), Some(&json!(3)));
+        assert_eq!(violation.param("max"), Some(&Value::Null));
+        assert_eq!(violation.param("pattern"), None);
+        assert_eq!(violation.value, Some(json!("ab")));
+        assert_eq!(violation.to_string(), "Length must be at least 3");
+    }
+
+    #[test]
+    fn bare_messages_use_the_custom_code() {
+        let err = ValidationError::new("Something is off");
+        assert_eq!(err.code(), Some(CUSTOM_CODE));
+        assert!(err.has_code(CUSTOM_CODE));
+        assert!(!err.has_code("required"));
+        assert_eq!(err.to_string(), "Something is off");
+    }
+
+    fn paths(err: &ValidationError) -> Vec<String> {
+        err.leaves().into_iter().map(|(path, _)| path.to_string()).collect()
+    }
+
+    #[test]
+    fn trees_arrange_violations_by_path() {
+        let mut tree = ErrorTree::new();
+        tree.add("items[10].sku", Violation::new("length.min", "Too short"));
+        tree.add("items[2].sku", Violation::new("required", "Required"));
+        tree.add("address.street", Violation::new("required", "Required"));
+        tree.add(FieldPath::root(), Violation::new("custom", "Whole value"));
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.get("items").map(ErrorTree::len), Some(2));
+        assert!(tree.get("name").is_none());
+
+        // Values come before their children, and indices sort numerically
+        let err = ValidationError::from(tree);
+        assert_eq!(paths(&err), ["", "address.street", "items[2].sku", "items[10].sku"]);
+        assert_eq!(err.field_violations("items[2].sku")[0].code, "required");
+        assert!(err.field_violations("items").is_empty());
+    }
+
+    #[test]
+    fn merging_keeps_the_structure_of_both_errors() {
+        let first = ValidationError::field("address.street", "Street is required");
+        let second = Violation::new("length.max", "Too long").into();
+        let second = ValidationError::at(second, "address.city");
+        let merged = first.merge(second).merge(ValidationError::new("Whole value"));
+        assert_eq!(paths(&merged), ["", "address.city", "address.street"]);
+        assert_eq!(merged.violations().len(), 3);
+        assert_eq!(merged.code(), None);
+    }
+
+    #[test]
+    fn errors_nest_below_indices_and_keys() {
+        let err = ValidationError::new("Invalid").at_index(3).at("items").at_key("a.b");
+        assert_eq!(paths(&err), ["[\"a.b\"].items[3]"]);
+        assert_eq!(err.field_violations(FieldPath::root().key("a.b").field("items").index(3)).len(), 1);
+    }
+
+    #[test]
+    fn retain_drops_emptied_children() {
+        let mut tree = ErrorTree::new();
+        tree.add("name", Violation::new("short", "Short"));
+        tree.add("email", Violation::new("required", "Required"));
+        tree.retain(&mut |violation| violation.code != "short");
+        assert_eq!(tree.children().len(), 1);
+        assert!(tree.get("name").is_none());
+
+        tree.map_violations(&mut |violation| violation.message = violation.message.to_uppercase());
+        assert_eq!(tree.leaves()[0].1.message, "REQUIRED");
+    }
+
+    #[test]
+    fn errors_serialize_as_a_list_of_entries() {
+        let err = ValidationError::field("items[3].sku", "Too short")
+            .merge(Violation::new("length.min", "Length must be at least 3").with_param("min", &3).with_value("ab").into());
+        let wire = serde_json::to_value(&err).unwrap();
+        assert_eq!(wire, json!({
+            "errors": [
+                { "path": "", "pointer": "", "code": "length.min", "message": "Length must be at least 3", "params": { "min": 3 }, "value": "ab" },
+                { "path": "items[3].sku", "pointer": "/items/3/sku", "code": "custom", "message": "Too short" },
+            ]
+        }));
+
+        let back: ValidationError = serde_json::from_value(wire).unwrap();
+        assert_eq!(back.entries(), err.entries());
+    }
+
+    #[test]
+    fn a_single_root_entry_deserializes_as_a_single_error() {
+        let err: ValidationError = serde_json::from_value(json!({
+            "errors": [{ "path": "", "code": "required", "message": "Value is required" }]
+        }))
+        .unwrap();
+        assert!(matches!(err, ValidationError::Single(ref violation) if violation.code == "required"));
+
+        let err: ValidationError = serde_json::from_value(json!({
+            "errors": [{ "path": "name", "pointer": "/ignored", "code": "required", "message": "Value is required" }]
+        }))
+        .unwrap();
+        assert_eq!(err.entries()[0].pointer, "/name");
+    }
+}
@@ -0,0 +1,496 @@
+//! Validation of HTML form submissions and query strings
+//!
+//! Forms arrive as names with one or more string values. A [`FormSchema`]
+//! binds rules to field names, converting the strings to the type the rules
+//! are written for, and reports findings at the field names so they can be
+//! shown next to the inputs when the form is rendered again:
+//!
+//! ```
+//! # use rustvalidity::form::{FormData, FormErrors, FormSchema};
+//! # use rustvalidity::rules::collection::{MinSize, Unique};
+//! # use rustvalidity::rules::common::Email;
+//! # use rustvalidity::rules::numeric::Range;
+//! # use rustvalidity::validator::ValidationMode;
+//! let schema = FormSchema::new()
+//!     .with_mode(ValidationMode::CollectAll)
+//!     .required("email")
+//!     .field::<String>("email", vec![Box::new(Email { check_dns: false })])
+//!     .field::<i32>("age", vec![Box::new(Range { min: 18, max: 120 })])
+//!     .values::<String>("tags", vec![Box::new(MinSize { min: 1 }), Box::new(Unique)]);
+//!
+//! let form = FormData::parse("email=ada%40example.com&age=abc&tags=rust&tags=rust");
+//! if let Err(err) = schema.validate(&form) {
+//!     let errors = FormErrors::from(&err);
+//!     assert_eq!(errors.first("age"), Some("Value is not an integer"));
+//!     assert!(!errors.contains("email"));
+//!     assert!(errors.contains("tags"));
+//! }
+//! ```
+//!
+//! Values are converted with [`FromValue`], so `"42"` is a number for a
+//! `Range<i32>` and `"abc"` is reported with the `invalid_type` code. Empty
+//! values are missing, as browsers send blank inputs as `name=`: field rules
+//! skip them, and [`FormSchema::required`] reports them.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+use crate::context::Clock;
+use crate::dynamic::FromValue;
+use crate::error::ValidationError;
+use crate::path::{FieldPath, PathSegment};
+use crate::report::ValidationReport;
+use crate::rules::common::Required;
+use crate::rules::{Rule, RuleInfo};
+use crate::validator::{ValidationMode, ValidationRun, Validator};
+
+type FieldCheck = Box<dyn Fn(FieldPath, &[String], &mut ValidationRun, &mut bool) + Send + Sync>;
+
+/// The fields of a form submission or query string, each with the values sent for it
+///
+/// Names that are sent more than once, such as a group of checkboxes or a
+/// multiple select, keep all of their values in the order they were sent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormData {
+    fields: HashMap<String, Vec<String>>,
+}
+
+impl FormData {
+    /// Create form data without any field
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse an `application/x-www-form-urlencoded` body, such as `name=Ada+L&tags=a&tags=b`
+    ///
+    /// Percent-encoded bytes that are not valid UTF-8 are replaced with `U+FFFD`.
+    pub fn parse(body: &str) -> Self {
+        Self::from_bytes(body.as_bytes())
+    }
+
+    /// Parse an `application/x-www-form-urlencoded` body that has not been decoded as text
+    pub fn from_bytes(body: &[u8]) -> Self {
+        form_urlencoded::parse(body).collect()
+    }
+
+    /// Parse a query string, with or without its leading `?`
+    pub fn from_query(query: &str) -> Self {
+        Self::parse(query.strip_prefix('?').unwrap_or(query))
+    }
+
+    /// The query string of a URL
+    pub fn from_url(url: &Url) -> Self {
+        url.query_pairs().collect()
+    }
+
+    /// Add a value to a field, after the values it already has
+    pub fn append<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        self.fields.entry(name.into()).or_default().push(value.into());
+    }
+
+    /// The first value sent for a field
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.get_all(name).first().map(String::as_str)
+    }
+
+    /// Every value sent for a field, empty when the field was not sent
+    pub fn get_all(&self, name: &str) -> &[String] {
+        self.fields.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Check whether a field was sent, even without a value
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields.contains_key(name)
+    }
+
+    /// The names of the fields that were sent
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// Number of fields that were sent
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Check whether no field was sent
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// The fields with their values
+    pub fn as_map(&self) -> &HashMap<String, Vec<String>> {
+        &self.fields
+    }
+}
+
+impl From<HashMap<String, Vec<String>>> for FormData {
+    fn from(fields: HashMap<String, Vec<String>>) -> Self {
+        FormData { fields }
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> FromIterator<(N, V)> for FormData {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(pairs: I) -> Self {
+        let mut form = FormData::new();
+        for (name, value) in pairs {
+            form.append(name, value);
+        }
+        form
+    }
+}
+
+/// A field with the rules checking its values
+struct Field {
+    name: String,
+    rules: Vec<RuleInfo>,
+    checks: Vec<FieldCheck>,
+}
+
+/// Rules bound to the fields of a form, checked against its submissions
+pub struct FormSchema {
+    validator: Validator,
+    fields: Vec<Field>,
+}
+
+impl FormSchema {
+    /// Create a schema without any field
+    pub fn new() -> Self {
+        FormSchema {
+            validator: Validator::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Set how far validation goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.validator = self.validator.with_mode(mode);
+        self
+    }
+
+    /// Stop checking a field after its first error while still checking the other fields
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.validator = self.validator.bail(bail);
+        self
+    }
+
+    /// Add data available to every rule through its context
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.validator = self.validator.with_data(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.validator = self.validator.with_clock(clock);
+        self
+    }
+
+    /// Require a field to be sent with a value that is not empty
+    pub fn required(self, name: &str) -> Self {
+        let rules: Vec<Box<dyn Rule<Value>>> = vec![Box::new(Required)];
+        self.bind(name, rules.iter().map(|rule| rule.describe()).collect(), Box::new(move |path, values, run, _| {
+            let value = submitted(values).next().map_or(Value::Null, |value| Value::String(value.clone()));
+            run.rules(path, &value, &rules);
+        }))
+    }
+
+    /// Bind rules on a type to the value of a field, converting it with [`FromValue`]
+    ///
+    /// The first value sent for the field is checked. Missing and empty values
+    /// are skipped; combine with [`FormSchema::required`] for fields that must
+    /// be filled in. A value that cannot be converted is reported with the
+    /// `invalid_type` code, e.g. "Value is not a number".
+    pub fn field<T: FromValue + 'static>(self, name: &str, rules: Vec<Box<dyn Rule<T>>>) -> Self {
+        self.bind(name, rules.iter().map(|rule| rule.describe()).collect(), Box::new(move |path, values, run, mistyped| {
+            let Some(value) = submitted(values).next() else {
+                return;
+            };
+            match T::from_value(&Value::String(value.clone())) {
+                Ok(value) => {
+                    run.rules(path, &value, &rules);
+                },
+                Err(err) => {
+                    // Fields with several typed bindings report a value they cannot read once
+                    if !std::mem::replace(mistyped, true) {
+                        run.result(path, Err(err));
+                    }
+                },
+            }
+        }))
+    }
+
+    /// Bind rules on a collection to every value of a repeated field, such as a group of checkboxes
+    ///
+    /// The values that are not empty are converted to a `Vec<T>` in the order
+    /// they were sent, so collection rules such as `MinSize` and `Unique`
+    /// apply. The rules run even when the field was not sent, with no values.
+    /// Values that cannot be converted are reported at their index among the
+    /// checked values, e.g. `ids[1]`.
+    pub fn values<T: FromValue + 'static>(self, name: &str, rules: Vec<Box<dyn Rule<Vec<T>>>>) -> Self {
+        self.bind(name, rules.iter().map(|rule| rule.describe()).collect(), Box::new(move |path, values, run, mistyped| {
+            let values = Value::Array(submitted(values).cloned().map(Value::String).collect());
+            match Vec::<T>::from_value(&values) {
+                Ok(values) => {
+                    run.rules(path, &values, &rules);
+                },
+                Err(err) => {
+                    if !std::mem::replace(mistyped, true) {
+                        run.result(path, Err(err));
+                    }
+                },
+            }
+        }))
+    }
+
+    fn bind(mut self, name: &str, rules: Vec<RuleInfo>, check: FieldCheck) -> Self {
+        match self.fields.iter_mut().find(|bound| bound.name == name) {
+            Some(bound) => {
+                bound.rules.extend(rules);
+                bound.checks.push(check);
+            },
+            None => self.fields.push(Field {
+                name: name.to_string(),
+                rules,
+                checks: vec![check],
+            }),
+        }
+        self
+    }
+
+    /// The fields rules are bound to, in declaration order
+    pub fn fields(&self) -> Vec<&str> {
+        self.fields.iter().map(|field| field.name.as_str()).collect()
+    }
+
+    /// Describe the rules bound to each field, in declaration order
+    pub fn describe(&self) -> Vec<(&str, &[RuleInfo])> {
+        self.fields.iter().map(|field| (field.name.as_str(), field.rules.as_slice())).collect()
+    }
+
+    /// Check a form as part of a validation run
+    ///
+    /// Rules reading sibling fields through their context see the [`FormData`].
+    pub fn apply<'v>(&self, form: &'v FormData, run: &mut ValidationRun<'v>) {
+        run.scope(form, |run| {
+            for field in &self.fields {
+                let values = form.get_all(&field.name);
+                let mut mistyped = false;
+                for check in &field.checks {
+                    if run.is_stopped() {
+                        return;
+                    }
+                    check(FieldPath::root().field(field.name.as_str()), values, run, &mut mistyped);
+                }
+            }
+        });
+    }
+
+    /// Validate a form in the schema's mode
+    pub fn validate(&self, form: &FormData) -> Result<(), ValidationError> {
+        self.report(form).into_result()
+    }
+
+    /// Validate a form and collect every error
+    pub fn validate_all(&self, form: &FormData) -> Result<(), ValidationError> {
+        let mut run = self.validator.start_with(ValidationMode::CollectAll);
+        self.apply(form, &mut run);
+        run.into_result()
+    }
+
+    /// Validate a form in the schema's mode and return every finding, including warnings
+    pub fn report(&self, form: &FormData) -> ValidationReport {
+        let mut run = self.validator.start();
+        self.apply(form, &mut run);
+        run.finish()
+    }
+}
+
+impl Default for FormSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The values of a field that are not empty
+fn submitted(values: &[String]) -> impl Iterator<Item = &String> {
+    values.iter().filter(|value| !value.is_empty())
+}
+
+/// Error messages keyed by form field, for showing next to the inputs of a form
+///
+/// Findings below a field, such as `ids[1]`, are listed under the field
+/// itself. Findings that do not belong to a field, e.g. of a rule on the form
+/// as a whole, are listed separately. Messages are taken as they are, so
+/// localize the error first to show them in another language.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FormErrors {
+    fields: BTreeMap<String, Vec<String>>,
+    general: Vec<String>,
+}
+
+impl FormErrors {
+    /// Check whether there is no error at all
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.general.is_empty()
+    }
+
+    /// Check whether a field has errors
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields.contains_key(name)
+    }
+
+    /// The error messages of a field
+    pub fn field(&self, name: &str) -> &[String] {
+        self.fields.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// The first error message of a field
+    pub fn first(&self, name: &str) -> Option<&str> {
+        self.field(name).first().map(String::as_str)
+    }
+
+    /// The error messages of every field with errors, by field name
+    pub fn fields(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.fields
+    }
+
+    /// The error messages that do not belong to a field
+    pub fn general(&self) -> &[String] {
+        &self.general
+    }
+}
+
+impl From<&ValidationError> for FormErrors {
+    fn from(error: &ValidationError) -> Self {
+        let mut errors = FormErrors::default();
+        for (path, violation) in error.leaves() {
+            match path.segments().first() {
+                Some(PathSegment::Field(name) | PathSegment::Key(name)) => {
+                    errors.fields.entry(name.clone()).or_default().push(violation.message.clone());
+                },
+                _ => errors.general.push(violation.message.clone()),
+            }
+        }
+        errors
+    }
+}
+
+impl From<ValidationError> for FormErrors {
+    fn from(error: ValidationError) -> Self {
+        FormErrors::from(&error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::collection::{Each, MaxSize, MinSize, Unique};
+    use crate::rules::common::Length;
+    use crate::rules::numeric::{Positive, Range};
+
+    fn codes(err: &ValidationError) -> Vec<(String, String)> {
+        err.entries().into_iter().map(|entry| (entry.path.to_string(), entry.violation.code)).collect()
+    }
+
+    #[test]
+    fn parse_decodes_and_keeps_repeated_values() {
+        let form = FormData::parse("name=Ada+Lovelace&email=ada%40example.com&tag=a&tag=b&blank=");
+        assert_eq!(form.get("name"), Some("Ada Lovelace"));
+        assert_eq!(form.get("email"), Some("ada@example.com"));
+        assert_eq!(form.get_all("tag"), ["a", "b"]);
+        assert_eq!(form.get("blank"), Some(""));
+        assert!(form.contains("blank"));
+        assert!(form.get_all("missing").is_empty());
+        assert_eq!(form.len(), 4);
+
+        assert_eq!(FormData::from_bytes(b"name=%FF"), FormData::parse("name=%EF%BF%BD"));
+    }
+
+    #[test]
+    fn query_strings_with_or_without_question_mark() {
+        assert_eq!(FormData::from_query("?page=2&sort=date"), FormData::from_query("page=2&sort=date"));
+        let url = Url::parse("https://example.com/search?page=2&sort=date").unwrap();
+        assert_eq!(FormData::from_url(&url), FormData::from_query("?page=2&sort=date"));
+    }
+
+    #[test]
+    fn fields_are_converted_to_the_rule_type() {
+        let schema = FormSchema::new()
+            .with_mode(ValidationMode::CollectAll)
+            .field::<i32>("age", vec![Box::new(Range { min: 18, max: 120 })])
+            .field::<f64>("score", vec![Box::new(Range { min: 0.0, max: 1.0 })])
+            .field::<bool>("agree", vec![]);
+
+        assert!(schema.validate(&FormData::parse("age=42&score=0.5&agree=true")).is_ok());
+
+        let err = schema.validate(&FormData::parse("age=12&score=high&agree=maybe")).unwrap_err();
+        assert_eq!(codes(&err), vec![
+            ("age".to_string(), "range.min".to_string()),
+            ("agree".to_string(), "invalid_type".to_string()),
+            ("score".to_string(), "invalid_type".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn the_first_value_is_checked_and_empty_values_are_missing() {
+        let schema = FormSchema::new()
+            .with_mode(ValidationMode::CollectAll)
+            .required("name")
+            .field::<String>("name", vec![Box::new(Length { min: 2, max: None })])
+            .field::<i32>("age", vec![Box::new(Range { min: 18, max: 120 })]);
+
+        assert!(schema.validate(&FormData::parse("name=Ada&name=A")).is_ok());
+        // A blank first value falls back to the next one that was filled in
+        assert!(schema.validate(&FormData::parse("name=&name=Ada&age=")).is_ok());
+
+        let err = schema.validate(&FormData::parse("name=&age=")).unwrap_err();
+        assert_eq!(codes(&err), vec![("name".to_string(), "required".to_string())]);
+    }
+
+    #[test]
+    fn values_collect_repeated_fields() {
+        let schema = FormSchema::new()
+            .with_mode(ValidationMode::CollectAll)
+            .values::<String>("tags", vec![Box::new(MinSize { min: 1 }), Box::new(MaxSize { max: 2 }), Box::new(Unique)])
+            .values::<u32>("ids", vec![Box::new(Each { rule: Box::new(Positive) })]);
+
+        assert!(schema.validate(&FormData::parse("tags=a&tags=b&ids=1&ids=2")).is_ok());
+
+        let err = schema.validate(&FormData::parse("tags=a&tags=a&tags=b&ids=3&ids=x")).unwrap_err();
+        assert_eq!(codes(&err), vec![
+            ("ids[1]".to_string(), "invalid_type".to_string()),
+            ("tags".to_string(), "max_size".to_string()),
+            ("tags".to_string(), "unique".to_string()),
+        ]);
+
+        // Rules on repeated fields run when the field was not sent
+        let err = schema.validate(&FormData::new()).unwrap_err();
+        assert_eq!(codes(&err), vec![("tags".to_string(), "min_size".to_string())]);
+    }
+
+    #[test]
+    fn errors_are_keyed_by_field() {
+        let schema = FormSchema::new()
+            .with_mode(ValidationMode::CollectAll)
+            .required("email")
+            .values::<u32>("ids", vec![Box::new(Each { rule: Box::new(Positive) })]);
+
+        let err = schema.validate(&FormData::parse("ids=0&ids=x")).unwrap_err();
+        let errors = FormErrors::from(&err);
+        assert_eq!(errors.fields().keys().collect::<Vec<_>>(), ["email", "ids"]);
+        assert_eq!(errors.first("email"), Some("Value is required"));
+        assert_eq!(errors.field("ids").len(), 1);
+        assert!(errors.general().is_empty());
+        assert!(errors.field("name").is_empty());
+
+        let wire = serde_json::to_value(&errors).unwrap();
+        assert_eq!(wire["fields"]["email"][0], "Value is required");
+
+        assert!(FormErrors::default().is_empty());
+    }
+}
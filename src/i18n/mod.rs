@@ -0,0 +1,470 @@
+//! Localized rendering of validation messages
+//!
+//! A [`MessageCatalog`] maps violation codes to message templates per locale.
+//! Templates reference rule parameters with `{name}` placeholders (`{value}`
+//! refers to the offending value) and may have plural forms selected by a
+//! numeric parameter. Catalogs are JSON objects keyed by code:
+//!
+//! ```json
+//! {
+//!   "required": "Wert ist erforderlich",
+//!   "min_size": {
+//!     "plural": "min",
+//!     "one": "Muss mindestens {min} Eintrag enthalten",
+//!     "other": "Muss mindestens {min} Einträge enthalten"
+//!   }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::error::{ValidationError, Violation};
+
+pub mod plural;
+
+pub use plural::PluralCategory;
+
+/// Locale of the bundle shipped with the crate
+pub const DEFAULT_LOCALE: &str = "en";
+
+const ENGLISH_BUNDLE: &str = include_str!("locales/en.json");
+
+/// Errors raised while loading message catalogs
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("Failed to read message catalog {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid message catalog: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// A message template, optionally with plural forms
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawTemplate", into = "RawTemplate")]
+pub enum MessageTemplate {
+    /// A single template used for every value
+    Simple(String),
+
+    /// Forms selected by the plural category of the named numeric parameter
+    Plural {
+        param: String,
+        forms: BTreeMap<PluralCategory, String>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawTemplate {
+    Simple(String),
+    Plural {
+        plural: String,
+        #[serde(flatten)]
+        forms: BTreeMap<String, String>,
+    },
+}
+
+impl TryFrom<RawTemplate> for MessageTemplate {
+    type Error = String;
+
+    fn try_from(raw: RawTemplate) -> Result<Self, Self::Error> {
+        match raw {
+            RawTemplate::Simple(template) => Ok(MessageTemplate::Simple(template)),
+            RawTemplate::Plural { plural, forms } => {
+                let forms = forms
+                    .into_iter()
+                    .map(|(category, template)| Ok((category.parse::<PluralCategory>()?, template)))
+                    .collect::<Result<BTreeMap<_, _>, String>>()?;
+                if !forms.contains_key(&PluralCategory::Other) {
+                    return Err(format!("Plural message on '{}' is missing the 'other' form", plural));
+                }
+                Ok(MessageTemplate::Plural { param: plural, forms })
+            },
+        }
+    }
+}
+
+impl From<MessageTemplate> for RawTemplate {
+    fn from(template: MessageTemplate) -> Self {
+        match template {
+            MessageTemplate::Simple(template) => RawTemplate::Simple(template),
+            MessageTemplate::Plural { param, forms } => RawTemplate::Plural {
+                plural: param,
+                forms: forms
+                    .into_iter()
+                    .map(|(category, template)| (category.as_str().to_string(), template))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl From<&str> for MessageTemplate {
+    fn from(template: &str) -> Self {
+        MessageTemplate::Simple(template.to_string())
+    }
+}
+
+impl From<String> for MessageTemplate {
+    fn from(template: String) -> Self {
+        MessageTemplate::Simple(template)
+    }
+}
+
+impl MessageTemplate {
+    /// Render the template for a violation in a language
+    pub fn render(&self, language: &str, violation: &Violation) -> String {
+        let template = match self {
+            MessageTemplate::Simple(template) => template,
+            MessageTemplate::Plural { param, forms } => {
+                let category = lookup(violation, param)
+                    .and_then(Value::as_f64)
+                    .map(|n| PluralCategory::for_number(language, n))
+                    .unwrap_or(PluralCategory::Other);
+                forms
+                    .get(&category)
+                    .or_else(|| forms.get(&PluralCategory::Other))
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            },
+        };
+
+        interpolate(template, violation)
+    }
+}
+
+/// Messages of a single locale keyed by violation code
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageBundle {
+    messages: BTreeMap<String, MessageTemplate>,
+}
+
+impl MessageBundle {
+    /// Create an empty bundle
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a bundle from JSON
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Read a bundle from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, CatalogError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|source| CatalogError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Set the template of a code
+    pub fn insert<C: Into<String>, T: Into<MessageTemplate>>(&mut self, code: C, template: T) {
+        self.messages.insert(code.into(), template.into());
+    }
+
+    /// Get the template of a code
+    pub fn get(&self, code: &str) -> Option<&MessageTemplate> {
+        self.messages.get(code)
+    }
+
+    /// The codes covered by the bundle
+    pub fn codes(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(String::as_str)
+    }
+
+    /// Copy every template of another bundle into this one, replacing existing ones
+    pub fn extend(&mut self, other: MessageBundle) {
+        self.messages.extend(other.messages);
+    }
+}
+
+/// Message templates for several locales
+///
+/// Lookups walk from the requested locale to its language (`de-CH` to `de`)
+/// and then to the fallback locale. Violations without a template keep the
+/// message the rule produced.
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    bundles: HashMap<String, MessageBundle>,
+    fallback: String,
+}
+
+impl MessageCatalog {
+    /// Create a catalog holding the built-in English bundle
+    pub fn new() -> Self {
+        let mut catalog = Self::empty();
+        let english = MessageBundle::from_json(ENGLISH_BUNDLE)
+            .expect("built-in English message bundle is valid");
+        catalog.add_bundle(DEFAULT_LOCALE, english);
+        catalog
+    }
+
+    /// Create a catalog without any bundle
+    pub fn empty() -> Self {
+        MessageCatalog {
+            bundles: HashMap::new(),
+            fallback: DEFAULT_LOCALE.to_string(),
+        }
+    }
+
+    /// Set the locale used when the requested one has no template
+    pub fn with_fallback(mut self, locale: &str) -> Self {
+        self.fallback = normalize(locale);
+        self
+    }
+
+    /// Add a bundle; templates of an existing bundle for the locale are replaced
+    pub fn add_bundle(&mut self, locale: &str, bundle: MessageBundle) {
+        self.bundles.entry(normalize(locale)).or_default().extend(bundle);
+    }
+
+    /// Set a single template
+    pub fn add_message<C: Into<String>, T: Into<MessageTemplate>>(&mut self, locale: &str, code: C, template: T) {
+        self.bundles.entry(normalize(locale)).or_default().insert(code, template);
+    }
+
+    /// Load a bundle for a locale from a JSON string
+    pub fn load_json(&mut self, locale: &str, json: &str) -> Result<(), CatalogError> {
+        self.add_bundle(locale, MessageBundle::from_json(json)?);
+        Ok(())
+    }
+
+    /// Load a bundle for a locale from a JSON file
+    pub fn load_file<P: AsRef<Path>>(&mut self, locale: &str, path: P) -> Result<(), CatalogError> {
+        self.add_bundle(locale, MessageBundle::from_file(path)?);
+        Ok(())
+    }
+
+    /// Load every `<locale>.json` file of a directory and return the loaded locales
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<Vec<String>, CatalogError> {
+        let dir = dir.as_ref();
+        let io_error = |source| CatalogError::Io {
+            path: dir.to_path_buf(),
+            source,
+        };
+
+        let mut loaded = Vec::new();
+        for entry in fs::read_dir(dir).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) {
+                self.load_file(locale, &path)?;
+                loaded.push(normalize(locale));
+            }
+        }
+        loaded.sort();
+        Ok(loaded)
+    }
+
+    /// The locales with a bundle
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.bundles.keys().map(String::as_str).collect();
+        locales.sort();
+        locales
+    }
+
+    /// Get the bundle of a locale
+    pub fn bundle(&self, locale: &str) -> Option<&MessageBundle> {
+        self.bundles.get(&normalize(locale))
+    }
+
+    /// Find the template for a code, walking the locale fallback chain
+    ///
+    /// Returns the locale the template was found in together with the template.
+    pub fn template(&self, locale: &str, code: &str) -> Option<(&str, &MessageTemplate)> {
+        self.fallback_chain(locale).into_iter().find_map(|candidate| {
+            self.bundles
+                .get_key_value(&candidate)
+                .and_then(|(locale, bundle)| bundle.get(code).map(|template| (locale.as_str(), template)))
+        })
+    }
+
+    /// Render the message of a violation in a locale
+    pub fn render(&self, violation: &Violation, locale: &str) -> String {
+        match self.template(locale, &violation.code) {
+            Some((found, template)) => template.render(language(found), violation),
+            None => violation.message.clone(),
+        }
+    }
+
+    /// Return a copy of the error with every message rendered in a locale
+    pub fn localize(&self, error: &ValidationError, locale: &str) -> ValidationError {
+        error.clone().localize(self, locale)
+    }
+
+    fn fallback_chain(&self, locale: &str) -> Vec<String> {
+        let locale = normalize(locale);
+        let mut chain = vec![locale.clone()];
+        let mut current = locale.as_str();
+        while let Some(pos) = current.rfind('-') {
+            current = &current[..pos];
+            chain.push(current.to_string());
+        }
+        if !chain.contains(&self.fallback) {
+            chain.push(self.fallback.clone());
+        }
+        chain
+    }
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationError {
+    /// Render every message of the error in a locale
+    pub fn localize(self, catalog: &MessageCatalog, locale: &str) -> ValidationError {
+        self.map_violations(|violation| {
+            violation.message = catalog.render(violation, locale);
+        })
+    }
+}
+
+fn normalize(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_lowercase()
+}
+
+fn language(locale: &str) -> &str {
+    locale.split('-').next().unwrap_or(locale)
+}
+
+fn lookup<'a>(violation: &'a Violation, name: &str) -> Option<&'a Value> {
+    violation.params.get(name).or(match name {
+        "value" => violation.value.as_ref(),
+        _ => None,
+    })
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(format_value).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// Replace `{name}` placeholders; `{{` and `}}` produce literal braces and
+/// unknown placeholders are left untouched
+fn interpolate(template: &str, violation: &Violation) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            output.push_str(&rest[..1]);
+            rest = &rest[2..];
+        } else if rest.starts_with('{') {
+            match rest.find('}') {
+                Some(end) => {
+                    let name = &rest[1..end];
+                    match lookup(violation, name) {
+                        Some(value) => output.push_str(&format_value(value)),
+                        None => output.push_str(&rest[..=end]),
+                    }
+                    rest = &rest[end + 1..];
+                },
+                None => {
+                    output.push_str(rest);
+                    rest = "";
+                },
+            }
+        } else {
+            output.push('}');
+            rest = &rest[1..];
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violation() -> Violation {
+        Violation::new("length.min", "Length must be at least 3").with_param("min", &3).with_value("ab")
+    }
+
+    #[test]
+    fn placeholders_are_replaced_and_braces_escaped() {
+        let rendered = interpolate("{{min}} is {min}, got '{value}' {unknown} }} {", &violation());
+        assert_eq!(rendered, "{min} is 3, got 'ab' {unknown} } {");
+        assert_eq!(interpolate("no placeholders", &violation()), "no placeholders");
+        assert_eq!(interpolate("{min}}}", &violation()), "3}");
+    }
+
+    #[test]
+    fn plural_forms_follow_the_language() {
+        let mut catalog = MessageCatalog::empty();
+        catalog
+            .load_json("pl", r#"{"min_size": {"plural": "min", "one": "{min} element", "few": "{min} elementy", "many": "{min} elementów", "other": "{min} elementu"}}"#)
+            .unwrap();
+        let render = |min: usize| catalog.render(&Violation::new("min_size", "").with_param("min", &min), "pl");
+        assert_eq!(render(1), "1 element");
+        assert_eq!(render(3), "3 elementy");
+        assert_eq!(render(5), "5 elementów");
+        assert_eq!(render(22), "22 elementy");
+        assert_eq!(catalog.render(&Violation::new("min_size", "").with_param("min", &1.5), "pl"), "1.5 elementu");
+
+        // A missing form falls back to `other`, and a missing parameter selects it
+        let template: MessageTemplate = serde_json::from_str(r#"{"plural": "min", "other": "at least {min}"}"#).unwrap();
+        assert_eq!(template.render("en", &violation()), "at least 3");
+        assert_eq!(template.render("en", &Violation::new("min_size", "")), "at least {min}");
+        assert!(serde_json::from_str::<MessageTemplate>(r#"{"plural": "min", "one": "one"}"#).is_err());
+    }
+
+    #[test]
+    fn locales_fall_back_to_their_language_and_then_the_fallback() {
+        let mut catalog = MessageCatalog::new();
+        catalog.add_message("de", "required", "Wert ist erforderlich");
+        catalog.add_message("de-AT", "length.min", "Mindestens {min} Zeichen");
+
+        let required = Violation::new("required", "Value is required");
+        assert_eq!(catalog.render(&required, "de_AT"), "Wert ist erforderlich");
+        assert_eq!(catalog.render(&violation(), "de-at"), "Mindestens 3 Zeichen");
+        assert_eq!(catalog.render(&violation(), "de"), "Length must be at least 3");
+        assert_eq!(catalog.template("fr-CA", "required").map(|(locale, _)| locale), Some("en"));
+
+        let catalog = MessageCatalog::empty().with_fallback("de");
+        assert_eq!(catalog.render(&Violation::new("unknown", "Kept as is"), "fr"), "Kept as is");
+    }
+
+    #[test]
+    fn errors_are_localized_in_place() {
+        let mut catalog = MessageCatalog::new();
+        catalog.add_message("de", "required", "Wert ist erforderlich");
+        let err = ValidationError::field("name", "Value is required").map_violations(|violation| violation.code = "required".to_string());
+        let localized = catalog.localize(&err, "de");
+        assert_eq!(localized.violations()[0].message, "Wert ist erforderlich");
+        assert_eq!(err.localize(&catalog, "en").violations()[0].message, "Value is required");
+    }
+
+    #[test]
+    fn catalogs_report_invalid_json() {
+        let mut catalog = MessageCatalog::empty();
+        assert!(matches!(catalog.load_json("de", "{"), Err(CatalogError::Parse(_))));
+        assert!(matches!(catalog.load_file("de", "/nonexistent/de.json"), Err(CatalogError::Io { .. })));
+        assert!(catalog.locales().is_empty());
+    }
+}
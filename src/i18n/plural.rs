@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// CLDR plural category used to pick the form of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// The category name as used in message catalogs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    /// Select the plural category of a number for a language
+    ///
+    /// Covers the cardinal rules of the most common languages; languages
+    /// without a specific rule use the English one/other split.
+    pub fn for_number(language: &str, n: f64) -> PluralCategory {
+        let integer = n.fract() == 0.0 && n >= 0.0;
+        let i = n.abs().trunc() as u64;
+        let mod10 = i % 10;
+        let mod100 = i % 100;
+
+        match language {
+            // No plural distinction
+            "ja" | "zh" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => PluralCategory::Other,
+
+            // 0 and 1 are singular
+            "fr" | "pt" | "hy" | "kab" => {
+                if i <= 1 && n < 2.0 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            },
+
+            "ru" | "uk" | "be" => {
+                if !integer {
+                    PluralCategory::Other
+                } else if mod10 == 1 && mod100 != 11 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            },
+
+            "pl" => {
+                if !integer {
+                    PluralCategory::Other
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            },
+
+            "cs" | "sk" => {
+                if !integer {
+                    PluralCategory::Many
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&i) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Other
+                }
+            },
+
+            "ar" => {
+                if !integer {
+                    PluralCategory::Other
+                } else if i == 0 {
+                    PluralCategory::Zero
+                } else if i == 1 {
+                    PluralCategory::One
+                } else if i == 2 {
+                    PluralCategory::Two
+                } else if (3..=10).contains(&mod100) {
+                    PluralCategory::Few
+                } else if (11..=99).contains(&mod100) {
+                    PluralCategory::Many
+                } else {
+                    PluralCategory::Other
+                }
+            },
+
+            // English, German, Dutch, Spanish, Italian, Swedish, ...
+            _ => {
+                if integer && i == 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            },
+        }
+    }
+}
+
+impl fmt::Display for PluralCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for PluralCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(PluralCategory::Zero),
+            "one" => Ok(PluralCategory::One),
+            "two" => Ok(PluralCategory::Two),
+            "few" => Ok(PluralCategory::Few),
+            "many" => Ok(PluralCategory::Many),
+            "other" => Ok(PluralCategory::Other),
+            _ => Err(format!("Unknown plural category '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_follow_cldr_rules() {
+        use PluralCategory::*;
+        let cases = [
+            ("en", 1.0, One),
+            ("en", 0.0, Other),
+            ("en", 1.5, Other),
+            ("fr", 0.0, One),
+            ("fr", 1.5, One),
+            ("fr", 2.0, Other),
+            ("ru", 21.0, One),
+            ("ru", 11.0, Many),
+            ("ru", 24.0, Few),
+            ("ru", 1.5, Other),
+            ("cs", 3.0, Few),
+            ("cs", 0.5, Many),
+            ("ar", 0.0, Zero),
+            ("ar", 2.0, Two),
+            ("ar", 103.0, Few),
+            ("ar", 111.0, Many),
+            ("ar", 100.0, Other),
+            ("ja", 1.0, Other),
+        ];
+        for (language, n, category) in cases {
+            assert_eq!(PluralCategory::for_number(language, n), category, "{} {}", language, n);
+        }
+    }
+
+    #[test]
+    fn categories_parse_their_names() {
+        for category in ["zero", "one", "two", "few", "many", "other"] {
+            assert_eq!(category.parse::<PluralCategory>().unwrap().to_string(), category);
+        }
+        assert!("several".parse::<PluralCategory>().is_err());
+    }
+}
@@ -0,0 +1,596 @@
+//! JSON Schema export of validation rules
+//!
+//! Rules describe the constraints they enforce as JSON Schema keywords through
+//! [`Rule::json_schema`](crate::rules::Rule::json_schema), and types describe
+//! their shape through [`JsonSchema`]. Together they produce a Draft 2020-12
+//! document, e.g. for a derived struct:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! # use rustvalidity::json_schema::JsonSchema;
+//! # use rustvalidity::Validate;
+//! # use serde_json::json;
+//! #[derive(Validate)]
+//! struct User {
+//!     #[validate(required, length(min = 3, max = 20))]
+//!     username: String,
+//!     #[validate(email)]
+//!     email: String,
+//!     nickname: Option<String>,
+//! }
+//!
+//! assert_eq!(User::schema(), json!({
+//!     "$schema": "https://json-schema.org/draft/2020-12/schema",
+//!     "title": "User",
+//!     "type": "object",
+//!     "properties": {
+//!         "username": {"type": "string", "minLength": 3, "maxLength": 20},
+//!         "email": {"type": "string", "format": "email"},
+//!         "nickname": {"type": ["string", "null"]}
+//!     },
+//!     "required": ["username", "email"]
+//! }));
+//! # }
+//! ```
+//!
+//! Named types, such as derived structs, are described once under `$defs` and
+//! referenced with `$ref`, so recursive types are supported.
+//!
+//! The same schemas can be published as the components of an OpenAPI
+//! document with [`openapi::Components`].
+//!
+//! The other direction, checking JSON documents against a schema written
+//! elsewhere, is covered by [`CompiledSchema`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde_json::{json, Map};
+
+use crate::error::ValidationError;
+use crate::rules::common::OneOf;
+use crate::rules::{LengthKind, Rule, RuleInfo};
+
+pub mod openapi;
+pub mod validate;
+
+pub use serde_json::Value;
+pub use validate::{CompiledSchema, SchemaError};
+
+/// The dialect of generated documents
+pub const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Keywords holding a single schema that applies to nested values
+const SUBSCHEMAS: [&str; 3] = ["items", "additionalProperties", "propertyNames"];
+
+/// Keywords setting a lower bound, of which the greatest value is the one that matters
+const LOWER_BOUNDS: [&str; 5] = ["minimum", "exclusiveMinimum", "minLength", "minItems", "minProperties"];
+
+/// Keywords setting an upper bound, of which the least value is the one that matters
+const UPPER_BOUNDS: [&str; 5] = ["maximum", "exclusiveMaximum", "maxLength", "maxItems", "maxProperties"];
+
+/// The JSON text of a value with integral numbers written as integers, so that
+/// values JSON Schema considers equal, such as `1` and `1.0`, have the same text
+///
+/// Object keys are already sorted, which makes the text canonical.
+pub(crate) fn canonical_text(value: &Value) -> String {
+    fn canonical(value: &Value) -> Value {
+        match value {
+            Value::Number(n) if n.is_f64() => match n.as_f64() {
+                Some(f) if f.fract() == 0.0 && f.abs() < i64::MAX as f64 => Value::from(f as i64),
+                _ => value.clone(),
+            },
+            Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+            Value::Object(members) => {
+                Value::Object(members.iter().map(|(key, member)| (key.clone(), canonical(member))).collect())
+            },
+            _ => value.clone(),
+        }
+    }
+    canonical(value).to_string()
+}
+
+/// [`OneOf`] on JSON values, compared by their canonical JSON text, so `1.0` is one of `[1, 2]`
+pub(crate) struct OneOfValues(pub OneOf<Value>);
+
+impl Rule<Value> for OneOfValues {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        let text = canonical_text(value);
+        if self.0.values.iter().any(|allowed| canonical_text(allowed) == text) {
+            return Ok(());
+        }
+        self.0.validate(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Rule::<Value>::json_schema(&self.0)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        Rule::<Value>::describe(&self.0)
+    }
+}
+
+/// The stricter of two values of a bound keyword, which together are equivalent to it
+fn stricter(keyword: &str, existing: &Value, value: &Value) -> Option<Value> {
+    let (a, b) = (existing.as_f64()?, value.as_f64()?);
+    if LOWER_BOUNDS.contains(&keyword) {
+        Some(if b > a { value.clone() } else { existing.clone() })
+    } else if UPPER_BOUNDS.contains(&keyword) {
+        Some(if b < a { value.clone() } else { existing.clone() })
+    } else {
+        None
+    }
+}
+
+/// JSON Schema keywords describing the constraints of a rule
+///
+/// Keywords apply to the schema of the checked value itself. Whether the
+/// value must be present is tracked separately, since JSON Schema expresses it
+/// on the enclosing object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Keywords {
+    keywords: Map<String, Value>,
+    required: bool,
+}
+
+impl Keywords {
+    /// Create keywords describing no constraint
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a keyword
+    pub fn with<V: Into<Value>>(mut self, keyword: &str, value: V) -> Self {
+        self.insert(keyword, value);
+        self
+    }
+
+    /// Add a keyword holding the schema of nested values, such as `items`, unless it describes nothing
+    pub fn with_subschema(self, keyword: &str, keywords: Keywords) -> Self {
+        if keywords.is_empty() {
+            return self;
+        }
+        self.with(keyword, keywords.into_value())
+    }
+
+    /// Mark the value as required in the enclosing object
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Let the value be left out, e.g. for a rule that only applies when it is present
+    pub fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// Add a keyword; of two bounds the stricter is kept, other conflicting values are kept as well, under `allOf`
+    pub fn insert<V: Into<Value>>(&mut self, keyword: &str, value: V) {
+        let value = value.into();
+        let Some(existing) = self.keywords.get_mut(keyword) else {
+            self.keywords.insert(keyword.to_string(), value);
+            return;
+        };
+        if *existing == value {
+            return;
+        }
+        if let Some(bound) = stricter(keyword, existing, &value) {
+            *existing = bound;
+            return;
+        }
+        if SUBSCHEMAS.contains(&keyword) {
+            if let Value::Object(nested) = value {
+                // Nested schemas, e.g. the `items` of an array, are refined rather than duplicated
+                *existing = Keywords { keywords: nested, required: false }.apply_to(existing.take());
+                return;
+            }
+        }
+        self.push_all_of(json!({ keyword: value }));
+    }
+
+    fn push_all_of(&mut self, schema: Value) {
+        match self.keywords.entry("allOf").or_insert_with(|| Value::Array(Vec::new())) {
+            Value::Array(schemas) => schemas.push(schema),
+            _ => unreachable!("allOf is always an array"),
+        }
+    }
+
+    /// Combine with the keywords of another rule that must also hold
+    pub fn merge(&mut self, other: Keywords) {
+        self.required |= other.required;
+        for (keyword, value) in other.keywords {
+            if keyword == "allOf" {
+                if let Value::Array(schemas) = value {
+                    schemas.into_iter().for_each(|schema| self.push_all_of(schema));
+                }
+            } else {
+                self.insert(&keyword, value);
+            }
+        }
+    }
+
+    /// Keywords requiring at least one of several descriptions to hold, as `anyOf`
+    pub fn any_of<I: IntoIterator<Item = Keywords>>(alternatives: I) -> Self {
+        let schemas: Vec<Value> = alternatives.into_iter().map(Keywords::into_value).collect();
+        if schemas.is_empty() {
+            // Without alternatives nothing is accepted, while an empty `anyOf` is not a valid schema
+            return Keywords::new().with("not", json!({}));
+        }
+        if schemas.iter().any(|schema| schema.as_object().is_some_and(Map::is_empty)) {
+            // One alternative accepts anything, so the alternatives do too
+            return Keywords::new();
+        }
+        Keywords::new().with("anyOf", schemas)
+    }
+
+    /// Keywords of the minimum length of a value, counted as `kind` says
+    pub fn min_length(kind: LengthKind, min: usize) -> Self {
+        let keyword = match kind {
+            LengthKind::Text => "minLength",
+            LengthKind::Items => "minItems",
+            LengthKind::Entries => "minProperties",
+        };
+        Keywords::new().with(keyword, min)
+    }
+
+    /// Keywords of the maximum length of a value, counted as `kind` says
+    pub fn max_length(kind: LengthKind, max: usize) -> Self {
+        let keyword = match kind {
+            LengthKind::Text => "maxLength",
+            LengthKind::Items => "maxItems",
+            LengthKind::Entries => "maxProperties",
+        };
+        Keywords::new().with(keyword, max)
+    }
+
+    /// Get a keyword
+    pub fn get(&self, keyword: &str) -> Option<&Value> {
+        self.keywords.get(keyword)
+    }
+
+    /// Check whether the value must be present
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Check whether no keyword was added
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// The keywords as a JSON Schema object
+    pub fn into_value(self) -> Value {
+        Value::Object(self.keywords)
+    }
+
+    /// Add the keywords to a schema; schemas that are not objects, such as a `$ref` to extend, are wrapped in `allOf`
+    pub fn apply_to(self, schema: Value) -> Value {
+        if self.is_empty() {
+            return schema;
+        }
+        match schema {
+            Value::Object(object) if !object.contains_key("$ref") => {
+                let mut keywords = Keywords { keywords: object, required: false };
+                keywords.merge(self);
+                keywords.into_value()
+            },
+            schema => json!({ "allOf": [schema, self.into_value()] }),
+        }
+    }
+
+    /// Combine the keywords of every rule of a field
+    pub fn from_rules<T: ?Sized, R: Rule<T>>(rules: &[R]) -> Self {
+        let mut keywords = Keywords::new();
+        for rule in rules {
+            keywords.merge(rule.json_schema());
+        }
+        keywords
+    }
+}
+
+/// Builds the schemas of types, collecting named ones under `$defs`
+#[derive(Debug, Default)]
+pub struct SchemaGenerator {
+    defs: Map<String, Value>,
+}
+
+impl SchemaGenerator {
+    /// Create a generator without definitions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The schema of a type used within another one, a `$ref` for named types
+    pub fn subschema<T: JsonSchema + ?Sized>(&mut self) -> Value {
+        let Some(name) = T::schema_name() else {
+            return T::json_schema(self);
+        };
+        if !self.defs.contains_key(&name) {
+            // Reserve the name first so recursive types refer to it instead of expanding forever
+            self.defs.insert(name.clone(), Value::Bool(true));
+            let schema = T::json_schema(self);
+            self.defs.insert(name.clone(), schema);
+        }
+        json!({ "$ref": format!("#/$defs/{}", name) })
+    }
+
+    /// The complete document describing a type
+    pub fn root<T: JsonSchema + ?Sized>(mut self) -> Value {
+        let schema = T::json_schema(&mut self);
+        let mut document = Map::new();
+        document.insert("$schema".to_string(), DRAFT_2020_12.into());
+        if let Some(name) = T::schema_name() {
+            document.insert("title".to_string(), name.into());
+        }
+        match schema {
+            Value::Object(object) => document.extend(object),
+            schema => {
+                document.insert("allOf".to_string(), json!([schema]));
+            },
+        }
+        if !self.defs.is_empty() {
+            document.insert("$defs".to_string(), Value::Object(self.defs));
+        }
+        Value::Object(document)
+    }
+
+    /// The definitions collected so far
+    pub fn definitions(&self) -> &Map<String, Value> {
+        &self.defs
+    }
+}
+
+/// Types that can describe their shape as JSON Schema
+///
+/// Implemented for primitives, strings, dates, options and collections, and by
+/// the derive macro for structs.
+pub trait JsonSchema {
+    /// The name under which the schema is shared in `$defs`; unnamed schemas are inlined
+    fn schema_name() -> Option<String> {
+        None
+    }
+
+    /// The schema of the type
+    fn json_schema(gen: &mut SchemaGenerator) -> Value;
+
+    /// Whether the value may be left out of an enclosing object, as an `Option` may
+    fn is_optional() -> bool {
+        false
+    }
+
+    /// The complete Draft 2020-12 document describing the type
+    fn schema() -> Value {
+        SchemaGenerator::new().root::<Self>()
+    }
+}
+
+macro_rules! impl_json_schema {
+    ($schema:tt => $($ty:ty),*) => {
+        $(impl JsonSchema for $ty {
+            fn json_schema(_: &mut SchemaGenerator) -> Value {
+                json!($schema)
+            }
+        })*
+    };
+}
+
+impl_json_schema!({ "type": "string" } => str, String, char);
+impl_json_schema!({ "type": "boolean" } => bool);
+impl_json_schema!({ "type": "integer" } => i8, i16, i32, i64, i128, isize);
+impl_json_schema!({ "type": "integer", "minimum": 0 } => u8, u16, u32, u64, u128, usize);
+impl_json_schema!({ "type": "number" } => f32, f64);
+impl_json_schema!({ "type": "string", "format": "date" } => NaiveDate);
+impl_json_schema!({ "type": "string", "format": "time" } => NaiveTime);
+impl_json_schema!({ "type": "string" } => NaiveDateTime);
+impl_json_schema!({ "type": "string", "format": "uuid" } => uuid::Uuid);
+impl_json_schema!({} => Value);
+
+impl<Tz: TimeZone> JsonSchema for DateTime<Tz> {
+    fn json_schema(_: &mut SchemaGenerator) -> Value {
+        json!({ "type": "string", "format": "date-time" })
+    }
+}
+
+impl<T: JsonSchema + ?Sized> JsonSchema for &T {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        T::json_schema(gen)
+    }
+}
+
+impl<T: JsonSchema + ?Sized> JsonSchema for Box<T> {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        T::json_schema(gen)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        let mut schema = gen.subschema::<T>();
+        // Widen a single type to accept null; anything else is combined with a null schema
+        if let Some(Value::String(ty)) = schema.get("type").cloned() {
+            schema["type"] = json!([ty, "null"]);
+            return schema;
+        }
+        json!({ "anyOf": [schema, { "type": "null" }] })
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+/// Schema of a sequence of `T`, with unique items for sets
+fn array_schema<T: JsonSchema>(gen: &mut SchemaGenerator, unique: bool) -> Value {
+    let mut schema = json!({ "type": "array", "items": gen.subschema::<T>() });
+    if unique {
+        schema["uniqueItems"] = Value::Bool(true);
+    }
+    schema
+}
+
+impl<T: JsonSchema> JsonSchema for [T] {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        array_schema::<T>(gen, false)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for Vec<T> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        array_schema::<T>(gen, false)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for VecDeque<T> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        array_schema::<T>(gen, false)
+    }
+}
+
+impl<T: JsonSchema, S> JsonSchema for HashSet<T, S> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        array_schema::<T>(gen, true)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for BTreeSet<T> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        array_schema::<T>(gen, true)
+    }
+}
+
+impl<K, V: JsonSchema, S> JsonSchema for HashMap<K, V, S> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        json!({ "type": "object", "additionalProperties": gen.subschema::<V>() })
+    }
+}
+
+impl<K, V: JsonSchema> JsonSchema for BTreeMap<K, V> {
+    fn json_schema(gen: &mut SchemaGenerator) -> Value {
+        json!({ "type": "object", "additionalProperties": gen.subschema::<V>() })
+    }
+}
+
+/// The schema of an object, assembled property by property
+///
+/// Used by the derive macro: a property is required when one of its rules
+/// requires it or its type cannot be left out, i.e. is not an `Option`.
+#[derive(Debug, Default)]
+pub struct ObjectSchema {
+    description: Option<String>,
+    properties: Map<String, Value>,
+    required: Vec<String>,
+}
+
+impl ObjectSchema {
+    /// Create an object without properties
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the description of the object
+    pub fn description<D: Into<String>>(&mut self, description: D) {
+        self.description = Some(description.into());
+    }
+
+    /// Add a property described by the schema of its type and the keywords of its rules
+    pub fn property(&mut self, name: &str, schema: Value, keywords: Keywords, optional: bool) {
+        if keywords.is_required() || !optional {
+            self.required.push(name.to_string());
+        }
+        self.properties.insert(name.to_string(), keywords.apply_to(schema));
+    }
+
+    /// Set the description of a property added before
+    pub fn describe_property<D: Into<String>>(&mut self, name: &str, description: D) {
+        let description = Value::String(description.into());
+        match self.properties.get_mut(name) {
+            Some(Value::Object(schema)) => {
+                schema.insert("description".to_string(), description);
+            },
+            Some(schema) => {
+                *schema = json!({ "description": description, "allOf": [schema.take()] });
+            },
+            None => {},
+        }
+    }
+
+    /// The schema of the object
+    pub fn into_value(self) -> Value {
+        let mut schema = Map::new();
+        if let Some(description) = self.description {
+            schema.insert("description".to_string(), description.into());
+        }
+        schema.insert("type".to_string(), "object".into());
+        schema.insert("properties".to_string(), Value::Object(self.properties));
+        if !self.required.is_empty() {
+            schema.insert("required".to_string(), self.required.into());
+        }
+        Value::Object(schema)
+    }
+}
+
+/// Support for the derive macro, which describes field types whether or not they implement [`JsonSchema`]
+#[doc(hidden)]
+pub mod __private {
+    use std::marker::PhantomData;
+
+    use serde_json::Value;
+
+    use super::{JsonSchema, SchemaGenerator};
+
+    pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+    impl<T: ?Sized> Probe<T> {
+        pub fn new() -> Self {
+            Probe(PhantomData)
+        }
+    }
+
+    impl<T: ?Sized> Default for Probe<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Picked by method resolution on `(&Probe::<T>::new())` when `T` implements [`JsonSchema`]
+    pub trait Described {
+        fn describe(&self, gen: &mut SchemaGenerator) -> Value;
+        fn optional(&self) -> bool;
+    }
+
+    impl<T: JsonSchema + ?Sized> Described for Probe<T> {
+        fn describe(&self, gen: &mut SchemaGenerator) -> Value {
+            gen.subschema::<T>()
+        }
+
+        fn optional(&self) -> bool {
+            T::is_optional()
+        }
+    }
+
+    /// Fallback for other types, found only after an extra autoref; they accept any value
+    pub trait Undescribed {
+        fn describe(&self, gen: &mut SchemaGenerator) -> Value;
+        fn optional(&self) -> bool;
+    }
+
+    impl<T: ?Sized> Undescribed for &Probe<T> {
+        fn describe(&self, _: &mut SchemaGenerator) -> Value {
+            Value::Object(Default::default())
+        }
+
+        fn optional(&self) -> bool {
+            false
+        }
+    }
+}
@@ -0,0 +1,313 @@
+//! OpenAPI schema components generated from validation rules
+//!
+//! The schemas of named types, such as derived structs, are collected into the
+//! `components/schemas` section of an OpenAPI document, with references
+//! pointing into it:
+//!
+//! ```
+//! # use rustvalidity::json_schema::openapi::{Components, OpenApiVersion};
+//! # use rustvalidity::json_schema::{JsonSchema, SchemaGenerator};
+//! # use serde_json::{json, Value};
+//! # macro_rules! named {
+//! #     ($($name:ident),*) => {$(
+//! #         struct $name;
+//! #         impl JsonSchema for $name {
+//! #             fn schema_name() -> Option<String> { Some(stringify!($name).to_string()) }
+//! #             fn json_schema(_: &mut SchemaGenerator) -> Value { json!({ "type": "object" }) }
+//! #         }
+//! #     )*};
+//! # }
+//! # named!(CreateUser, Order);
+//! let components = Components::new()
+//!     .with_version(OpenApiVersion::V3_0)
+//!     .schema::<CreateUser>()
+//!     .schema::<Order>();
+//!
+//! let mut spec = json!({ "openapi": "3.0.3", "paths": {} });
+//! components.merge_into(&mut spec);
+//! assert!(spec["components"]["schemas"]["Order"].is_object());
+//! ```
+//!
+//! OpenAPI 3.1 uses JSON Schema 2020-12, so its schemas are the exported ones.
+//! For OpenAPI 3.0 they are rewritten to its dialect: nullable types use
+//! `nullable`, exclusive bounds are flags on `minimum` and `maximum`, and
+//! keywords it lacks, such as `contains` or `if`, are left out.
+
+use serde_json::{json, Map, Value};
+
+use super::{JsonSchema, SchemaGenerator};
+
+/// Where component schemas are referenced from
+const COMPONENTS_REF: &str = "#/components/schemas/";
+
+/// Keywords holding a single nested schema
+const SUBSCHEMA: [&str; 11] = [
+    "items",
+    "additionalProperties",
+    "not",
+    "contains",
+    "propertyNames",
+    "if",
+    "then",
+    "else",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contentSchema",
+];
+
+/// Keywords holding a list of nested schemas
+const SUBSCHEMAS: [&str; 4] = ["allOf", "anyOf", "oneOf", "prefixItems"];
+
+/// Keywords holding nested schemas by name
+const SUBSCHEMA_MAPS: [&str; 3] = ["properties", "patternProperties", "dependentSchemas"];
+
+/// JSON Schema keywords without an OpenAPI 3.0 equivalent
+const UNSUPPORTED_3_0: [&str; 14] = [
+    "contains",
+    "contentMediaType",
+    "contentSchema",
+    "propertyNames",
+    "prefixItems",
+    "if",
+    "then",
+    "else",
+    "patternProperties",
+    "dependentSchemas",
+    "dependentRequired",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "$defs",
+];
+
+/// The OpenAPI version whose schema dialect is generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenApiVersion {
+    /// OpenAPI 3.0, with its own subset of JSON Schema
+    V3_0,
+    /// OpenAPI 3.1, which uses JSON Schema 2020-12
+    #[default]
+    V3_1,
+}
+
+/// The `components` section of an OpenAPI document
+#[derive(Debug, Clone, Default)]
+pub struct Components {
+    version: OpenApiVersion,
+    schemas: Map<String, Value>,
+}
+
+impl Components {
+    /// Create components without schemas, for OpenAPI 3.1
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the OpenAPI version whose dialect schemas are written in
+    pub fn with_version(mut self, version: OpenApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Add the schema of a type and of the named types it refers to
+    ///
+    /// Types without a name, such as `Vec<User>`, only contribute the named
+    /// types they refer to.
+    pub fn schema<T: JsonSchema + ?Sized>(mut self) -> Self {
+        let mut gen = SchemaGenerator::new();
+        gen.subschema::<T>();
+        for (name, schema) in gen.definitions() {
+            let schema = self.convert(schema.clone());
+            self.schemas.insert(name.clone(), schema);
+        }
+        self
+    }
+
+    /// The component schemas by name
+    pub fn schemas(&self) -> &Map<String, Value> {
+        &self.schemas
+    }
+
+    /// A reference to the component schema of a type, e.g. for a request body
+    pub fn reference<T: JsonSchema + ?Sized>() -> Option<Value> {
+        T::schema_name().map(|name| json!({ "$ref": format!("{}{}", COMPONENTS_REF, name) }))
+    }
+
+    /// The section as a JSON object, `{"schemas": {...}}`
+    pub fn into_value(self) -> Value {
+        json!({ "schemas": self.schemas })
+    }
+
+    /// Add the schemas to the `components` of an OpenAPI document, replacing schemas of the same name
+    pub fn merge_into(self, spec: &mut Value) {
+        let Value::Object(spec) = spec else {
+            return;
+        };
+        let components = spec.entry("components").or_insert_with(|| json!({}));
+        if !components.is_object() {
+            *components = json!({});
+        }
+        let schemas = components
+            .as_object_mut()
+            .expect("components is an object")
+            .entry("schemas")
+            .or_insert_with(|| json!({}));
+        match schemas {
+            Value::Object(schemas) => schemas.extend(self.schemas),
+            schemas => *schemas = Value::Object(self.schemas),
+        }
+    }
+
+    /// Rewrite an exported schema in the dialect of the OpenAPI version
+    fn convert(&self, schema: Value) -> Value {
+        let Value::Object(mut object) = schema else {
+            return schema;
+        };
+
+        if let Some(Value::String(reference)) = object.get_mut("$ref") {
+            if let Some(name) = reference.strip_prefix("#/$defs/") {
+                *reference = format!("{}{}", COMPONENTS_REF, name);
+            }
+        }
+        for keyword in SUBSCHEMA_MAPS {
+            if let Some(Value::Object(nested)) = object.get_mut(keyword) {
+                for schema in nested.values_mut() {
+                    *schema = self.convert(schema.take());
+                }
+            }
+        }
+        for keyword in SUBSCHEMA {
+            if let Some(nested) = object.get_mut(keyword) {
+                *nested = self.convert(nested.take());
+            }
+        }
+        for keyword in SUBSCHEMAS {
+            if let Some(Value::Array(nested)) = object.get_mut(keyword) {
+                for schema in nested.iter_mut() {
+                    *schema = self.convert(schema.take());
+                }
+            }
+        }
+
+        if self.version == OpenApiVersion::V3_0 {
+            to_3_0(&mut object);
+        }
+        Value::Object(object)
+    }
+}
+
+/// Rewrite the keywords of a schema, whose nested schemas are already rewritten, for OpenAPI 3.0
+fn to_3_0(object: &mut Map<String, Value>) {
+    for keyword in UNSUPPORTED_3_0 {
+        object.remove(keyword);
+    }
+
+    // `["string", "null"]` is a nullable string
+    if let Some(Value::Array(types)) = object.get("type") {
+        let types: Vec<Value> = types.iter().filter(|ty| *ty != "null").cloned().collect();
+        if types.len() == 1 {
+            object.insert("type".to_string(), types[0].clone());
+            object.insert("nullable".to_string(), Value::Bool(true));
+        }
+    }
+
+    // `anyOf` a schema or null, as generated for an `Option` of a named type
+    if let Some(Value::Array(alternatives)) = object.get("anyOf") {
+        let null = json!({ "type": "null" });
+        if alternatives.len() == 2 && alternatives.contains(&null) {
+            let schema = alternatives.iter().find(|schema| **schema != null).cloned();
+            object.remove("anyOf");
+            object.insert("allOf".to_string(), json!([schema]));
+            object.insert("nullable".to_string(), Value::Bool(true));
+        }
+    }
+
+    if let Some(value) = object.remove("const") {
+        object.insert("enum".to_string(), json!([value]));
+    }
+
+    exclusive_bound(object, "minimum", "exclusiveMinimum", |a, b| a >= b);
+    exclusive_bound(object, "maximum", "exclusiveMaximum", |a, b| a <= b);
+}
+
+/// Turn a numeric exclusive bound into the inclusive keyword with a flag, keeping the stricter of both bounds
+fn exclusive_bound(object: &mut Map<String, Value>, inclusive: &str, exclusive: &str, stricter: fn(f64, f64) -> bool) {
+    let Some(bound) = object.get(exclusive).filter(|bound| bound.is_number()).cloned() else {
+        return;
+    };
+    object.remove(exclusive);
+    let keep_inclusive = match object.get(inclusive).and_then(Value::as_f64) {
+        Some(existing) => bound.as_f64().is_some_and(|bound| !stricter(bound, existing)),
+        None => false,
+    };
+    if !keep_inclusive {
+        object.insert(inclusive.to_string(), bound);
+        object.insert(exclusive.to_string(), Value::Bool(true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Leaf;
+
+    impl JsonSchema for Leaf {
+        fn schema_name() -> Option<String> {
+            Some("Leaf".to_string())
+        }
+
+        fn json_schema(_: &mut SchemaGenerator) -> Value {
+            json!({ "type": "string" })
+        }
+    }
+
+    /// Refers to `Leaf` from every keyword holding a subschema
+    struct Tree;
+
+    impl JsonSchema for Tree {
+        fn schema_name() -> Option<String> {
+            Some("Tree".to_string())
+        }
+
+        fn json_schema(gen: &mut SchemaGenerator) -> Value {
+            let leaf = gen.subschema::<Leaf>();
+            let tree = gen.subschema::<Tree>();
+            json!({
+                "type": "object",
+                "properties": { "leaf": leaf, "children": { "type": "array", "items": tree } },
+                "patternProperties": { "^x-": leaf },
+                "dependentSchemas": { "leaf": { "properties": { "other": leaf } } },
+                "additionalProperties": leaf,
+                "propertyNames": leaf,
+                "unevaluatedProperties": leaf,
+                "if": leaf,
+                "then": leaf,
+                "else": { "not": leaf },
+                "allOf": [leaf],
+                "anyOf": [leaf, { "type": "null" }],
+                "oneOf": [{ "prefixItems": [leaf], "contains": leaf, "unevaluatedItems": leaf }],
+                "contentSchema": leaf,
+            })
+        }
+    }
+
+    #[test]
+    fn every_reference_points_into_the_components() {
+        for version in [OpenApiVersion::V3_0, OpenApiVersion::V3_1] {
+            let value = Components::new().with_version(version).schema::<Tree>().into_value();
+            let text = value.to_string();
+            assert!(!text.contains("#/$defs/"), "{:?}: {}", version, text);
+            assert!(text.contains("#/components/schemas/Leaf"));
+        }
+    }
+
+    #[test]
+    fn version_3_0_leaves_out_keywords_it_lacks() {
+        let components = Components::new().with_version(OpenApiVersion::V3_0).schema::<Tree>();
+        let tree = &components.schemas()["Tree"];
+        for keyword in ["patternProperties", "dependentSchemas", "if", "then", "else", "propertyNames"] {
+            assert!(tree.get(keyword).is_none(), "{} is left in", keyword);
+        }
+        assert_eq!(tree["properties"]["children"]["items"], json!({ "$ref": "#/components/schemas/Tree" }));
+    }
+}
@@ -0,0 +1,825 @@
+//! Validation of JSON documents against a JSON Schema
+//!
+//! A [`CompiledSchema`] is built once from a schema document and checks any
+//! number of [`Value`]s. Keywords are compiled into the crate's rules, e.g.
+//! `"format": "email"` into [`Email`] and `"minimum"` into [`Min`], so
+//! violations carry the same codes and parameters as everywhere else:
+//!
+//! ```
+//! # use rustvalidity::json_schema::CompiledSchema;
+//! # use serde_json::json;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join("rustvalidity-json-schema-doc");
+//! # std::fs::create_dir_all(&dir)?;
+//! # std::fs::write(dir.join("order.json"), r#"{"properties": {"items": {"items": {"properties": {"quantity": {"minimum": 1}}}}}}"#)?;
+//! let schema = CompiledSchema::from_file(dir.join("order.json"))?;
+//! let payload = json!({ "items": [{ "sku": "A-1", "quantity": 0 }] });
+//! if let Err(err) = schema.validate(&payload) {
+//!     for entry in err.entries() {
+//!         println!("{}: {}", entry.pointer, entry.violation.message); // /items/0/quantity: ...
+//!     }
+//! }
+//! # assert_eq!(schema.validate(&payload).unwrap_err().entries()[0].pointer, "/items/0/quantity");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The assertion keywords of Draft 2020-12 are supported, with `$ref`
+//! resolved within the document. Annotations and unknown formats are ignored,
+//! as the specification requires; the known formats are `email`, `uri`,
+//! `uuid`, `date`, `ipv4` and `ipv6`. Keywords that cannot be honoured, such as
+//! `unevaluatedProperties`, are rejected when compiling rather than letting
+//! every document through.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+use crate::error::{ErrorTree, ValidationError, Violation};
+use crate::json_schema::{canonical_text, OneOfValues};
+use crate::path::FieldPath;
+use crate::rules::advanced::{RegexRule, IP};
+use crate::rules::collection::{MaxSize, MinSize, Unique};
+use crate::rules::common::{Custom, Date, Email, Json, Length, OneOf, Required, UrlRule, UuidRule};
+use crate::rules::numeric::{DivisibleBy, Max, Min};
+use crate::rules::{downcast_str, invalid_type, Rule, RuleInfo, INVALID_TYPE};
+
+/// Keywords the compiler cannot honour; schemas using them are rejected
+const UNSUPPORTED: [&str; 4] = ["unevaluatedItems", "unevaluatedProperties", "$dynamicRef", "$recursiveRef"];
+
+/// Values of the `type` keyword
+const TYPES: [&str; 7] = ["null", "boolean", "object", "array", "number", "integer", "string"];
+
+/// Errors raised while loading and compiling a schema
+///
+/// Locations are JSON Pointers into the schema document, written as URI
+/// fragments, e.g. `#/properties/age/minimum`.
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Failed to read schema {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid schema document: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("Invalid schema at {location}: {message}")]
+    Invalid {
+        location: String,
+        message: String,
+    },
+
+    #[error("Unsupported keyword '{keyword}' at {location}")]
+    Unsupported {
+        location: String,
+        keyword: String,
+    },
+
+    #[error("Unresolvable reference '{reference}' at {location}")]
+    Reference {
+        location: String,
+        reference: String,
+    },
+}
+
+/// A JSON number compared by value, so that `1` and `1.0` are equal, and printed as written
+#[derive(Clone)]
+struct JsonNumber(Number);
+
+impl PartialEq for JsonNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for JsonNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Some(a), Some(b)) = (self.0.as_i64(), other.0.as_i64()) {
+            return Some(a.cmp(&b));
+        }
+        if let (Some(a), Some(b)) = (self.0.as_u64(), other.0.as_u64()) {
+            return Some(a.cmp(&b));
+        }
+        self.0.as_f64()?.partial_cmp(&other.0.as_f64()?)
+    }
+}
+
+impl fmt::Debug for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for JsonNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+type NodeId = usize;
+
+/// The compiled form of a single schema
+#[derive(Default)]
+struct Node {
+    /// The `false` schema, which no value matches
+    reject: bool,
+    types: Vec<&'static str>,
+    values: Vec<Box<dyn Rule<Value>>>,
+    strings: Vec<Box<dyn Rule<String>>>,
+    numbers: Vec<Box<dyn Rule<JsonNumber>>>,
+    arrays: Vec<Box<dyn Rule<Vec<Value>>>>,
+    objects: Vec<Box<dyn Rule<Map<String, Value>>>>,
+    prefix_items: Vec<NodeId>,
+    items: Option<NodeId>,
+    contains: Option<NodeId>,
+    min_contains: Option<usize>,
+    max_contains: Option<usize>,
+    required: Vec<String>,
+    dependent_required: Vec<(String, Vec<String>)>,
+    dependent_schemas: Vec<(String, NodeId)>,
+    properties: BTreeMap<String, NodeId>,
+    pattern_properties: Vec<(RegexRule, NodeId)>,
+    additional_properties: Option<NodeId>,
+    property_names: Option<NodeId>,
+    all_of: Vec<NodeId>,
+    any_of: Vec<NodeId>,
+    one_of: Vec<NodeId>,
+    not: Option<NodeId>,
+    condition: Option<NodeId>,
+    then: Option<NodeId>,
+    otherwise: Option<NodeId>,
+}
+
+/// A JSON Schema compiled into rules, ready to validate documents
+///
+/// Violations are reported at the location of the offending value in the
+/// document; [`ValidationError::entries`] gives each one with its JSON
+/// Pointer. Also usable as a rule on [`Value`]s and on strings holding JSON.
+pub struct CompiledSchema {
+    nodes: Vec<Node>,
+}
+
+impl CompiledSchema {
+    /// Compile a schema
+    pub fn compile(schema: &Value) -> Result<Self, SchemaError> {
+        let mut compiler = Compiler {
+            root: schema,
+            nodes: Vec::new(),
+            references: HashMap::from([("#".to_string(), 0)]),
+        };
+        compiler.compile(schema, "#")?;
+        Ok(CompiledSchema { nodes: compiler.nodes })
+    }
+
+    /// Parse and compile a schema from JSON
+    pub fn from_json(json: &str) -> Result<Self, SchemaError> {
+        Self::compile(&serde_json::from_str(json)?)
+    }
+
+    /// Read and compile a schema from a JSON file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SchemaError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|source| SchemaError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Validate a document, reporting every violation
+    pub fn validate(&self, document: &Value) -> Result<(), ValidationError> {
+        let mut errors = ErrorTree::new();
+        self.check(0, document, &FieldPath::root(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into())
+        }
+    }
+
+    /// Check whether a document matches the schema
+    pub fn is_valid(&self, document: &Value) -> bool {
+        self.matches(0, document)
+    }
+
+    fn matches(&self, id: NodeId, value: &Value) -> bool {
+        let mut errors = ErrorTree::new();
+        self.check(id, value, &FieldPath::root(), &mut errors);
+        errors.is_empty()
+    }
+
+    fn check(&self, id: NodeId, value: &Value, path: &FieldPath, errors: &mut ErrorTree) {
+        let node = &self.nodes[id];
+        if node.reject {
+            errors.add(path.clone(), Violation::new("not_allowed", "Value is not allowed"));
+            return;
+        }
+        if !node.types.is_empty() && !node.types.iter().any(|name| has_type(value, name)) {
+            let expected = node.types.join(" or ");
+            let message = format!("Value must be of type {}", expected);
+            errors.add(path.clone(), Violation::new(INVALID_TYPE, message)
+                .with_param("expected", &expected)
+                .with_param("actual", type_of(value)));
+            return;
+        }
+
+        apply(&node.values, value, path, errors);
+        match value {
+            Value::String(s) => apply(&node.strings, s, path, errors),
+            Value::Number(n) => apply(&node.numbers, &JsonNumber(n.clone()), path, errors),
+            Value::Array(items) => self.check_array(node, items, path, errors),
+            Value::Object(members) => self.check_object(node, value, members, path, errors),
+            _ => {},
+        }
+
+        for &schema in &node.all_of {
+            self.check(schema, value, path, errors);
+        }
+        if !node.any_of.is_empty() && !node.any_of.iter().any(|&schema| self.matches(schema, value)) {
+            errors.add(path.clone(), Violation::new("any_of", "Value must match at least one of the allowed schemas")
+                .with_param("schemas", &node.any_of.len()));
+        }
+        if !node.one_of.is_empty() {
+            let matched = node.one_of.iter().filter(|&&schema| self.matches(schema, value)).count();
+            if matched != 1 {
+                errors.add(path.clone(), Violation::new("one_of_schemas", format!(
+                    "Value must match exactly one of the allowed schemas, but matches {}", matched
+                ))
+                .with_param("schemas", &node.one_of.len())
+                .with_param("matched", &matched));
+            }
+        }
+        if let Some(schema) = node.not {
+            if self.matches(schema, value) {
+                errors.add(path.clone(), Violation::new("not", "Value must not match the schema"));
+            }
+        }
+        if let Some(condition) = node.condition {
+            let branch = if self.matches(condition, value) { node.then } else { node.otherwise };
+            if let Some(schema) = branch {
+                self.check(schema, value, path, errors);
+            }
+        }
+    }
+
+    fn check_array(&self, node: &Node, items: &Vec<Value>, path: &FieldPath, errors: &mut ErrorTree) {
+        apply(&node.arrays, items, path, errors);
+        for (index, item) in items.iter().enumerate() {
+            // `items` covers what `prefixItems` does not
+            if let Some(schema) = node.prefix_items.get(index).copied().or(node.items) {
+                self.check(schema, item, &path.index(index), errors);
+            }
+        }
+
+        let Some(schema) = node.contains else {
+            return;
+        };
+        let matched = items.iter().filter(|item| self.matches(schema, item)).count();
+        let min = node.min_contains.unwrap_or(1);
+        if matched < min {
+            errors.add(path.clone(), Violation::new("contains", format!(
+                "Collection must contain at least {} matching items", min
+            ))
+            .with_param("min", &min)
+            .with_param("actual", &matched));
+        }
+        if let Some(max) = node.max_contains.filter(|&max| matched > max) {
+            errors.add(path.clone(), Violation::new("contains.max", format!(
+                "Collection must contain at most {} matching items", max
+            ))
+            .with_param("max", &max)
+            .with_param("actual", &matched));
+        }
+    }
+
+    fn check_object(&self, node: &Node, value: &Value, members: &Map<String, Value>, path: &FieldPath, errors: &mut ErrorTree) {
+        apply(&node.objects, members, path, errors);
+        for name in &node.required {
+            if let Err(err) = Rule::<Option<&Value>>::validate(&Required, &members.get(name)) {
+                errors.add_error(path.field(name.as_str()), err);
+            }
+        }
+        for (name, dependencies) in node.dependent_required.iter().filter(|(name, _)| members.contains_key(name)) {
+            for dependency in dependencies.iter().filter(|dependency| !members.contains_key(*dependency)) {
+                errors.add(path.field(dependency.as_str()), Violation::new("required", format!(
+                    "Value is required when {} is present", name
+                ))
+                .with_param("field", name));
+            }
+        }
+        for (name, schema) in &node.dependent_schemas {
+            if members.contains_key(name) {
+                self.check(*schema, value, path, errors);
+            }
+        }
+
+        for (name, member) in members {
+            let member_path = path.field(name.as_str());
+            let mut evaluated = false;
+            if let Some(&schema) = node.properties.get(name) {
+                evaluated = true;
+                self.check(schema, member, &member_path, errors);
+            }
+            for (pattern, schema) in &node.pattern_properties {
+                if pattern.regex.is_match(name) {
+                    evaluated = true;
+                    self.check(*schema, member, &member_path, errors);
+                }
+            }
+            if let Some(schema) = node.additional_properties.filter(|_| !evaluated) {
+                self.check(schema, member, &member_path, errors);
+            }
+            if let Some(schema) = node.property_names {
+                self.check(schema, &Value::String(name.clone()), &member_path, errors);
+            }
+        }
+    }
+}
+
+/// Run rules on a value, recording their violations at its path
+fn apply<T: ?Sized>(rules: &[Box<dyn Rule<T>>], value: &T, path: &FieldPath, errors: &mut ErrorTree) {
+    for rule in rules {
+        if let Err(err) = rule.validate(value) {
+            errors.add_error(path.clone(), err);
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match (name, value) {
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|n| n.fract() == 0.0),
+        _ => type_of(value) == name,
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl Rule<Value> for CompiledSchema {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        CompiledSchema::validate(self, value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("json_schema", "Value must match the JSON Schema")
+    }
+}
+
+/// Strings are parsed as JSON first, reporting syntax errors like [`Json`]
+impl Rule<str> for CompiledSchema {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        match serde_json::from_str::<Value>(value) {
+            Ok(document) => CompiledSchema::validate(self, &document),
+            Err(_) => Rule::<str>::validate(&Json, value),
+        }
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("json_schema", "Value must match the JSON Schema")
+    }
+}
+
+impl Rule<String> for CompiledSchema {
+    fn validate(&self, value: &String) -> Result<(), ValidationError> {
+        Rule::<str>::validate(self, value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("json_schema", "Value must match the JSON Schema")
+    }
+}
+
+impl Rule for CompiledSchema {
+    fn validate(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+        if let Some(document) = value.downcast_ref::<Value>() {
+            CompiledSchema::validate(self, document)
+        } else if let Some(s) = downcast_str(value) {
+            Rule::<str>::validate(self, s)
+        } else {
+            Err(invalid_type("Value must be a JSON value or string", "JSON value or string"))
+        }
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("json_schema", "Value must match the JSON Schema")
+    }
+}
+
+/// Compiles schemas into nodes, resolving each reference once so that recursive schemas terminate
+struct Compiler<'a> {
+    root: &'a Value,
+    nodes: Vec<Node>,
+    references: HashMap<String, NodeId>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(&mut self, schema: &'a Value, location: &str) -> Result<NodeId, SchemaError> {
+        let id = self.reserve();
+        self.nodes[id] = self.node(schema, location)?;
+        Ok(id)
+    }
+
+    fn reserve(&mut self) -> NodeId {
+        self.nodes.push(Node::default());
+        self.nodes.len() - 1
+    }
+
+    fn reference(&mut self, reference: &str, location: &str) -> Result<NodeId, SchemaError> {
+        if let Some(&id) = self.references.get(reference) {
+            return Ok(id);
+        }
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| self.root.pointer(pointer))
+            .ok_or_else(|| SchemaError::Reference {
+                location: location.to_string(),
+                reference: reference.to_string(),
+            })?;
+
+        let id = self.reserve();
+        self.references.insert(reference.to_string(), id);
+        self.nodes[id] = self.node(target, reference)?;
+        Ok(id)
+    }
+
+    fn node(&mut self, schema: &'a Value, location: &str) -> Result<Node, SchemaError> {
+        let keywords = match schema {
+            Value::Bool(accept) => return Ok(Node { reject: !accept, ..Node::default() }),
+            Value::Object(keywords) => keywords,
+            _ => return Err(invalid(location, "a schema must be an object or a boolean")),
+        };
+        if let Some(keyword) = UNSUPPORTED.iter().find(|keyword| keywords.contains_key(**keyword)) {
+            return Err(SchemaError::Unsupported {
+                location: location.to_string(),
+                keyword: keyword.to_string(),
+            });
+        }
+
+        let mut node = Node::default();
+        for (keyword, value) in keywords {
+            let at = &child(location, keyword);
+            match keyword.as_str() {
+                "type" => node.types = types(value, at)?,
+                "enum" => node.values.push(Box::new(OneOfValues(OneOf { values: array(value, at)?.clone() }))),
+                "const" => node.values.push(Box::new(OneOfValues(OneOf { values: vec![value.clone()] }))),
+
+                "minLength" => node.strings.push(Box::new(Length { min: count(value, at)?, max: None })),
+                "maxLength" => node.strings.push(Box::new(Length { min: 0, max: Some(count(value, at)?) })),
+                "pattern" => node.strings.push(Box::new(pattern(value, at)?)),
+                "format" => node.strings.extend(format(string(value, at)?)),
+
+                "minimum" => node.numbers.push(Box::new(Min { value: number(value, at)? })),
+                "maximum" => node.numbers.push(Box::new(Max { value: number(value, at)? })),
+                "exclusiveMinimum" => node.numbers.push(exclusive_min(number(value, at)?)),
+                "exclusiveMaximum" => node.numbers.push(exclusive_max(number(value, at)?)),
+                "multipleOf" => {
+                    let divisor = number(value, at)?;
+                    if divisor <= JsonNumber(0.into()) {
+                        return Err(invalid(at, "expected a number greater than 0"));
+                    }
+                    node.numbers.push(multiple_of(divisor));
+                },
+
+                "minItems" => node.arrays.push(Box::new(MinSize { min: count(value, at)? })),
+                "maxItems" => node.arrays.push(Box::new(MaxSize { max: count(value, at)? })),
+                "uniqueItems" => {
+                    let unique = value.as_bool().ok_or_else(|| invalid(at, "expected a boolean"))?;
+                    if unique {
+                        node.arrays.push(unique_items());
+                    }
+                },
+                "prefixItems" => node.prefix_items = self.schemas(value, at)?,
+                "items" => node.items = Some(self.compile(value, at)?),
+                "contains" => node.contains = Some(self.compile(value, at)?),
+                "minContains" => node.min_contains = Some(count(value, at)?),
+                "maxContains" => node.max_contains = Some(count(value, at)?),
+
+                "minProperties" => node.objects.push(Box::new(MinSize { min: count(value, at)? })),
+                "maxProperties" => node.objects.push(Box::new(MaxSize { max: count(value, at)? })),
+                "required" => node.required = strings(value, at)?,
+                "dependentRequired" => {
+                    for (name, dependencies) in object(value, at)? {
+                        node.dependent_required.push((name.clone(), strings(dependencies, &child(at, name))?));
+                    }
+                },
+                "dependentSchemas" => {
+                    for (name, schema) in object(value, at)? {
+                        node.dependent_schemas.push((name.clone(), self.compile(schema, &child(at, name))?));
+                    }
+                },
+                "properties" => {
+                    for (name, schema) in object(value, at)? {
+                        node.properties.insert(name.clone(), self.compile(schema, &child(at, name))?);
+                    }
+                },
+                "patternProperties" => {
+                    for (source, schema) in object(value, at)? {
+                        let regex = RegexRule::new(source)
+                            .map_err(|_| invalid(at, format!("invalid pattern '{}'", source)))?;
+                        node.pattern_properties.push((regex, self.compile(schema, &child(at, source))?));
+                    }
+                },
+                "additionalProperties" => node.additional_properties = Some(self.compile(value, at)?),
+                "propertyNames" => node.property_names = Some(self.compile(value, at)?),
+
+                "$ref" => {
+                    let reference = self.reference(string(value, at)?, at)?;
+                    node.all_of.push(reference);
+                },
+                "allOf" => node.all_of.extend(self.schemas(value, at)?),
+                "anyOf" => node.any_of = self.schemas(value, at)?,
+                "oneOf" => node.one_of = self.schemas(value, at)?,
+                "not" => node.not = Some(self.compile(value, at)?),
+                "if" => node.condition = Some(self.compile(value, at)?),
+                "then" => node.then = Some(self.compile(value, at)?),
+                "else" => node.otherwise = Some(self.compile(value, at)?),
+
+                // Annotations, `$defs` (compiled when referenced) and unknown keywords
+                _ => {},
+            }
+        }
+        Ok(node)
+    }
+
+    fn schemas(&mut self, value: &'a Value, location: &str) -> Result<Vec<NodeId>, SchemaError> {
+        let schemas = array(value, location)?;
+        if schemas.is_empty() {
+            return Err(invalid(location, "expected at least one schema"));
+        }
+        schemas
+            .iter()
+            .enumerate()
+            .map(|(index, schema)| self.compile(schema, &child(location, &index.to_string())))
+            .collect()
+    }
+}
+
+fn child(location: &str, token: &str) -> String {
+    format!("{}/{}", location, token.replace('~', "~0").replace('/', "~1"))
+}
+
+fn invalid<M: Into<String>>(location: &str, message: M) -> SchemaError {
+    SchemaError::Invalid {
+        location: location.to_string(),
+        message: message.into(),
+    }
+}
+
+fn string<'v>(value: &'v Value, location: &str) -> Result<&'v str, SchemaError> {
+    value.as_str().ok_or_else(|| invalid(location, "expected a string"))
+}
+
+fn strings(value: &Value, location: &str) -> Result<Vec<String>, SchemaError> {
+    array(value, location)?
+        .iter()
+        .map(|item| string(item, location).map(str::to_string))
+        .collect()
+}
+
+fn array<'v>(value: &'v Value, location: &str) -> Result<&'v Vec<Value>, SchemaError> {
+    value.as_array().ok_or_else(|| invalid(location, "expected an array"))
+}
+
+fn object<'v>(value: &'v Value, location: &str) -> Result<&'v Map<String, Value>, SchemaError> {
+    value.as_object().ok_or_else(|| invalid(location, "expected an object"))
+}
+
+fn count(value: &Value, location: &str) -> Result<usize, SchemaError> {
+    value
+        .as_u64()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| invalid(location, "expected a non-negative integer"))
+}
+
+fn number(value: &Value, location: &str) -> Result<JsonNumber, SchemaError> {
+    match value {
+        Value::Number(n) => Ok(JsonNumber(n.clone())),
+        _ => Err(invalid(location, "expected a number")),
+    }
+}
+
+fn types(value: &Value, location: &str) -> Result<Vec<&'static str>, SchemaError> {
+    let names = match value {
+        Value::String(name) => vec![name.clone()],
+        _ => strings(value, location)?,
+    };
+    names
+        .iter()
+        .map(|name| {
+            TYPES
+                .iter()
+                .find(|known| *known == name)
+                .copied()
+                .ok_or_else(|| invalid(location, format!("unknown type '{}'", name)))
+        })
+        .collect()
+}
+
+fn pattern(value: &Value, location: &str) -> Result<RegexRule, SchemaError> {
+    let source = string(value, location)?;
+    RegexRule::new(source).map_err(|_| invalid(location, format!("invalid pattern '{}'", source)))
+}
+
+/// The rule checking a format, or `None` for formats that are only annotations
+fn format(name: &str) -> Option<Box<dyn Rule<String>>> {
+    Some(match name {
+        "email" => Box::new(Email { check_dns: false }),
+        "uri" => Box::new(UrlRule { allowed_schemes: None }),
+        "uuid" => Box::new(UuidRule),
+        "date" => Box::new(Date { format: "%Y-%m-%d".to_string(), min: None, max: None }),
+        "ipv4" => Box::new(IP { allow_v4: true, allow_v6: false }),
+        "ipv6" => Box::new(IP { allow_v4: false, allow_v6: true }),
+        _ => return None,
+    })
+}
+
+fn exclusive_min(min: JsonNumber) -> Box<dyn Rule<JsonNumber>> {
+    Box::new(Custom {
+        validator: move |n: &JsonNumber| {
+            if *n > min {
+                return Ok(());
+            }
+            Err(Violation::new("exclusive_min", format!("Value must be greater than {:?}", min))
+                .with_param("min", &min)
+                .with_value(n)
+                .into())
+        },
+    })
+}
+
+fn exclusive_max(max: JsonNumber) -> Box<dyn Rule<JsonNumber>> {
+    Box::new(Custom {
+        validator: move |n: &JsonNumber| {
+            if *n < max {
+                return Ok(());
+            }
+            Err(Violation::new("exclusive_max", format!("Value must be less than {:?}", max))
+                .with_param("max", &max)
+                .with_value(n)
+                .into())
+        },
+    })
+}
+
+fn multiple_of(divisor: JsonNumber) -> Box<dyn Rule<JsonNumber>> {
+    Box::new(Custom {
+        validator: move |n: &JsonNumber| {
+            if let (Some(value), Some(divisor)) = (n.0.as_i64(), divisor.0.as_i64()) {
+                return Rule::<i64>::validate(&DivisibleBy { divisor }, &value);
+            }
+            // Decimal divisors such as 0.01 are not exact in binary, so allow for rounding
+            let quotient = n.0.as_f64().unwrap_or_default() / divisor.0.as_f64().unwrap_or(1.0);
+            if (quotient - quotient.round()).abs() <= f64::EPSILON * quotient.abs().max(1.0) {
+                return Ok(());
+            }
+            Err(Violation::new("divisible_by", format!("Value must be divisible by {:?}", divisor))
+                .with_param("divisor", &divisor)
+                .with_value(n)
+                .into())
+        },
+    })
+}
+
+/// Items compared by their canonical JSON text, so `1` and `1.0` are duplicates
+fn unique_items() -> Box<dyn Rule<Vec<Value>>> {
+    Box::new(Custom {
+        validator: |items: &Vec<Value>| {
+            let items: Vec<String> = items.iter().map(canonical_text).collect();
+            Rule::<Vec<String>>::validate(&Unique, &items)
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pointers(schema: &CompiledSchema, document: Value) -> Vec<String> {
+        match schema.validate(&document) {
+            Ok(()) => Vec::new(),
+            Err(err) => err.entries().into_iter().map(|entry| entry.pointer).collect(),
+        }
+    }
+
+    #[test]
+    fn recursive_references_are_followed() {
+        let schema = CompiledSchema::compile(&json!({
+            "$ref": "#/$defs/node",
+            "$defs": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": { "type": "integer" },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/node" } },
+                    },
+                },
+            },
+        }))
+        .unwrap();
+
+        let tree = json!({ "value": 1, "children": [{ "value": 2, "children": [{ "value": "three" }] }] });
+        assert_eq!(pointers(&schema, tree), ["/children/0/children/0/value"]);
+        assert!(schema.is_valid(&json!({ "value": 1, "children": [{ "value": 2, "children": [] }] })));
+    }
+
+    #[test]
+    fn unresolvable_references_are_rejected() {
+        let err = CompiledSchema::compile(&json!({ "items": { "$ref": "#/$defs/missing" } })).err().unwrap();
+        assert!(matches!(err, SchemaError::Reference { ref location, .. } if location == "#/items/$ref"));
+    }
+
+    #[test]
+    fn known_formats_are_checked_and_unknown_ones_ignored() {
+        let schema = CompiledSchema::compile(&json!({
+            "properties": {
+                "email": { "format": "email" },
+                "id": { "format": "uuid" },
+                "day": { "format": "date" },
+                "host": { "format": "ipv4" },
+                "color": { "format": "hex-color" },
+            },
+        }))
+        .unwrap();
+
+        let valid = json!({
+            "email": "ada@example.com",
+            "id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "day": "2024-02-29",
+            "host": "192.168.0.1",
+            "color": "not a color",
+        });
+        assert!(schema.is_valid(&valid));
+
+        let invalid = json!({ "email": "ada", "id": "67e55044", "day": "2023-02-29", "host": "::1" });
+        assert_eq!(pointers(&schema, invalid), ["/day", "/email", "/host", "/id"]);
+    }
+
+    #[test]
+    fn additional_properties_apply_to_members_not_otherwise_evaluated() {
+        let schema = CompiledSchema::compile(&json!({
+            "properties": { "name": { "type": "string" } },
+            "patternProperties": { "^x-": { "type": "string" } },
+            "additionalProperties": false,
+        }))
+        .unwrap();
+
+        assert!(schema.is_valid(&json!({ "name": "ada", "x-trace": "1" })));
+        assert_eq!(pointers(&schema, json!({ "name": "ada", "age": 36 })), ["/age"]);
+        assert_eq!(pointers(&schema, json!({ "x-trace": 1 })), ["/x-trace"]);
+    }
+
+    #[test]
+    fn unevaluated_keywords_are_rejected() {
+        let err = CompiledSchema::compile(&json!({ "properties": { "a": { "unevaluatedProperties": false } } }))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            SchemaError::Unsupported { ref location, ref keyword } if location == "#/properties/a" && keyword == "unevaluatedProperties"
+        ));
+    }
+
+    #[test]
+    fn unique_items_compare_numbers_by_value() {
+        let schema = CompiledSchema::compile(&json!({ "uniqueItems": true })).unwrap();
+        assert!(!schema.is_valid(&json!([1, 1.0])));
+        assert!(schema.is_valid(&json!([1, "1", [1]])));
+    }
+
+    #[test]
+    fn enum_and_const_compare_numbers_by_value() {
+        let schema = CompiledSchema::compile(&json!({ "enum": [1, 2, {"a": [3]}] })).unwrap();
+        assert!(schema.is_valid(&json!(1.0)));
+        assert!(schema.is_valid(&json!({"a": [3.0]})));
+        assert!(!schema.is_valid(&json!(1.5)));
+        assert!(!schema.is_valid(&json!("1")));
+
+        let schema = CompiledSchema::compile(&json!({ "const": 2 })).unwrap();
+        assert!(schema.is_valid(&json!(2.0)));
+        assert!(!schema.is_valid(&json!(2.5)));
+    }
+
+    #[test]
+    fn string_lengths_count_characters() {
+        let schema = CompiledSchema::compile(&json!({ "maxLength": 3 })).unwrap();
+        assert!(schema.is_valid(&json!("日本語")));
+        assert!(!schema.is_valid(&json!("日本語です")));
+    }
+}
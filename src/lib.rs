@@ -11,9 +11,32 @@
 pub mod rules;
 pub mod validator;
 pub mod error;
+pub mod context;
+pub mod path;
+pub mod problem;
+pub mod i18n;
+pub mod report;
+pub mod schema;
+pub mod sanitize;
+pub mod validated;
+pub mod json_schema;
+pub mod rule_set;
+pub mod dynamic;
+pub mod form;
+#[cfg(feature = "csv")]
+pub mod tabular;
+#[cfg(feature = "async")]
+pub mod asynchronous;
 
 pub use validator::Validator;
 pub use error::ValidationError;
+pub use path::FieldPath;
+pub use report::ValidationReport;
+pub use schema::Schema;
+pub use validated::Validated;
+pub use rule_set::RuleSet;
+pub use dynamic::DynamicSchema;
+pub use form::FormSchema;
 
 // Re-export the derive macro when the derive feature is enabled
 #[cfg(feature = "derive")]
@@ -21,9 +44,15 @@ pub use rustvalidity_derive::Validate;
 
 /// Re-export commonly used items for easier imports
 pub mod prelude {
-    pub use crate::validator::{Validator, Validate};
+    pub use crate::validator::{Validate, ValidationMode, ValidationRun, Validator};
     pub use crate::rules::Rule;
-    pub use crate::error::ValidationError;
+    pub use crate::error::{Severity, ValidationError};
+    pub use crate::context::{ContextData, ValidationContext};
+    pub use crate::report::ValidationReport;
+    pub use crate::schema::Schema;
+    pub use crate::sanitize::{Modifier, Sanitize};
+    pub use crate::validated::Validated;
+    pub use crate::json_schema::JsonSchema;
     pub use crate::rules::prelude::*;
     
     // Re-export the derive macro when the derive feature is enabled
@@ -0,0 +1,482 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A single step in a field path
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// A named struct field, rendered as `address`
+    Field(String),
+
+    /// A position in a sequence, rendered as `[3]`
+    Index(usize),
+
+    /// A key in a map, rendered as `["env"]`
+    Key(String),
+}
+
+impl PathSegment {
+    /// The segment as it appears in a JSON Pointer, before escaping
+    fn pointer_token(&self) -> String {
+        match self {
+            PathSegment::Field(name) | PathSegment::Key(name) => name.clone(),
+            PathSegment::Index(index) => index.to_string(),
+        }
+    }
+}
+
+impl From<&str> for PathSegment {
+    fn from(name: &str) -> Self {
+        PathSegment::Field(name.to_string())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(name: String) -> Self {
+        PathSegment::Field(name)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+/// Location of a value inside the structure being validated, e.g. `items[3].sku`
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FieldPath {
+    segments: Vec<PathSegment>,
+}
+
+impl FieldPath {
+    /// Create an empty path pointing at the root value
+    pub fn root() -> Self {
+        FieldPath { segments: Vec::new() }
+    }
+
+    /// Create a path from its segments
+    pub fn from_segments(segments: Vec<PathSegment>) -> Self {
+        FieldPath { segments }
+    }
+
+    /// Parse a dotted path such as `address.street`, `items[3].sku` or `tags["env"]`
+    pub fn parse(path: &str) -> Result<Self, PathParseError> {
+        let mut segments = Vec::new();
+        let mut chars = path.char_indices().peekable();
+        let mut expect_field = true;
+
+        while let Some(&(pos, c)) = chars.peek() {
+            match c {
+                '.' => {
+                    chars.next();
+                    expect_field = true;
+                },
+                '[' => {
+                    chars.next();
+                    if let Some(&(_, '"')) = chars.peek() {
+                        chars.next();
+                        let mut key = String::new();
+                        let mut closed = false;
+                        while let Some((_, c)) = chars.next() {
+                            match c {
+                                '\\' => match chars.next() {
+                                    Some((_, escaped)) => key.push(escaped),
+                                    None => break,
+                                },
+                                '"' => {
+                                    closed = true;
+                                    break;
+                                },
+                                _ => key.push(c),
+                            }
+                        }
+                        if !closed || !matches!(chars.next(), Some((_, ']'))) {
+                            return Err(PathParseError::new(path, pos));
+                        }
+                        segments.push(PathSegment::Key(key));
+                    } else {
+                        let mut digits = String::new();
+                        let mut closed = false;
+                        for (_, c) in chars.by_ref() {
+                            if c == ']' {
+                                closed = true;
+                                break;
+                            }
+                            digits.push(c);
+                        }
+                        match digits.trim().parse::<usize>() {
+                            Ok(index) if closed => segments.push(PathSegment::Index(index)),
+                            _ => return Err(PathParseError::new(path, pos)),
+                        }
+                    }
+                    expect_field = false;
+                },
+                _ => {
+                    if !expect_field {
+                        return Err(PathParseError::new(path, pos));
+                    }
+                    let mut name = String::new();
+                    while let Some(&(_, c)) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        name.push(c);
+                        chars.next();
+                    }
+                    segments.push(PathSegment::Field(name));
+                    expect_field = false;
+                },
+            }
+        }
+
+        Ok(FieldPath { segments })
+    }
+
+    /// Check whether the path points at the root value
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The segments making up the path
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Append a segment to the path
+    pub fn push<S: Into<PathSegment>>(&mut self, segment: S) {
+        self.segments.push(segment.into());
+    }
+
+    /// Return a new path with a segment appended
+    pub fn join<S: Into<PathSegment>>(&self, segment: S) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// Return a new path with a struct field appended
+    pub fn field<S: Into<String>>(&self, name: S) -> Self {
+        self.join(PathSegment::Field(name.into()))
+    }
+
+    /// Return a new path with a sequence index appended
+    pub fn index(&self, index: usize) -> Self {
+        self.join(PathSegment::Index(index))
+    }
+
+    /// Return a new path with a map key appended
+    pub fn key<S: Into<String>>(&self, key: S) -> Self {
+        self.join(PathSegment::Key(key.into()))
+    }
+
+    /// Return a new path with `other` appended to this one
+    pub fn concat(&self, other: &FieldPath) -> Self {
+        let mut path = self.clone();
+        path.segments.extend(other.segments.iter().cloned());
+        path
+    }
+
+    /// Render the path in dotted form, e.g. `items[3].sku`
+    pub fn to_dotted(&self) -> String {
+        self.to_string()
+    }
+
+    /// Render the path as an RFC 6901 JSON Pointer, e.g. `/items/3/sku`
+    pub fn to_json_pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| format!("/{}", segment.pointer_token().replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{}", name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Key(key) => {
+                    write!(f, "[\"{}\"]", key.replace('\\', "\\\\").replace('"', "\\\""))?
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = PathParseError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        FieldPath::parse(path)
+    }
+}
+
+/// Parses a path written in the notation of [`FieldPath::parse`], e.g. `"items[0].name"`
+///
+/// # Panics
+///
+/// Panics if the path does not parse, as a malformed path is a mistake in the
+/// code naming it. Use [`FieldPath::parse`] for paths that come from input.
+impl From<&str> for FieldPath {
+    fn from(path: &str) -> Self {
+        FieldPath::parse(path).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl From<String> for FieldPath {
+    fn from(path: String) -> Self {
+        FieldPath::from(path.as_str())
+    }
+}
+
+impl From<&String> for FieldPath {
+    fn from(path: &String) -> Self {
+        FieldPath::from(path.as_str())
+    }
+}
+
+impl From<PathSegment> for FieldPath {
+    fn from(segment: PathSegment) -> Self {
+        FieldPath { segments: vec![segment] }
+    }
+}
+
+impl From<usize> for FieldPath {
+    fn from(index: usize) -> Self {
+        FieldPath::from(PathSegment::Index(index))
+    }
+}
+
+impl From<&FieldPath> for FieldPath {
+    fn from(path: &FieldPath) -> Self {
+        path.clone()
+    }
+}
+
+/// Paths are serialized in their dotted form, e.g. `"items[3].sku"`
+impl Serialize for FieldPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let path = String::deserialize(deserializer)?;
+        FieldPath::parse(&path).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Error returned when a path string is malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathParseError {
+    pub path: String,
+    pub position: usize,
+}
+
+impl PathParseError {
+    fn new(path: &str, position: usize) -> Self {
+        PathParseError {
+            path: path.to_string(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid field path '{}' at position {}", self.path, self.position)
+    }
+}
+
+impl std::error::Error for PathParseError {}
+
+/// A step of a [`PathPattern`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PatternSegment {
+    /// A step matching one field, index or key
+    Exact(PathSegment),
+
+    /// Every element of a sequence, written `[*]`
+    AnyIndex,
+
+    /// Every member of a map or object, written `*`
+    AnyField,
+}
+
+/// A field path that may contain wildcards, e.g. `items[*].price` or `metadata.*`
+///
+/// Wildcards select every element of a sequence (`[*]`) or every member of a
+/// map (`*`); a quoted key `["*"]` stays a literal key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathPattern {
+    segments: Vec<PatternSegment>,
+}
+
+impl PathPattern {
+    /// Parse a pattern, in the syntax of [`FieldPath::parse`] extended with wildcards
+    pub fn parse(pattern: &str) -> Result<Self, PathParseError> {
+        let mut segments = Vec::new();
+        let mut offset = 0;
+        for (i, part) in pattern.split("[*]").enumerate() {
+            if i > 0 {
+                segments.push(PatternSegment::AnyIndex);
+            }
+            // `[*]` ends a segment, so the rest must continue with `.` or `[`
+            if i > 0 && !part.is_empty() && !part.starts_with(['.', '[']) {
+                return Err(PathParseError::new(pattern, offset));
+            }
+            let path = FieldPath::parse(part).map_err(|err| PathParseError::new(pattern, offset + err.position))?;
+            segments.extend(path.segments.into_iter().map(|segment| match segment {
+                PathSegment::Field(name) if name == "*" => PatternSegment::AnyField,
+                segment => PatternSegment::Exact(segment),
+            }));
+            offset += part.len() + "[*]".len();
+        }
+        Ok(PathPattern { segments })
+    }
+
+    /// The segments making up the pattern
+    pub fn segments(&self) -> &[PatternSegment] {
+        &self.segments
+    }
+
+    /// Check whether the pattern contains a wildcard
+    pub fn has_wildcards(&self) -> bool {
+        self.segments.iter().any(|segment| !matches!(segment, PatternSegment::Exact(_)))
+    }
+
+    /// Check whether a concrete path is selected by the pattern
+    pub fn matches(&self, path: &FieldPath) -> bool {
+        self.segments.len() == path.segments.len()
+            && self.segments.iter().zip(&path.segments).all(|(pattern, segment)| match (pattern, segment) {
+                (PatternSegment::Exact(expected), segment) => expected == segment,
+                (PatternSegment::AnyIndex, PathSegment::Index(_)) => true,
+                (PatternSegment::AnyField, PathSegment::Field(_) | PathSegment::Key(_)) => true,
+                _ => false,
+            })
+    }
+}
+
+impl fmt::Display for PathPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PatternSegment::Exact(segment) => {
+                    let path = FieldPath::from(segment.clone());
+                    if i > 0 && matches!(segment, PathSegment::Field(_)) {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", path)?
+                },
+                PatternSegment::AnyIndex => write!(f, "[*]")?,
+                PatternSegment::AnyField if i == 0 => write!(f, "*")?,
+                PatternSegment::AnyField => write!(f, ".*")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PathPattern {
+    type Err = PathParseError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        PathPattern::parse(pattern)
+    }
+}
+
+impl From<FieldPath> for PathPattern {
+    fn from(path: FieldPath) -> Self {
+        PathPattern {
+            segments: path.segments.into_iter().map(PatternSegment::Exact).collect(),
+        }
+    }
+}
+
+/// Parses a pattern written in the notation of [`PathPattern::parse`], e.g. `"users[*].email"`
+///
+/// # Panics
+///
+/// Panics if the pattern does not parse, so that a typo in a pattern is not
+/// taken as a field name that never matches. Use [`PathPattern::parse`] for
+/// patterns that come from input.
+impl From<&str> for PathPattern {
+    fn from(pattern: &str) -> Self {
+        PathPattern::parse(pattern).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl From<String> for PathPattern {
+    fn from(pattern: String) -> Self {
+        PathPattern::from(pattern.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_parse_wildcards() {
+        let pattern = PathPattern::parse("items[*].tags.*").unwrap();
+        assert_eq!(pattern.segments(), [
+            PatternSegment::Exact(PathSegment::Field("items".to_string())),
+            PatternSegment::AnyIndex,
+            PatternSegment::Exact(PathSegment::Field("tags".to_string())),
+            PatternSegment::AnyField,
+        ]);
+        assert_eq!(pattern.to_string(), "items[*].tags.*");
+        assert!(pattern.has_wildcards());
+    }
+
+    #[test]
+    fn quoted_stars_are_literal_keys() {
+        let pattern = PathPattern::parse(r#"metadata["*"]"#).unwrap();
+        assert!(!pattern.has_wildcards());
+        assert!(pattern.matches(&FieldPath::root().field("metadata").key("*")));
+        assert!(!pattern.matches(&FieldPath::root().field("metadata").key("env")));
+    }
+
+    #[test]
+    fn patterns_match_paths_of_the_same_shape() {
+        let pattern = PathPattern::parse("items[*].price").unwrap();
+        assert!(pattern.matches(&FieldPath::parse("items[4].price").unwrap()));
+        assert!(!pattern.matches(&FieldPath::parse("items.price").unwrap()));
+        assert!(!pattern.matches(&FieldPath::parse("items[4].price.amount").unwrap()));
+
+        let pattern = PathPattern::parse("env.*").unwrap();
+        assert!(pattern.matches(&FieldPath::root().field("env").key("HOME")));
+        assert!(!pattern.matches(&FieldPath::parse("env[0]").unwrap()));
+    }
+
+    #[test]
+    fn wildcards_must_end_a_segment() {
+        let err = PathPattern::parse("items[*]price").unwrap_err();
+        assert_eq!(err.position, "items[*]".len());
+        assert!(PathPattern::parse("items[*][*]").is_ok());
+    }
+
+    #[test]
+    fn strings_convert_to_parsed_paths() {
+        assert_eq!(FieldPath::from("items[0].name"), FieldPath::root().field("items").index(0).field("name"));
+        assert_eq!(PathPattern::from("users[*].email"), PathPattern::parse("users[*].email").unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid field path 'users[*.email' at position 5")]
+    fn malformed_patterns_panic() {
+        let _ = PathPattern::from("users[*.email");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid field path 'items[x]' at position 5")]
+    fn malformed_paths_panic() {
+        let _ = FieldPath::from("items[x]");
+    }
+}
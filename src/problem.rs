@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ErrorEntry, ValidationError};
+
+/// Media type of RFC 7807 problem documents
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// An RFC 7807 `problem+json` body describing a validation failure
+///
+/// The individual violations are carried in the `errors` extension member using
+/// the same entry shape as the serialized [`ValidationError`]:
+///
+/// ```json
+/// {
+///   "type": "about:blank",
+///   "title": "Validation failed",
+///   "status": 422,
+///   "detail": "2 validation errors",
+///   "errors": [
+///     { "path": "email", "pointer": "/email", "code": "email.format", "message": "Invalid email format" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    /// URI reference identifying the problem type
+    #[serde(rename = "type")]
+    pub type_uri: String,
+
+    /// Short, human-readable summary of the problem type
+    pub title: String,
+
+    /// HTTP status code
+    pub status: u16,
+
+    /// Human-readable explanation specific to this occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    /// URI reference identifying this occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+
+    /// Every violation, in the order of [`ValidationError::entries`]
+    #[serde(default)]
+    pub errors: Vec<ErrorEntry>,
+}
+
+impl ProblemDetails {
+    /// Create a problem document for a validation error with status 422
+    pub fn new(error: &ValidationError) -> Self {
+        let errors = error.entries();
+        let detail = match errors.len() {
+            1 => "1 validation error".to_string(),
+            n => format!("{} validation errors", n),
+        };
+
+        ProblemDetails {
+            type_uri: "about:blank".to_string(),
+            title: "Validation failed".to_string(),
+            status: 422,
+            detail: Some(detail),
+            instance: None,
+            errors,
+        }
+    }
+
+    /// Set the problem type URI
+    pub fn with_type<S: Into<String>>(mut self, type_uri: S) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Set the title
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the HTTP status code
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Set the detail message
+    pub fn with_detail<S: Into<String>>(mut self, detail: S) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Set the occurrence URI
+    pub fn with_instance<S: Into<String>>(mut self, instance: S) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Rebuild the validation error carried in the `errors` member
+    pub fn to_validation_error(&self) -> ValidationError {
+        ValidationError::from_entries(self.errors.iter().cloned())
+    }
+}
+
+impl From<&ValidationError> for ProblemDetails {
+    fn from(error: &ValidationError) -> Self {
+        ProblemDetails::new(error)
+    }
+}
+
+impl ValidationError {
+    /// Describe the error as an RFC 7807 problem document
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        ProblemDetails::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Violation;
+    use serde_json::json;
+
+    #[test]
+    fn problems_describe_validation_errors() {
+        let err = ValidationError::field("email", "Invalid email format").merge(ValidationError::field("name", "Required"));
+        let problem = err.to_problem_details().with_instance("/users/7");
+        assert_eq!(problem.status, 422);
+        assert_eq!(problem.detail.as_deref(), Some("2 validation errors"));
+
+        let wire = serde_json::to_value(&problem).unwrap();
+        assert_eq!(wire["type"], "about:blank");
+        assert_eq!(wire["title"], "Validation failed");
+        assert_eq!(wire["instance"], "/users/7");
+        assert_eq!(wire["errors"][0]["pointer"], "/email");
+        assert_eq!(wire["errors"][1]["path"], "name");
+    }
+
+    #[test]
+    fn problems_can_be_customized_and_read_back() {
+        let err: ValidationError = Violation::new("required", "Value is required").into();
+        let problem = ProblemDetails::from(&err)
+            .with_type("https://example.com/problems/invalid")
+            .with_title("Invalid request")
+            .with_status(400)
+            .with_detail("The body is invalid");
+        assert_eq!(problem.detail.as_deref(), Some("The body is invalid"));
+
+        let wire = serde_json::to_value(&problem).unwrap();
+        assert_eq!(wire, json!({
+            "type": "https://example.com/problems/invalid",
+            "title": "Invalid request",
+            "status": 400,
+            "detail": "The body is invalid",
+            "errors": [{ "path": "", "pointer": "", "code": "required", "message": "Value is required" }],
+        }));
+        let back: ProblemDetails = serde_json::from_value(wire).unwrap();
+        assert_eq!(back.to_validation_error().code(), Some("required"));
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Serialize, Serializer};
+
+use crate::error::{ErrorEntry, ErrorTree, Severity, ValidationError, Violation};
+use crate::path::FieldPath;
+
+/// Outcome of a validation together with every finding, fatal or not
+///
+/// Validation passes when no finding has the `Error` severity; warnings and
+/// informational findings are kept so callers can show them next to the
+/// pass/fail outcome.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    findings: ErrorTree,
+}
+
+impl ValidationReport {
+    /// Create an empty, passing report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a report from the result of a validation
+    pub fn from_result(result: Result<(), ValidationError>) -> Self {
+        let mut report = Self::new();
+        if let Err(err) = result {
+            report.record(err);
+        }
+        report
+    }
+
+    /// Record every violation of an error at the root
+    pub fn record(&mut self, error: ValidationError) {
+        self.findings.merge(error.into_tree());
+    }
+
+    /// Record every violation of an error below a path
+    pub fn record_at<P: Into<FieldPath>>(&mut self, path: P, error: ValidationError) {
+        self.findings.add_error(path, error);
+    }
+
+    /// Record a single violation at a path
+    pub fn add<P: Into<FieldPath>>(&mut self, path: P, violation: Violation) {
+        self.findings.add(path, violation);
+    }
+
+    /// Merge the findings of another report into this one
+    pub fn merge(&mut self, other: ValidationReport) {
+        self.findings.merge(other.findings);
+    }
+
+    /// Check whether validation passed, i.e. there is no `Error` finding
+    pub fn is_valid(&self) -> bool {
+        self.count(Severity::Error) == 0
+    }
+
+    /// Check whether there are no findings at all
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Every finding, regardless of severity
+    pub fn findings(&self) -> &ErrorTree {
+        &self.findings
+    }
+
+    /// Flatten every finding into wire entries, ordered like [`ValidationError::entries`]
+    pub fn entries(&self) -> Vec<ErrorEntry> {
+        self.findings
+            .leaves()
+            .into_iter()
+            .map(|(path, violation)| ErrorEntry::new(path, violation.clone()))
+            .collect()
+    }
+
+    /// Count the findings of a severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.with(severity).len()
+    }
+
+    /// Findings of a severity together with their paths
+    pub fn with(&self, severity: Severity) -> Vec<(FieldPath, &Violation)> {
+        self.findings
+            .leaves()
+            .into_iter()
+            .filter(|(_, violation)| violation.severity == severity)
+            .collect()
+    }
+
+    /// The fatal findings as a validation error, if there are any
+    pub fn errors(&self) -> Option<ValidationError> {
+        let mut errors = self.findings.clone();
+        errors.retain(&mut |violation| violation.is_error());
+        if errors.is_empty() {
+            None
+        } else {
+            Some(collapse(errors))
+        }
+    }
+
+    /// The `Warning` findings together with their paths
+    pub fn warnings(&self) -> Vec<(FieldPath, &Violation)> {
+        self.with(Severity::Warning)
+    }
+
+    /// The `Info` findings together with their paths
+    pub fn infos(&self) -> Vec<(FieldPath, &Violation)> {
+        self.with(Severity::Info)
+    }
+
+    /// Convert the report into a result holding only the fatal findings
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        match self.errors() {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<ValidationError> for ValidationReport {
+    fn from(error: ValidationError) -> Self {
+        ValidationReport::from_result(Err(error))
+    }
+}
+
+#[derive(Serialize)]
+struct WireReport {
+    valid: bool,
+    findings: Vec<ErrorEntry>,
+}
+
+/// Serializes as `{"valid": bool, "findings": [ErrorEntry, ...]}`
+impl Serialize for ValidationReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WireReport {
+            valid: self.is_valid(),
+            findings: self.entries(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Turn a tree holding a single root violation back into a single error
+fn collapse(errors: ErrorTree) -> ValidationError {
+    if errors.len() == 1 && errors.violations().len() == 1 {
+        let mut leaves = errors.into_leaves();
+        ValidationError::Single(leaves.remove(0).1)
+    } else {
+        ValidationError::Multiple(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report() -> ValidationReport {
+        let mut report = ValidationReport::new();
+        report.add("password", Violation::warning("password.weak", "Password is weak"));
+        report.add("fax", Violation::new("deprecated", "Field is deprecated").with_severity(Severity::Info));
+        report
+    }
+
+    #[test]
+    fn only_errors_make_a_report_invalid() {
+        let mut report = report();
+        assert!(report.is_valid());
+        assert!(!report.is_empty());
+        assert_eq!(report.warnings().len(), 1);
+        assert_eq!(report.infos()[0].0.to_string(), "fax");
+        assert!(report.errors().is_none());
+
+        report.record_at("email", ValidationError::new("Invalid email"));
+        assert!(!report.is_valid());
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.findings().len(), 3);
+    }
+
+    #[test]
+    fn results_keep_only_the_errors() {
+        let mut report = report();
+        assert!(report.clone().into_result().is_ok());
+
+        report.record(Violation::new("custom", "Whole value").into());
+        let err = report.into_result().unwrap_err();
+        // A single remaining root violation is a single error again
+        assert_eq!(err.code(), Some("custom"));
+
+        let warning: ValidationError = Violation::warning("password.weak", "Password is weak").into();
+        assert!(!warning.is_fatal());
+        assert!(ValidationReport::from(warning).is_valid());
+        assert!(ValidationError::new("Invalid").is_fatal());
+    }
+
+    #[test]
+    fn reports_serialize_with_their_outcome() {
+        let wire = serde_json::to_value(report()).unwrap();
+        assert_eq!(wire, json!({
+            "valid": true,
+            "findings": [
+                { "path": "fax", "pointer": "/fax", "code": "deprecated", "message": "Field is deprecated", "severity": "info" },
+                { "path": "password", "pointer": "/password", "code": "password.weak", "message": "Password is weak", "severity": "warning" },
+            ]
+        }));
+    }
+
+    #[test]
+    fn severities_can_be_changed_for_a_whole_error() {
+        let err = ValidationError::field("name", "Too short").merge(ValidationError::new("Invalid")).with_severity(Severity::Warning);
+        assert!(!err.is_fatal());
+        assert!(err.violations().iter().all(|violation| violation.severity == Severity::Warning));
+        assert_eq!(Severity::Info.to_string(), "info");
+    }
+}
@@ -0,0 +1,936 @@
+//! Rule sets loaded from configuration
+//!
+//! A [`RuleSet`] names the rules of each field together with their
+//! parameters, so limits can be tuned without a release. In YAML:
+//!
+//! ```yaml
+//! fields:
+//!   username:
+//!     - required
+//!     - length: { min: 3, max: 20 }
+//!   role:
+//!     - one_of: { values: [admin, editor, viewer] }
+//!   age:
+//!     - range: { min: 18, max: 130, groups: [signup] }
+//!   address.postal_code:
+//!     - regex: { pattern: "^[0-9]{5}$" }
+//! ```
+//!
+//! Rule sets are checked when they are loaded, so an unknown rule or a bad
+//! parameter is reported with the field and rule it belongs to before any
+//! value is validated. [`RuleSet::schema`] builds a [`Schema`] over JSON values
+//! from the built-in rules; structs are checked by serializing them first:
+//!
+//! ```
+//! # use rustvalidity::RuleSet;
+//! # use rustvalidity::validator::ValidationMode;
+//! # use serde::Serialize;
+//! # #[derive(Serialize)]
+//! # struct User { username: String, role: String }
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let dir = std::env::temp_dir().join("rustvalidity-rule-set-doc");
+//! # std::fs::create_dir_all(&dir)?;
+//! # std::fs::write(dir.join("user.json"), r#"{"fields": {"username": [{"length": {"min": 3}}], "role": [{"one_of": {"values": ["admin", "viewer"]}}]}}"#)?;
+//! let rules = RuleSet::from_file(dir.join("user.json"))?;
+//! let schema = rules.schema()?.with_mode(ValidationMode::CollectAll);
+//!
+//! let user = User { username: "al".to_string(), role: "owner".to_string() };
+//! let err = schema.validate(&serde_json::to_value(&user)?).unwrap_err();
+//! assert_eq!(err.violations().len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! JSON is always supported; YAML and TOML need the `yaml` and `toml`
+//! features. Loaded rule sets serialize back to the same form.
+//!
+//! | Rule | Parameters | Applies to |
+//! |------|------------|------------|
+//! | `required` | | any value; null and empty strings, arrays and objects are missing |
+//! | `length` | `min` (default 0), `max` | strings (in characters), arrays, objects |
+//! | `email` | `check_dns` (default false) | strings |
+//! | `url` | `schemes` | strings |
+//! | `uuid`, `json`, `semver`, `domain`, `credit_card` | | strings |
+//! | `phone` | `allow_empty` (default false) | strings |
+//! | `date` | `format` (default `%Y-%m-%d`), `min`, `max` | strings |
+//! | `ip` | `v4`, `v6` (both default true) | strings |
+//! | `regex` | `pattern` | strings |
+//! | `password` | `min_length` (default 8), `require_uppercase`, `require_lowercase`, `require_digit`, `require_special` | strings |
+//! | `port` | | integers and strings |
+//! | `one_of` | `values` | any value |
+//! | `range` | `min`, `max` | numbers |
+//! | `min`, `max` | `value` | numbers |
+//! | `positive`, `negative` | | numbers |
+//! | `divisible_by` | `divisor` | numbers |
+//! | `min_size`, `max_size`, `exact_size` | `min`, `max`, `size` | strings, arrays, objects |
+//! | `unique` | | arrays |
+//! | `contains` | `value` | arrays |
+//! | `each` | `rules`, a list of rules | arrays |
+//!
+//! Every rule also takes `groups`, the [validation groups](crate::validator::DEFAULT_GROUP)
+//! it belongs to. Rules other than `required` accept null, so fields are
+//! optional unless they are required.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use thiserror::Error;
+
+use crate::error::ValidationError;
+use crate::json_schema::{canonical_text, Keywords, OneOfValues};
+use crate::path::{FieldPath, PathParseError, PathSegment};
+use crate::rules::advanced::{CreditCard, Domain, Password, Port, RegexRule, SemVer, IP};
+use crate::rules::collection::{Contains, Each, ExactSize, MaxSize, MinSize, Unique};
+use crate::rules::combinator::{AllOf, Grouped};
+use crate::rules::common::{Date, Email, Json, Length, OneOf, Phone, Required, UrlRule, UuidRule};
+use crate::rules::numeric::{DivisibleBy, Max, Min, Negative, Positive, Range};
+use crate::rules::{invalid_type, Rule, RuleInfo};
+use crate::schema::Schema;
+
+/// Errors raised while loading a rule set or building rules from it
+#[derive(Debug, Error)]
+pub enum RuleSetError {
+    #[error("Failed to read rule set {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid rule set: {0}")]
+    Parse(String),
+
+    #[error("Failed to write rule set: {0}")]
+    Serialize(String),
+
+    #[error("Unsupported rule set format '{0}'")]
+    Format(String),
+
+    #[error("Invalid field '{field}': {source}")]
+    Field {
+        field: String,
+        source: PathParseError,
+    },
+
+    #[error("Unknown rule '{rule}' on field '{field}'")]
+    UnknownRule {
+        field: String,
+        rule: String,
+    },
+
+    #[error("Invalid parameters for rule '{rule}' on field '{field}': {message}")]
+    InvalidParams {
+        field: String,
+        rule: String,
+        message: String,
+    },
+}
+
+/// A rule given by name and parameters, e.g. `length` with `{"min": 3, "max": 20}`
+///
+/// Written as the bare name when it has no parameters and as a map from the
+/// name to the parameters otherwise.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Value", into = "Value")]
+pub struct RuleSpec {
+    pub name: String,
+    pub params: Map<String, Value>,
+}
+
+impl RuleSpec {
+    /// Create a rule without parameters
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        RuleSpec {
+            name: name.into(),
+            params: Map::new(),
+        }
+    }
+
+    /// Set a parameter
+    pub fn with_param<V: Into<Value>>(mut self, name: &str, value: V) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Build the rule, checking its parameters
+    fn build(&self, field: &str) -> Result<Box<dyn Rule<Value>>, RuleSetError> {
+        let invalid = |message: String| RuleSetError::InvalidParams {
+            field: field.to_string(),
+            rule: self.name.clone(),
+            message,
+        };
+        let mut params = self.params.clone();
+        let groups: Option<Vec<String>> = params
+            .remove("groups")
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|err| invalid(format!("groups: {}", err)))?;
+        let params = Value::Object(params);
+
+        let rule: Box<dyn Rule<Value>> = match self.name.as_str() {
+            "required" => {
+                parse::<NoParams>(params).map_err(invalid)?;
+                Box::new(Required)
+            },
+            "length" => {
+                let LengthParams { min, max } = parse(params).map_err(invalid)?;
+                if max.is_some_and(|max| min > max) {
+                    return Err(invalid("min must not be greater than max".to_string()));
+                }
+                Box::new(OnSized(Length { min, max }))
+            },
+            "email" => {
+                let EmailParams { check_dns } = parse(params).map_err(invalid)?;
+                Box::new(OnStr(Email { check_dns }))
+            },
+            "url" => {
+                let UrlParams { schemes } = parse(params).map_err(invalid)?;
+                Box::new(OnStr(UrlRule { allowed_schemes: schemes }))
+            },
+            "uuid" => string_rule(params, UuidRule).map_err(invalid)?,
+            "json" => string_rule(params, Json).map_err(invalid)?,
+            "semver" => string_rule(params, SemVer).map_err(invalid)?,
+            "domain" => string_rule(params, Domain).map_err(invalid)?,
+            "credit_card" => string_rule(params, CreditCard).map_err(invalid)?,
+            "phone" => {
+                let PhoneParams { allow_empty } = parse(params).map_err(invalid)?;
+                Box::new(OnStr(Phone { allow_empty }))
+            },
+            "date" => {
+                let DateParams { format, min, max } = parse(params).map_err(invalid)?;
+                let date = |bound: Option<String>| {
+                    bound
+                        .map(|date| NaiveDate::parse_from_str(&date, &format))
+                        .transpose()
+                        .map_err(|_| invalid(format!("dates must be written as {}", format)))
+                };
+                let (min, max) = (date(min)?, date(max)?);
+                if min.zip(max).is_some_and(|(min, max)| min > max) {
+                    return Err(invalid("min must not be after max".to_string()));
+                }
+                Box::new(OnStr(Date { format, min, max }))
+            },
+            "ip" => {
+                let IpParams { v4, v6 } = parse(params).map_err(invalid)?;
+                Box::new(OnStr(IP { allow_v4: v4, allow_v6: v6 }))
+            },
+            "regex" => {
+                let RegexParams { pattern } = parse(params).map_err(invalid)?;
+                let rule = RegexRule::new(&pattern).map_err(|_| invalid(format!("invalid pattern '{}'", pattern)))?;
+                Box::new(OnStr(rule))
+            },
+            "password" => {
+                let params: PasswordParams = parse(params).map_err(invalid)?;
+                Box::new(OnStr(Password {
+                    min_length: params.min_length,
+                    require_uppercase: params.require_uppercase,
+                    require_lowercase: params.require_lowercase,
+                    require_digit: params.require_digit,
+                    require_special: params.require_special,
+                }))
+            },
+            "port" => {
+                parse::<NoParams>(params).map_err(invalid)?;
+                Box::new(OnPort)
+            },
+            "one_of" => {
+                let OneOfParams { values } = parse(params).map_err(invalid)?;
+                Box::new(Nullable(OneOfValues(OneOf { values })))
+            },
+            "range" => {
+                let RangeParams { min, max } = parse(params).map_err(invalid)?;
+                if float(&min) > float(&max) {
+                    return Err(invalid("min must not be greater than max".to_string()));
+                }
+                let int = min.as_i64().zip(max.as_i64()).map(|(min, max)| Range { min, max });
+                Box::new(OnNumber { int, float: Range { min: float(&min), max: float(&max) } })
+            },
+            "min" => {
+                let BoundParams { value } = parse(params).map_err(invalid)?;
+                let int = value.as_i64().map(|value| Min { value });
+                Box::new(OnNumber { int, float: Min { value: float(&value) } })
+            },
+            "max" => {
+                let BoundParams { value } = parse(params).map_err(invalid)?;
+                let int = value.as_i64().map(|value| Max { value });
+                Box::new(OnNumber { int, float: Max { value: float(&value) } })
+            },
+            "positive" => {
+                parse::<NoParams>(params).map_err(invalid)?;
+                Box::new(OnNumber { int: Some(Positive), float: Positive })
+            },
+            "negative" => {
+                parse::<NoParams>(params).map_err(invalid)?;
+                Box::new(OnNumber { int: Some(Negative), float: Negative })
+            },
+            "divisible_by" => {
+                let DivisorParams { divisor } = parse(params).map_err(invalid)?;
+                if float(&divisor) == 0.0 {
+                    return Err(invalid("divisor must not be zero".to_string()));
+                }
+                let int = divisor.as_i64().map(|divisor| DivisibleBy { divisor });
+                Box::new(OnNumber { int, float: DivisibleBy { divisor: float(&divisor) } })
+            },
+            "min_size" => {
+                let MinSizeParams { min } = parse(params).map_err(invalid)?;
+                Box::new(OnSized(MinSize { min }))
+            },
+            "max_size" => {
+                let MaxSizeParams { max } = parse(params).map_err(invalid)?;
+                Box::new(OnSized(MaxSize { max }))
+            },
+            "exact_size" => {
+                let ExactSizeParams { size } = parse(params).map_err(invalid)?;
+                Box::new(OnSized(ExactSize { size }))
+            },
+            "unique" => {
+                parse::<NoParams>(params).map_err(invalid)?;
+                Box::new(OnArray(UniqueValues))
+            },
+            "contains" => {
+                let ContainsParams { value } = parse(params).map_err(invalid)?;
+                Box::new(OnArray(Contains { value }))
+            },
+            "each" => {
+                let EachParams { rules } = parse(params).map_err(invalid)?;
+                let rules = rules.iter().map(|spec| spec.build(field)).collect::<Result<Vec<_>, _>>()?;
+                Box::new(OnArray(Each { rule: Box::new(AllOf { rules }) }))
+            },
+            _ => {
+                return Err(RuleSetError::UnknownRule {
+                    field: field.to_string(),
+                    rule: self.name.clone(),
+                })
+            },
+        };
+
+        Ok(match groups {
+            Some(groups) => Box::new(Grouped::new(rule, groups)),
+            None => rule,
+        })
+    }
+}
+
+impl TryFrom<Value> for RuleSpec {
+    type Error = String;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(name) => Ok(RuleSpec::new(name)),
+            Value::Object(map) if map.len() == 1 => {
+                let (name, params) = map.into_iter().next().unwrap_or_default();
+                match params {
+                    Value::Object(params) => Ok(RuleSpec { name, params }),
+                    Value::Null => Ok(RuleSpec::new(name)),
+                    _ => Err(format!("parameters of rule '{}' must be a map", name)),
+                }
+            },
+            _ => Err("a rule must be a name or a map from a name to its parameters".to_string()),
+        }
+    }
+}
+
+impl From<RuleSpec> for Value {
+    fn from(spec: RuleSpec) -> Self {
+        if spec.params.is_empty() {
+            Value::String(spec.name)
+        } else {
+            Value::Object(Map::from_iter([(spec.name, Value::Object(spec.params))]))
+        }
+    }
+}
+
+/// The rules of each field, by field path, e.g. `address.city` or `items[0].sku`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSet {
+    #[serde(default)]
+    fields: BTreeMap<String, Vec<RuleSpec>>,
+}
+
+impl RuleSet {
+    /// Create a rule set without any field
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the rules of a field
+    pub fn field<S: Into<String>>(mut self, path: S, rules: Vec<RuleSpec>) -> Self {
+        self.fields.insert(path.into(), rules);
+        self
+    }
+
+    /// The rules of a field
+    pub fn rules(&self, path: &str) -> Option<&[RuleSpec]> {
+        self.fields.get(path).map(Vec::as_slice)
+    }
+
+    /// Every field with its rules, ordered by path
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &[RuleSpec])> {
+        self.fields.iter().map(|(path, rules)| (path.as_str(), rules.as_slice()))
+    }
+
+    /// Convert text, such as a CSV cell, to the JSON value the rules of a field check
+    ///
+    /// Empty text is null. Fields with a numeric rule read the text as a
+    /// number, and `one_of` rules with numbers or booleans among their values
+    /// read it as one of those; text that does not parse stays a string, so
+    /// the rules report it. Text of other fields is a string.
+    pub fn value_from_text(&self, field: &str, text: &str) -> Value {
+        if text.is_empty() {
+            return Value::Null;
+        }
+        let specs = self.rules(field).unwrap_or_default();
+        let typed = specs.iter().any(|spec| match spec.name.as_str() {
+            "range" | "min" | "max" | "positive" | "negative" | "divisible_by" => true,
+            "one_of" => match spec.params.get("values") {
+                Some(Value::Array(values)) => values.iter().any(|value| value.is_number() || value.is_boolean()),
+                _ => false,
+            },
+            _ => false,
+        });
+        if typed {
+            if let Ok(value @ (Value::Number(_) | Value::Bool(_))) = serde_json::from_str(text.trim()) {
+                return value;
+            }
+        }
+        Value::String(text.to_string())
+    }
+
+    /// Check that every field path, rule name and parameter is valid
+    pub fn check(&self) -> Result<(), RuleSetError> {
+        self.schema().map(|_| ())
+    }
+
+    /// Build a schema checking JSON values with the built-in rules
+    ///
+    /// Fields are looked up by path; a missing field is checked as null.
+    pub fn schema(&self) -> Result<Schema<Value>, RuleSetError> {
+        let mut schema = Schema::new();
+        for (field, specs) in &self.fields {
+            let path = FieldPath::parse(field).map_err(|source| RuleSetError::Field {
+                field: field.clone(),
+                source,
+            })?;
+            let rules = specs.iter().map(|spec| spec.build(field)).collect::<Result<Vec<_>, _>>()?;
+            let location = path.clone();
+            schema = schema.field(path, move |value: &Value| lookup(value, &location), rules);
+        }
+        Ok(schema)
+    }
+
+    /// Parse and check a rule set from JSON
+    pub fn from_json(json: &str) -> Result<Self, RuleSetError> {
+        let rule_set: RuleSet = serde_json::from_str(json).map_err(|err| RuleSetError::Parse(err.to_string()))?;
+        rule_set.check()?;
+        Ok(rule_set)
+    }
+
+    /// Parse and check a rule set from YAML
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, RuleSetError> {
+        let rule_set: RuleSet = serde_yaml::from_str(yaml).map_err(|err| RuleSetError::Parse(err.to_string()))?;
+        rule_set.check()?;
+        Ok(rule_set)
+    }
+
+    /// Parse and check a rule set from TOML
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, RuleSetError> {
+        let rule_set: RuleSet = toml::from_str(toml).map_err(|err| RuleSetError::Parse(err.to_string()))?;
+        rule_set.check()?;
+        Ok(rule_set)
+    }
+
+    /// Read and check a rule set from a file, in the format given by its extension
+    ///
+    /// `.json` files are always supported, `.yaml`/`.yml` and `.toml` files
+    /// with the matching feature.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RuleSetError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| RuleSetError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        match path.extension().and_then(|extension| extension.to_str()).unwrap_or_default() {
+            "json" => Self::from_json(&content),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::from_yaml(&content),
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml(&content),
+            extension => Err(RuleSetError::Format(extension.to_string())),
+        }
+    }
+
+    /// Write the rule set as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, RuleSetError> {
+        serde_json::to_string_pretty(self).map_err(|err| RuleSetError::Serialize(err.to_string()))
+    }
+
+    /// Write the rule set as YAML
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> Result<String, RuleSetError> {
+        serde_yaml::to_string(self).map_err(|err| RuleSetError::Serialize(err.to_string()))
+    }
+
+    /// Write the rule set as TOML
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, RuleSetError> {
+        toml::to_string(self).map_err(|err| RuleSetError::Serialize(err.to_string()))
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// The value at a path, or null when there is none
+fn lookup<'v>(value: &'v Value, path: &FieldPath) -> &'v Value {
+    path.segments()
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            PathSegment::Field(name) | PathSegment::Key(name) => value.get(name),
+            PathSegment::Index(index) => value.get(index),
+        })
+        .unwrap_or(&NULL)
+}
+
+fn parse<P: DeserializeOwned>(params: Value) -> Result<P, String> {
+    serde_json::from_value(params).map_err(|err| err.to_string())
+}
+
+fn string_rule<R: Rule<str> + 'static>(params: Value, rule: R) -> Result<Box<dyn Rule<Value>>, String> {
+    parse::<NoParams>(params)?;
+    Ok(Box::new(OnStr(rule)))
+}
+
+fn float(number: &Number) -> f64 {
+    number.as_f64().unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoParams {}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LengthParams {
+    #[serde(default)]
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EmailParams {
+    #[serde(default)]
+    check_dns: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UrlParams {
+    schemes: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PhoneParams {
+    #[serde(default)]
+    allow_empty: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DateParams {
+    #[serde(default = "iso_date")]
+    format: String,
+    min: Option<String>,
+    max: Option<String>,
+}
+
+fn iso_date() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IpParams {
+    #[serde(default = "enabled")]
+    v4: bool,
+    #[serde(default = "enabled")]
+    v6: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegexParams {
+    pattern: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PasswordParams {
+    #[serde(default = "password_length")]
+    min_length: usize,
+    #[serde(default)]
+    require_uppercase: bool,
+    #[serde(default)]
+    require_lowercase: bool,
+    #[serde(default)]
+    require_digit: bool,
+    #[serde(default)]
+    require_special: bool,
+}
+
+fn password_length() -> usize {
+    8
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OneOfParams {
+    values: Vec<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RangeParams {
+    min: Number,
+    max: Number,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoundParams {
+    value: Number,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DivisorParams {
+    divisor: Number,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MinSizeParams {
+    min: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaxSizeParams {
+    max: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExactSizeParams {
+    size: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContainsParams {
+    value: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EachParams {
+    rules: Vec<RuleSpec>,
+}
+
+/// Accepts null before applying a rule on JSON values
+struct Nullable<R>(R);
+
+impl<R: Rule<Value>> Rule<Value> for Nullable<R> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            _ => self.0.validate(value),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.0.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.0.describe()
+    }
+}
+
+/// Applies a rule on strings to JSON strings
+struct OnStr<R>(R);
+
+impl<R: Rule<str>> Rule<Value> for OnStr<R> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            Value::String(s) => self.0.validate(s),
+            _ => Err(invalid_type("Value must be a string", "string")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.0.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.0.describe()
+    }
+}
+
+/// Applies a rule on numbers to JSON numbers, as integers when both the rule's parameters and the number are integers
+struct OnNumber<I, F> {
+    int: Option<I>,
+    float: F,
+}
+
+impl<I: Rule<i64>, F: Rule<f64>> Rule<Value> for OnNumber<I, F> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            Value::Number(n) => match (&self.int, n.as_i64()) {
+                (Some(rule), Some(n)) => rule.validate(&n),
+                _ => self.float.validate(&float(n)),
+            },
+            _ => Err(invalid_type("Value must be a number", "number")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        match &self.int {
+            Some(rule) => rule.json_schema(),
+            None => self.float.json_schema(),
+        }
+    }
+
+    fn describe(&self) -> RuleInfo {
+        match &self.int {
+            Some(rule) => rule.describe(),
+            None => self.float.describe(),
+        }
+    }
+}
+
+/// Applies a rule on lengths to JSON strings, arrays and objects
+struct OnSized<R>(R);
+
+impl<R> Rule<Value> for OnSized<R>
+where
+    R: Rule<str> + Rule<Vec<Value>> + Rule<Map<String, Value>>,
+{
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            Value::String(s) => Rule::<str>::validate(&self.0, s),
+            Value::Array(items) => Rule::<Vec<Value>>::validate(&self.0, items),
+            Value::Object(members) => Rule::<Map<String, Value>>::validate(&self.0, members),
+            _ => Err(invalid_type("Value must be a string, array or object", "string, array or object")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        let mut keywords = Rule::<str>::json_schema(&self.0);
+        keywords.merge(Rule::<Vec<Value>>::json_schema(&self.0));
+        keywords.merge(Rule::<Map<String, Value>>::json_schema(&self.0));
+        keywords
+    }
+
+    fn describe(&self) -> RuleInfo {
+        Rule::<str>::describe(&self.0)
+    }
+}
+
+/// Applies a rule on sequences to JSON arrays
+struct OnArray<R>(R);
+
+impl<R: Rule<Vec<Value>>> Rule<Value> for OnArray<R> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            Value::Array(items) => self.0.validate(items),
+            _ => Err(invalid_type("Value must be an array", "array")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.0.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.0.describe()
+    }
+}
+
+/// [`Port`] on integers and strings
+struct OnPort;
+
+impl Rule<Value> for OnPort {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Null => Ok(()),
+            Value::String(s) => Rule::<str>::validate(&Port, s),
+            Value::Number(n) => match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+                Some(port) => Port.validate(&port),
+                None => Port.validate(&-1),
+            },
+            _ => Err(invalid_type("Value must be an integer or string", "integer or string")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Rule::<u16>::json_schema(&Port)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        Rule::<u16>::describe(&Port)
+    }
+}
+
+/// [`Unique`] on JSON values, compared by their canonical JSON text, so `1` and `1.0` are duplicates
+struct UniqueValues;
+
+impl Rule<Vec<Value>> for UniqueValues {
+    fn validate(&self, items: &Vec<Value>) -> Result<(), ValidationError> {
+        let items: Vec<String> = items.iter().map(canonical_text).collect();
+        Rule::<Vec<String>>::validate(&Unique, &items)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::new().with("uniqueItems", true)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        Rule::<Vec<String>>::describe(&Unique)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn build_error(spec: RuleSpec) -> RuleSetError {
+        RuleSet::new().field("field", vec![spec]).check().unwrap_err()
+    }
+
+    #[test]
+    fn unknown_rules_are_reported_with_their_field() {
+        let err = RuleSet::from_json(r#"{"fields": {"name": ["requried"]}}"#).unwrap_err();
+        assert!(matches!(err, RuleSetError::UnknownRule { ref field, ref rule } if field == "name" && rule == "requried"));
+    }
+
+    #[test]
+    fn bad_parameters_are_reported_with_their_rule() {
+        let err = RuleSet::from_json(r#"{"fields": {"name": [{"length": {"minimum": 3}}]}}"#).unwrap_err();
+        assert!(matches!(err, RuleSetError::InvalidParams { ref rule, .. } if rule == "length"));
+
+        let err = build_error(RuleSpec::new("divisible_by").with_param("divisor", 0));
+        assert!(matches!(err, RuleSetError::InvalidParams { .. }));
+    }
+
+    #[test]
+    fn inverted_bounds_are_rejected() {
+        for spec in [
+            RuleSpec::new("range").with_param("min", 10).with_param("max", 1),
+            RuleSpec::new("range").with_param("min", 1.5).with_param("max", 1),
+            RuleSpec::new("length").with_param("min", 5).with_param("max", 2),
+            RuleSpec::new("date").with_param("min", "2024-02-01").with_param("max", "2024-01-01"),
+        ] {
+            let name = spec.name.clone();
+            assert!(matches!(build_error(spec), RuleSetError::InvalidParams { ref rule, .. } if *rule == name));
+        }
+        assert!(RuleSet::new()
+            .field("age", vec![RuleSpec::new("range").with_param("min", 1).with_param("max", 1)])
+            .check()
+            .is_ok());
+    }
+
+    #[test]
+    fn unique_compares_numbers_by_value() {
+        let rule = RuleSpec::new("unique").build("ids").unwrap();
+        assert!(rule.validate(&json!([1, 1.0])).is_err());
+        assert!(rule.validate(&json!([{"a": 1}, {"a": 1.0}])).is_err());
+        assert!(rule.validate(&json!([1, 1.5, "1"])).is_ok());
+    }
+
+    #[test]
+    fn one_of_compares_numbers_by_value() {
+        let rule = RuleSpec::new("one_of").with_param("values", json!([1, "b"])).build("choice").unwrap();
+        assert!(rule.validate(&json!(1.0)).is_ok());
+        assert!(rule.validate(&json!("b")).is_ok());
+        assert!(rule.validate(&Value::Null).is_ok());
+        assert!(rule.validate(&json!(1.5)).is_err());
+        assert!(rule.validate(&json!("1")).is_err());
+    }
+
+    fn sample() -> RuleSet {
+        RuleSet::new()
+            .field("username", vec![
+                RuleSpec::new("required"),
+                RuleSpec::new("length").with_param("min", 3).with_param("max", 20),
+            ])
+            .field("address.postal_code", vec![RuleSpec::new("regex").with_param("pattern", "^[0-9]{5}$")])
+            .field("age", vec![RuleSpec::new("range").with_param("min", 18).with_param("max", 130).with_param("groups", json!(["signup"]))])
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let rules = sample();
+        assert_eq!(RuleSet::from_json(&rules.to_json().unwrap()).unwrap(), rules);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_round_trips() {
+        let rules = sample();
+        assert_eq!(RuleSet::from_yaml(&rules.to_yaml().unwrap()).unwrap(), rules);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_round_trips() {
+        let rules = sample();
+        assert_eq!(RuleSet::from_toml(&rules.to_toml().unwrap()).unwrap(), rules);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn write_failures_are_not_parse_errors() {
+        // TOML has no null
+        let rules = RuleSet::new().field("choice", vec![RuleSpec::new("one_of").with_param("values", json!([null, 1]))]);
+        assert!(matches!(rules.to_toml(), Err(RuleSetError::Serialize(_))));
+    }
+
+    #[test]
+    fn text_is_read_as_the_type_the_rules_check() {
+        let rules = RuleSet::new()
+            .field("age", vec![RuleSpec::new("min").with_param("value", 0)])
+            .field("active", vec![RuleSpec::new("one_of").with_param("values", json!([true, false]))])
+            .field("zip", vec![RuleSpec::new("length").with_param("max", 5)]);
+        assert_eq!(rules.value_from_text("age", " 42 "), json!(42));
+        assert_eq!(rules.value_from_text("age", "forty"), json!("forty"));
+        assert_eq!(rules.value_from_text("active", "true"), json!(true));
+        assert_eq!(rules.value_from_text("zip", "01234"), json!("01234"));
+        assert_eq!(rules.value_from_text("zip", ""), Value::Null);
+    }
+
+    #[test]
+    fn size_rules_describe_strings_arrays_and_objects() {
+        let rule = RuleSpec::new("length").with_param("max", 3).build("tags").unwrap();
+        assert_eq!(
+            rule.json_schema().into_value(),
+            json!({ "maxLength": 3, "maxItems": 3, "maxProperties": 3 })
+        );
+    }
+}
@@ -1,7 +1,19 @@
+use std::any::Any;
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::LazyLock;
+
 use regex::Regex;
 
-use crate::error::ValidationError;
-use crate::rules::Rule;
+use crate::error::{ValidationError, Violation};
+use crate::json_schema::Keywords;
+use crate::rules::{downcast_str, invalid_type, LengthKind, Rule, RuleInfo};
+
+// Patterns are compiled on first use and shared by every validation
+const SEMVER_PATTERN: &str = r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$";
+static SEMVER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(SEMVER_PATTERN).unwrap());
+static DOMAIN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?\.)+[a-zA-Z0-9][a-zA-Z0-9-]{0,61}[a-zA-Z0-9]$").unwrap()
+});
 
 /// Validates password complexity
 pub struct Password {
@@ -12,59 +24,159 @@ pub struct Password {
     pub require_special: bool,
 }
 
+impl Password {
+    fn info(&self) -> RuleInfo {
+        let mut description = format!("Password must be at least {} characters long", self.min_length);
+        let required: Vec<&str> = [
+            (self.require_uppercase, "an uppercase letter"),
+            (self.require_lowercase, "a lowercase letter"),
+            (self.require_digit, "a digit"),
+            (self.require_special, "a special character"),
+        ]
+        .into_iter()
+        .filter_map(|(required, class)| required.then_some(class))
+        .collect();
+        if !required.is_empty() {
+            description.push_str(&format!(" and contain {}", required.join(", ")));
+        }
+        RuleInfo::new("password", description)
+            .with_param("min_length", &self.min_length)
+            .with_param("require_uppercase", &self.require_uppercase)
+            .with_param("require_lowercase", &self.require_lowercase)
+            .with_param("require_digit", &self.require_digit)
+            .with_param("require_special", &self.require_special)
+    }
+
+    fn check(&self, s: &str) -> Result<(), ValidationError> {
+        if s.chars().count() < self.min_length {
+            return Err(Violation::new("password.min_length", format!(
+                "Password must be at least {} characters long", self.min_length
+            ))
+            .with_param("min_length", &self.min_length)
+            .into());
+        }
+        
+        if self.require_uppercase && !s.chars().any(|c| c.is_uppercase()) {
+            return Err(Violation::new(
+                "password.uppercase",
+                "Password must contain at least one uppercase letter"
+            ).into());
+        }
+        
+        if self.require_lowercase && !s.chars().any(|c| c.is_lowercase()) {
+            return Err(Violation::new(
+                "password.lowercase",
+                "Password must contain at least one lowercase letter"
+            ).into());
+        }
+        
+        if self.require_digit && !s.chars().any(|c| c.is_ascii_digit()) {
+            return Err(Violation::new(
+                "password.digit",
+                "Password must contain at least one digit"
+            ).into());
+        }
+        
+        if self.require_special && !s.chars().any(|c| !c.is_alphanumeric()) {
+            return Err(Violation::new(
+                "password.special",
+                "Password must contain at least one special character"
+            ).into());
+        }
+        
+        Ok(())
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::min_length(LengthKind::Text, self.min_length)
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for Password {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Password {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        self.check(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Password {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            if s.len() < self.min_length {
-                return Err(ValidationError::new(format!(
-                    "Password must be at least {} characters long", self.min_length
-                )));
-            }
-            
-            if self.require_uppercase && !s.chars().any(|c| c.is_uppercase()) {
-                return Err(ValidationError::new(
-                    "Password must contain at least one uppercase letter"
-                ));
-            }
-            
-            if self.require_lowercase && !s.chars().any(|c| c.is_lowercase()) {
-                return Err(ValidationError::new(
-                    "Password must contain at least one lowercase letter"
-                ));
-            }
-            
-            if self.require_digit && !s.chars().any(|c| c.is_digit(10)) {
-                return Err(ValidationError::new(
-                    "Password must contain at least one digit"
-                ));
-            }
-            
-            if self.require_special && !s.chars().any(|c| !c.is_alphanumeric()) {
-                return Err(ValidationError::new(
-                    "Password must contain at least one special character"
-                ));
-            }
-            
-            Ok(())
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 /// Validates credit card numbers
 pub struct CreditCard;
 
+impl CreditCard {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("credit_card", "Value must be a valid credit card number")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for CreditCard {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for CreditCard {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_credit_card(value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for CreditCard {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_credit_card(s)
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            validate_credit_card(s)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_credit_card(card: &str) -> Result<(), ValidationError> {
@@ -72,13 +184,17 @@ fn validate_credit_card(card: &str) -> Result<(), ValidationError> {
     let card = card.replace([' ', '-'], "");
     
     // Check if the card number contains only digits
-    if !card.chars().all(|c| c.is_digit(10)) {
-        return Err(ValidationError::new("Credit card number must contain only digits"));
+    if !card.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Violation::new("credit_card.digits", "Credit card number must contain only digits").into());
     }
     
     // Check length (most cards are 13-19 digits)
     if card.len() < 13 || card.len() > 19 {
-        return Err(ValidationError::new("Credit card number has invalid length"));
+        return Err(Violation::new("credit_card.length", "Credit card number has invalid length")
+            .with_param("min", &13)
+            .with_param("max", &19)
+            .with_param("actual", &card.len())
+            .into());
     }
     
     // Luhn algorithm validation
@@ -100,7 +216,7 @@ fn validate_credit_card(card: &str) -> Result<(), ValidationError> {
     }
     
     if sum % 10 != 0 {
-        return Err(ValidationError::new("Invalid credit card number"));
+        return Err(Violation::new("credit_card.checksum", "Invalid credit card number").into());
     }
     
     Ok(())
@@ -109,23 +225,66 @@ fn validate_credit_card(card: &str) -> Result<(), ValidationError> {
 /// Validates semantic version strings
 pub struct SemVer;
 
+impl SemVer {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("semver", "Value must be a semantic version, e.g. 1.2.3")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("pattern", SEMVER_PATTERN)
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for SemVer {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for SemVer {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_semver(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for SemVer {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_semver(s)
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            validate_semver(s)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_semver(version: &str) -> Result<(), ValidationError> {
-    let semver_regex = regex::Regex::new(r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?$").unwrap();
-    
-    if !semver_regex.is_match(version) {
-        return Err(ValidationError::new("Invalid semantic version format"));
+    if !SEMVER_REGEX.is_match(version) {
+        return Err(Violation::new("semver.format", "Invalid semantic version format")
+            .with_value(version)
+            .into());
     }
     
     Ok(())
@@ -134,23 +293,66 @@ fn validate_semver(version: &str) -> Result<(), ValidationError> {
 /// Validates domain names
 pub struct Domain;
 
+impl Domain {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("domain", "Value must be a valid domain name")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("format", "hostname")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for Domain {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Domain {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_domain(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Domain {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_domain(s)
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            validate_domain(s)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_domain(domain: &str) -> Result<(), ValidationError> {
-    let domain_regex = regex::Regex::new(r"^(?:[a-zA-Z0-9](?:[a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?\.)+[a-zA-Z0-9][a-zA-Z0-9-]{0,61}[a-zA-Z0-9]$").unwrap();
-    
-    if !domain_regex.is_match(domain) {
-        return Err(ValidationError::new("Invalid domain name format"));
+    if !DOMAIN_REGEX.is_match(domain) {
+        return Err(Violation::new("domain.format", "Invalid domain name format")
+            .with_value(domain)
+            .into());
     }
     
     Ok(())
@@ -159,116 +361,319 @@ fn validate_domain(domain: &str) -> Result<(), ValidationError> {
 /// Validates port numbers
 pub struct Port;
 
+fn port_zero() -> ValidationError {
+    Violation::new("port.zero", "Port number cannot be 0").into()
+}
+
+impl Port {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("port", "Value must be a port number between 1 and 65535")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("minimum", 1).with("maximum", 65535)
+    }
+}
+
+impl Rule<u16> for Port {
+    fn validate(&self, port: &u16) -> Result<(), ValidationError> {
+        if *port == 0 {
+            return Err(port_zero());
+        }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<i32> for Port {
+    fn validate(&self, port: &i32) -> Result<(), ValidationError> {
+        if *port <= 0 || *port > 65535 {
+            return Err(Violation::new("port.range", "Port number must be between 1 and 65535")
+                .with_param("min", &1)
+                .with_param("max", &65535)
+                .with_value(port)
+                .into());
+        }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Port {
+    fn validate(&self, s: &str) -> Result<(), ValidationError> {
+        match s.parse::<u16>() {
+            Ok(port) => self.validate(&port),
+            Err(_) => Err(Violation::new("port.format", "Invalid port number format")
+                .with_value(s)
+                .into()),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::new()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<String> for Port {
+    fn validate(&self, s: &String) -> Result<(), ValidationError> {
+        self.validate(s.as_str())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::new()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<&str> for Port {
+    fn validate(&self, s: &&str) -> Result<(), ValidationError> {
+        self.validate(*s)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::new()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Port {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
         if let Some(port) = value.downcast_ref::<u16>() {
-            if *port == 0 {
-                return Err(ValidationError::new("Port number cannot be 0"));
-            }
-            Ok(())
+            self.validate(port)
         } else if let Some(port) = value.downcast_ref::<i32>() {
-            if *port <= 0 || *port > 65535 {
-                return Err(ValidationError::new("Port number must be between 1 and 65535"));
-            }
-            Ok(())
-        } else if let Some(s) = value.downcast_ref::<String>() {
-            match s.parse::<u16>() {
-                Ok(port) => {
-                    if port == 0 {
-                        return Err(ValidationError::new("Port number cannot be 0"));
-                    }
-                    Ok(())
-                },
-                Err(_) => Err(ValidationError::new("Invalid port number format")),
-            }
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            match s.parse::<u16>() {
-                Ok(port) => {
-                    if port == 0 {
-                        return Err(ValidationError::new("Port number cannot be 0"));
-                    }
-                    Ok(())
-                },
-                Err(_) => Err(ValidationError::new("Invalid port number format")),
-            }
+            self.validate(port)
+        } else if let Some(s) = downcast_str(value) {
+            self.validate(s)
         } else {
-            Err(ValidationError::new("Value must be a port number (u16, i32, or string)"))
+            Err(invalid_type("Value must be a port number (u16, i32, or string)", "port"))
         }
     }
+
+    // The bounds only constrain numbers, so strings are still accepted
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 /// Validates IP addresses
+///
+/// Addresses are parsed with [`std::net`], so IPv4 octets with leading zeros
+/// are rejected; IPv6 addresses may carry a zone index, e.g. `fe80::1%eth0`.
 pub struct IP {
     pub allow_v4: bool,
     pub allow_v6: bool,
 }
 
+impl<T: AsRef<str>> Rule<T> for IP {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for IP {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_ip(value, self)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for IP {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_ip(s, self)
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            validate_ip(s, self)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Parse an IP address, accepting an IPv6 zone index such as `fe80::1%eth0`
+fn parse_ip(ip: &str) -> Option<IpAddr> {
+    if let Ok(addr) = ip.parse::<IpAddr>() {
+        return Some(addr);
+    }
+    let (addr, zone) = ip.split_once('%')?;
+    if zone.is_empty() || !zone.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    addr.parse::<Ipv6Addr>().ok().map(IpAddr::V6)
+}
+
+impl IP {
+    fn info(&self) -> RuleInfo {
+        let description = match (self.allow_v4, self.allow_v6) {
+            (true, false) => "Value must be an IPv4 address",
+            (false, true) => "Value must be an IPv6 address",
+            _ => "Value must be an IP address",
+        };
+        RuleInfo::new("ip", description)
+            .with_param("allow_v4", &self.allow_v4)
+            .with_param("allow_v6", &self.allow_v6)
+    }
+
+    fn keywords(&self) -> Keywords {
+        match (self.allow_v4, self.allow_v6) {
+            (true, false) => Keywords::new().with("format", "ipv4"),
+            (false, true) => Keywords::new().with("format", "ipv6"),
+            _ => Keywords::any_of([
+                Keywords::new().with("format", "ipv4"),
+                Keywords::new().with("format", "ipv6"),
+            ]),
         }
     }
 }
 
 fn validate_ip(ip: &str, ip_rule: &IP) -> Result<(), ValidationError> {
-    let ipv4_regex = regex::Regex::new(r"^(?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)$").unwrap();
-    let ipv6_regex = regex::Regex::new(r"^(([0-9a-fA-F]{1,4}:){7,7}[0-9a-fA-F]{1,4}|([0-9a-fA-F]{1,4}:){1,7}:|([0-9a-fA-F]{1,4}:){1,6}:[0-9a-fA-F]{1,4}|([0-9a-fA-F]{1,4}:){1,5}(:[0-9a-fA-F]{1,4}){1,2}|([0-9a-fA-F]{1,4}:){1,4}(:[0-9a-fA-F]{1,4}){1,3}|([0-9a-fA-F]{1,4}:){1,3}(:[0-9a-fA-F]{1,4}){1,4}|([0-9a-fA-F]{1,4}:){1,2}(:[0-9a-fA-F]{1,4}){1,5}|[0-9a-fA-F]{1,4}:((:[0-9a-fA-F]{1,4}){1,6})|:((:[0-9a-fA-F]{1,4}){1,7}|:)|fe80:(:[0-9a-fA-F]{0,4}){0,4}%[0-9a-zA-Z]{1,}|::(ffff(:0{1,4}){0,1}:){0,1}((25[0-5]|(2[0-4]|1{0,1}[0-9]){0,1}[0-9])\.){3,3}(25[0-5]|(2[0-4]|1{0,1}[0-9]){0,1}[0-9])|([0-9a-fA-F]{1,4}:){1,4}:((25[0-5]|(2[0-4]|1{0,1}[0-9]){0,1}[0-9])\.){3,3}(25[0-5]|(2[0-4]|1{0,1}[0-9]){0,1}[0-9]))$").unwrap();
-    
-    let is_ipv4 = ipv4_regex.is_match(ip);
-    let is_ipv6 = ipv6_regex.is_match(ip);
-    
-    if (is_ipv4 && ip_rule.allow_v4) || (is_ipv6 && ip_rule.allow_v6) {
-        Ok(())
-    } else if is_ipv4 && !ip_rule.allow_v4 {
-        Err(ValidationError::new("IPv4 addresses are not allowed"))
-    } else if is_ipv6 && !ip_rule.allow_v6 {
-        Err(ValidationError::new("IPv6 addresses are not allowed"))
-    } else {
-        Err(ValidationError::new("Invalid IP address format"))
+    match parse_ip(ip) {
+        Some(IpAddr::V4(_)) if ip_rule.allow_v4 => Ok(()),
+        Some(IpAddr::V6(_)) if ip_rule.allow_v6 => Ok(()),
+        Some(IpAddr::V4(_)) => Err(Violation::new("ip.v4_not_allowed", "IPv4 addresses are not allowed")
+            .with_value(ip)
+            .into()),
+        Some(IpAddr::V6(_)) => Err(Violation::new("ip.v6_not_allowed", "IPv6 addresses are not allowed")
+            .with_value(ip)
+            .into()),
+        None => Err(Violation::new("ip.format", "Invalid IP address format")
+            .with_value(ip)
+            .into()),
     }
 }
 
 /// Validates against a regular expression
 pub struct RegexRule {
     pub pattern: String,
-    pub regex: regex::Regex,
+    pub regex: Regex,
 }
 
 impl RegexRule {
     pub fn new(pattern: &str) -> Result<Self, ValidationError> {
-        match regex::Regex::new(pattern) {
+        match Regex::new(pattern) {
             Ok(regex) => Ok(RegexRule {
                 pattern: pattern.to_string(),
                 regex,
             }),
-            Err(_) => Err(ValidationError::new("Invalid regex pattern")),
+            Err(_) => Err(Violation::new("regex.invalid_pattern", "Invalid regex pattern")
+                .with_param("pattern", pattern)
+                .into()),
         }
     }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("regex", format!("Value must match the pattern {}", self.pattern))
+            .with_param("pattern", &self.pattern)
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("pattern", self.pattern.as_str())
+    }
 }
 
-impl Rule for RegexRule {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            if !self.regex.is_match(s) {
-                return Err(ValidationError::new(format!(
-                    "Value does not match pattern: {}", self.pattern
-                )));
-            }
-        } else if let Some(s) = value.downcast_ref::<&str>() {
-            if !self.regex.is_match(s) {
-                return Err(ValidationError::new(format!(
-                    "Value does not match pattern: {}", self.pattern
-                )));
-            }
-        } else {
-            return Err(ValidationError::new("Value must be a string"));
+impl RegexRule {
+    fn check(&self, s: &str) -> Result<(), ValidationError> {
+        if !self.regex.is_match(s) {
+            return Err(Violation::new("regex.pattern", format!(
+                "Value does not match pattern: {}", self.pattern
+            ))
+            .with_param("pattern", &self.pattern)
+            .with_value(s)
+            .into());
         }
-        
+
         Ok(())
     }
 }
+
+impl<T: AsRef<str>> Rule<T> for RegexRule {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for RegexRule {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        self.check(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for RegexRule {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
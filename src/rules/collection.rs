@@ -1,49 +1,95 @@
-use std::collections::{HashMap, HashSet};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
-use crate::error::ValidationError;
-use crate::rules::Rule;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::context::ValidationContext;
+use crate::error::{ErrorTree, ValidationError, Violation};
+use crate::json_schema::Keywords;
+use crate::path::PathSegment;
+use crate::rules::{invalid_type, HasLength, LengthKind, Rule, RuleInfo};
 
 /// Validates that all elements in a collection are unique
 pub struct Unique;
 
+fn find_duplicate<T: Eq + Hash + Display + Serialize>(items: &[T]) -> Result<(), ValidationError> {
+    let mut set = HashSet::new();
+    for item in items {
+        if !set.insert(item) {
+            return Err(Violation::new("unique", format!(
+                "Duplicate value found: {}", item
+            ))
+            .with_value(item)
+            .into());
+        }
+    }
+    Ok(())
+}
+
+impl Unique {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("unique", "Items must be unique")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("uniqueItems", true)
+    }
+}
+
+impl<T: Eq + Hash + Display + Serialize> Rule<[T]> for Unique {
+    fn validate(&self, value: &[T]) -> Result<(), ValidationError> {
+        find_duplicate(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: Eq + Hash + Display + Serialize> Rule<Vec<T>> for Unique {
+    fn validate(&self, value: &Vec<T>) -> Result<(), ValidationError> {
+        find_duplicate(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Unique {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
         // For Vec<T> where T: Eq + Hash
         if let Some(vec) = value.downcast_ref::<Vec<String>>() {
-            let mut set = HashSet::new();
-            for item in vec {
-                if !set.insert(item) {
-                    return Err(ValidationError::new(format!(
-                        "Duplicate value found: {}", item
-                    )));
-                }
-            }
+            find_duplicate(vec)
         } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
-            let mut set = HashSet::new();
-            for item in vec {
-                if !set.insert(*item) {
-                    return Err(ValidationError::new(format!(
-                        "Duplicate value found: {}", item
-                    )));
-                }
-            }
+            find_duplicate(vec)
         } else if let Some(vec) = value.downcast_ref::<Vec<i64>>() {
-            let mut set = HashSet::new();
-            for item in vec {
-                if !set.insert(*item) {
-                    return Err(ValidationError::new(format!(
-                        "Duplicate value found: {}", item
-                    )));
-                }
-            }
+            find_duplicate(vec)
         } else {
-            return Err(ValidationError::new(
-                "Value must be a collection of hashable items"
-            ));
+            Err(invalid_type(
+                "Value must be a collection of hashable items",
+                "collection",
+            ))
         }
-        
-        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
@@ -52,114 +98,422 @@ pub struct Contains<T: PartialEq + Clone + 'static> {
     pub value: T,
 }
 
-impl<T: PartialEq + Clone + Send + Sync + std::fmt::Debug + 'static> Rule for Contains<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(vec) = value.downcast_ref::<Vec<T>>() {
-            if !vec.contains(&self.value) {
-                return Err(ValidationError::new(format!(
-                    "Collection must contain {:?}", self.value
-                )));
-            }
-        } else {
-            return Err(ValidationError::new(
-                "Value must be a collection of the expected type"
-            ));
+impl<T: PartialEq + Clone + Serialize + Send + Sync + Debug + 'static> Contains<T> {
+    fn check(&self, items: &[T]) -> Result<(), ValidationError> {
+        if !items.contains(&self.value) {
+            return Err(Violation::new("contains", format!(
+                "Collection must contain {:?}", self.value
+            ))
+            .with_param("value", &self.value)
+            .into());
         }
-        
+
         Ok(())
     }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("contains", format!("Collection must contain {:?}", self.value))
+            .with_param("value", &self.value)
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("contains", json!({ "const": self.value }))
+    }
+}
+
+impl<T: PartialEq + Clone + Serialize + Send + Sync + Debug + 'static> Rule<[T]> for Contains<T> {
+    fn validate(&self, value: &[T]) -> Result<(), ValidationError> {
+        self.check(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialEq + Clone + Serialize + Send + Sync + Debug + 'static> Rule<Vec<T>> for Contains<T> {
+    fn validate(&self, value: &Vec<T>) -> Result<(), ValidationError> {
+        self.check(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialEq + Clone + Serialize + Send + Sync + Debug + 'static> Rule for Contains<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match value.downcast_ref::<Vec<T>>() {
+            Some(vec) => self.check(vec),
+            None => Err(invalid_type(
+                "Value must be a collection of the expected type",
+                std::any::type_name::<Vec<T>>(),
+            )),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Turn the errors collected from individual elements into a result
+fn collected(errors: ErrorTree) -> Result<(), ValidationError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError::Multiple(errors))
+    }
 }
 
 /// Applies a validation rule to each element in a collection
-pub struct Each<R: Rule + 'static> {
+///
+/// Every failing element is reported at its own path, e.g. `[3]` for a
+/// sequence or `["env"]` for a map.
+pub struct Each<R: ?Sized> {
     pub rule: Box<R>,
 }
 
-impl<R: Rule + 'static> Rule for Each<R> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(vec) = value.downcast_ref::<Vec<String>>() {
-            for (i, item) in vec.iter().enumerate() {
-                if let Err(err) = self.rule.validate_any(item) {
-                    return Err(ValidationError::new(format!(
-                        "Item at index {} failed validation: {}", i, err
-                    )));
-                }
+impl<R: ?Sized> Each<R> {
+    fn check_items<'a, T: ?Sized + 'a>(
+        &self,
+        items: impl Iterator<Item = &'a T>,
+        ctx: &ValidationContext<'_>,
+    ) -> Result<(), ValidationError>
+    where
+        R: Rule<T>,
+    {
+        let mut errors = ErrorTree::new();
+        for (i, item) in items.enumerate() {
+            if let Err(err) = self.rule.validate_in(item, &ctx.at(i)) {
+                errors.add_error(PathSegment::Index(i), err);
             }
-        } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
-            for (i, item) in vec.iter().enumerate() {
-                if let Err(err) = self.rule.validate_any(item) {
-                    return Err(ValidationError::new(format!(
-                        "Item at index {} failed validation: {}", i, err
-                    )));
-                }
+        }
+        collected(errors)
+    }
+
+    fn check_entries<'a, K: Display + 'a, T: ?Sized + 'a>(
+        &self,
+        entries: impl Iterator<Item = (&'a K, &'a T)>,
+        ctx: &ValidationContext<'_>,
+    ) -> Result<(), ValidationError>
+    where
+        R: Rule<T>,
+    {
+        let mut errors = ErrorTree::new();
+        for (key, val) in entries {
+            let segment = PathSegment::Key(key.to_string());
+            if let Err(err) = self.rule.validate_in(val, &ctx.at(segment.clone())) {
+                errors.add_error(segment, err);
             }
+        }
+        collected(errors)
+    }
+
+    /// Describe the rule's keywords as the schema of the elements under each of the given keywords
+    fn keywords<T: ?Sized>(&self, keywords: &[&str]) -> Keywords
+    where
+        R: Rule<T>,
+    {
+        let schema = self.rule.json_schema();
+        keywords
+            .iter()
+            .fold(Keywords::new(), |acc, keyword| acc.with_subschema(keyword, schema.clone()))
+    }
+
+    /// Describe the rule every element must satisfy
+    fn info<T: ?Sized>(&self) -> RuleInfo
+    where
+        R: Rule<T>,
+    {
+        RuleInfo::new("each", "Every item must satisfy the rule").with_rule(self.rule.describe())
+    }
+}
+
+impl<T, R: Rule<T> + ?Sized> Rule<[T]> for Each<R> {
+    fn validate(&self, value: &[T]) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &[T], ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        self.check_items(value.iter(), ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&["items"])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T, R: Rule<T> + ?Sized> Rule<Vec<T>> for Each<R> {
+    fn validate(&self, value: &Vec<T>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &Vec<T>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        self.check_items(value.iter(), ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&["items"])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<K: Display, T, S, R: Rule<T> + ?Sized> Rule<HashMap<K, T, S>> for Each<R> {
+    fn validate(&self, value: &HashMap<K, T, S>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &HashMap<K, T, S>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        self.check_entries(value.iter(), ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&["additionalProperties"])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<K: Display, T, R: Rule<T> + ?Sized> Rule<BTreeMap<K, T>> for Each<R> {
+    fn validate(&self, value: &BTreeMap<K, T>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &BTreeMap<K, T>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        self.check_entries(value.iter(), ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&["additionalProperties"])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<R: Rule + ?Sized> Rule for Each<R> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &dyn Any, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        if let Some(vec) = value.downcast_ref::<Vec<String>>() {
+            self.check_items(vec.iter().map(|item| item as &dyn Any), ctx)
+        } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
+            self.check_items(vec.iter().map(|item| item as &dyn Any), ctx)
         } else if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
-            for (key, val) in map {
-                if let Err(err) = self.rule.validate_any(val) {
-                    return Err(ValidationError::new(format!(
-                        "Value for key '{}' failed validation: {}", key, err
-                    )));
-                }
-            }
+            self.check_entries(map.iter().map(|(key, val)| (key, val as &dyn Any)), ctx)
         } else {
-            return Err(ValidationError::new("Value must be a collection or map"));
+            Err(invalid_type("Value must be a collection or map", "collection or map"))
         }
-        
-        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&["items", "additionalProperties"])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
 /// Validates a map's keys and values
-pub struct Map {
-    pub key_rule: Option<Box<dyn Rule>>,
-    pub value_rule: Option<Box<dyn Rule>>,
+///
+/// Failures are reported at the entry's path, e.g. `["env"]`; violations
+/// raised by the key rule carry the `part` parameter set to `"key"`.
+pub struct Map<K: ?Sized = dyn Any, V: ?Sized = dyn Any> {
+    pub key_rule: Option<Box<dyn Rule<K>>>,
+    pub value_rule: Option<Box<dyn Rule<V>>>,
+}
+
+impl<K: ?Sized, V: ?Sized> Map<K, V> {
+    fn check_entry<Q: Display + ?Sized>(
+        &self,
+        label: &Q,
+        key: &K,
+        val: &V,
+        ctx: &ValidationContext<'_>,
+        errors: &mut ErrorTree,
+    ) {
+        let path = PathSegment::Key(label.to_string());
+        let ctx = ctx.at(path.clone());
+
+        if let Some(key_rule) = &self.key_rule {
+            if let Err(err) = key_rule.validate_in(key, &ctx) {
+                let err = err.map_violations(|violation| {
+                    violation.params.insert("part".to_string(), "key".into());
+                });
+                errors.add_error(path.clone(), err);
+            }
+        }
+
+        if let Some(value_rule) = &self.value_rule {
+            if let Err(err) = value_rule.validate_in(val, &ctx) {
+                errors.add_error(path, err);
+            }
+        }
+    }
+}
+
+impl<K: ?Sized, V: ?Sized> Map<K, V> {
+    /// Describe key rules as `propertyNames` and value rules as `additionalProperties`
+    fn keywords(&self) -> Keywords {
+        let mut keywords = Keywords::new();
+        if let Some(key_rule) = &self.key_rule {
+            keywords = keywords.with_subschema("propertyNames", key_rule.json_schema());
+        }
+        if let Some(value_rule) = &self.value_rule {
+            keywords = keywords.with_subschema("additionalProperties", value_rule.json_schema());
+        }
+        keywords
+    }
+
+    /// Describe the key rule and the value rule, marking which part of the entries they check
+    fn info(&self) -> RuleInfo {
+        let mut info = RuleInfo::new("map", "Keys and values must satisfy their rules");
+        if let Some(key_rule) = &self.key_rule {
+            info = info.with_rule(key_rule.describe().with_param("part", "key"));
+        }
+        if let Some(value_rule) = &self.value_rule {
+            info = info.with_rule(value_rule.describe().with_param("part", "value"));
+        }
+        info
+    }
+}
+
+impl<K: Display, V, S> Rule<HashMap<K, V, S>> for Map<K, V> {
+    fn validate(&self, value: &HashMap<K, V, S>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &HashMap<K, V, S>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let mut errors = ErrorTree::new();
+        for (key, val) in value {
+            self.check_entry(key, key, val, ctx, &mut errors);
+        }
+        collected(errors)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<K: Display, V> Rule<BTreeMap<K, V>> for Map<K, V> {
+    fn validate(&self, value: &BTreeMap<K, V>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &BTreeMap<K, V>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let mut errors = ErrorTree::new();
+        for (key, val) in value {
+            self.check_entry(key, key, val, ctx, &mut errors);
+        }
+        collected(errors)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 impl Rule for Map {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &dyn Any, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let mut errors = ErrorTree::new();
         if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
             for (key, val) in map {
-                if let Some(key_rule) = &self.key_rule {
-                    if let Err(err) = key_rule.validate_any(key) {
-                        return Err(ValidationError::new(format!(
-                            "Map key '{}' failed validation: {}", key, err
-                        )));
-                    }
-                }
-                
-                if let Some(value_rule) = &self.value_rule {
-                    if let Err(err) = value_rule.validate_any(val) {
-                        return Err(ValidationError::new(format!(
-                            "Map value for key '{}' failed validation: {}", key, err
-                        )));
-                    }
-                }
+                self.check_entry(key, key, val, ctx, &mut errors);
             }
         } else if let Some(map) = value.downcast_ref::<HashMap<String, i32>>() {
             for (key, val) in map {
-                if let Some(key_rule) = &self.key_rule {
-                    if let Err(err) = key_rule.validate_any(key) {
-                        return Err(ValidationError::new(format!(
-                            "Map key '{}' failed validation: {}", key, err
-                        )));
-                    }
-                }
-                
-                if let Some(value_rule) = &self.value_rule {
-                    if let Err(err) = value_rule.validate_any(val) {
-                        return Err(ValidationError::new(format!(
-                            "Map value for key '{}' failed validation: {}", key, err
-                        )));
-                    }
-                }
+                self.check_entry(key, key, val, ctx, &mut errors);
             }
         } else {
-            return Err(ValidationError::new(
-                "Value must be a map"
+            return Err(invalid_type(
+                "Value must be a map",
+                "map",
             ));
         }
-        
-        Ok(())
+
+        collected(errors)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Build the error reported by the size rules
+fn size_error(code: &str, message: String, param: &str, expected: usize, actual: usize) -> ValidationError {
+    Violation::new(code, message)
+        .with_param(param, &expected)
+        .with_param("actual", &actual)
+        .into()
+}
+
+/// Describe what the size of a value counts, e.g. `Collection must have ... items`
+fn size_terms(kind: LengthKind) -> (&'static str, &'static str) {
+    match kind {
+        LengthKind::Text => ("String", "characters"),
+        LengthKind::Items => ("Collection", "items"),
+        LengthKind::Entries => ("Map", "entries"),
+    }
+}
+
+/// Get the length of a dynamically typed value supported by the size rules
+fn any_length(value: &dyn Any) -> Result<(usize, LengthKind), ValidationError> {
+    if let Some(vec) = value.downcast_ref::<Vec<String>>() {
+        Ok((vec.length(), LengthKind::Items))
+    } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
+        Ok((vec.length(), LengthKind::Items))
+    } else if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
+        Ok((map.length(), LengthKind::Entries))
+    } else if let Some(s) = value.downcast_ref::<String>() {
+        Ok((s.length(), LengthKind::Text))
+    } else {
+        Err(invalid_type(
+            "Value must be a collection, map, or string",
+            "collection, map, or string",
+        ))
     }
 }
 
@@ -168,40 +522,59 @@ pub struct MinSize {
     pub min: usize,
 }
 
-impl Rule for MinSize {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(vec) = value.downcast_ref::<Vec<String>>() {
-            if vec.len() < self.min {
-                return Err(ValidationError::new(format!(
-                    "Collection must have at least {} items", self.min
-                )));
-            }
-        } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
-            if vec.len() < self.min {
-                return Err(ValidationError::new(format!(
-                    "Collection must have at least {} items", self.min
-                )));
-            }
-        } else if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
-            if map.len() < self.min {
-                return Err(ValidationError::new(format!(
-                    "Map must have at least {} entries", self.min
-                )));
-            }
-        } else if let Some(s) = value.downcast_ref::<String>() {
-            if s.len() < self.min {
-                return Err(ValidationError::new(format!(
-                    "String must have at least {} characters", self.min
-                )));
-            }
-        } else {
-            return Err(ValidationError::new(
-                "Value must be a collection, map, or string"
-            ));
+impl MinSize {
+    fn check(&self, len: usize, kind: LengthKind) -> Result<(), ValidationError> {
+        if len < self.min {
+            let (subject, unit) = size_terms(kind);
+            return Err(size_error("min_size", format!(
+                "{} must have at least {} {}", subject, self.min, unit
+            ), "min", self.min, len));
         }
-        
+
         Ok(())
     }
+
+    /// Describe the minimum for each of the kinds of length given
+    fn keywords(&self, kinds: &[LengthKind]) -> Keywords {
+        let mut keywords = Keywords::new();
+        for &kind in kinds {
+            keywords.merge(Keywords::min_length(kind, self.min));
+        }
+        keywords
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("min_size", format!("Size must be at least {}", self.min)).with_param("min", &self.min)
+    }
+}
+
+impl<T: HasLength + ?Sized> Rule<T> for MinSize {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.check(value.length(), T::KIND)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[T::KIND])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for MinSize {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        let (len, kind) = any_length(value)?;
+        self.check(len, kind)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&LengthKind::ALL)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 /// Validates that a collection has a maximum size
@@ -209,40 +582,59 @@ pub struct MaxSize {
     pub max: usize,
 }
 
-impl Rule for MaxSize {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(vec) = value.downcast_ref::<Vec<String>>() {
-            if vec.len() > self.max {
-                return Err(ValidationError::new(format!(
-                    "Collection must have at most {} items", self.max
-                )));
-            }
-        } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
-            if vec.len() > self.max {
-                return Err(ValidationError::new(format!(
-                    "Collection must have at most {} items", self.max
-                )));
-            }
-        } else if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
-            if map.len() > self.max {
-                return Err(ValidationError::new(format!(
-                    "Map must have at most {} entries", self.max
-                )));
-            }
-        } else if let Some(s) = value.downcast_ref::<String>() {
-            if s.len() > self.max {
-                return Err(ValidationError::new(format!(
-                    "String must have at most {} characters", self.max
-                )));
-            }
-        } else {
-            return Err(ValidationError::new(
-                "Value must be a collection, map, or string"
-            ));
+impl MaxSize {
+    fn check(&self, len: usize, kind: LengthKind) -> Result<(), ValidationError> {
+        if len > self.max {
+            let (subject, unit) = size_terms(kind);
+            return Err(size_error("max_size", format!(
+                "{} must have at most {} {}", subject, self.max, unit
+            ), "max", self.max, len));
         }
-        
+
         Ok(())
     }
+
+    /// Describe the maximum for each of the kinds of length given
+    fn keywords(&self, kinds: &[LengthKind]) -> Keywords {
+        let mut keywords = Keywords::new();
+        for &kind in kinds {
+            keywords.merge(Keywords::max_length(kind, self.max));
+        }
+        keywords
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("max_size", format!("Size must be at most {}", self.max)).with_param("max", &self.max)
+    }
+}
+
+impl<T: HasLength + ?Sized> Rule<T> for MaxSize {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.check(value.length(), T::KIND)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[T::KIND])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for MaxSize {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        let (len, kind) = any_length(value)?;
+        self.check(len, kind)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&LengthKind::ALL)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 /// Validates that a collection has an exact size
@@ -250,38 +642,88 @@ pub struct ExactSize {
     pub size: usize,
 }
 
-impl Rule for ExactSize {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(vec) = value.downcast_ref::<Vec<String>>() {
-            if vec.len() != self.size {
-                return Err(ValidationError::new(format!(
-                    "Collection must have exactly {} items", self.size
-                )));
-            }
-        } else if let Some(vec) = value.downcast_ref::<Vec<i32>>() {
-            if vec.len() != self.size {
-                return Err(ValidationError::new(format!(
-                    "Collection must have exactly {} items", self.size
-                )));
-            }
-        } else if let Some(map) = value.downcast_ref::<HashMap<String, String>>() {
-            if map.len() != self.size {
-                return Err(ValidationError::new(format!(
-                    "Map must have exactly {} entries", self.size
-                )));
-            }
-        } else if let Some(s) = value.downcast_ref::<String>() {
-            if s.len() != self.size {
-                return Err(ValidationError::new(format!(
-                    "String must have exactly {} characters", self.size
-                )));
-            }
-        } else {
-            return Err(ValidationError::new(
-                "Value must be a collection, map, or string"
-            ));
+impl ExactSize {
+    fn check(&self, len: usize, kind: LengthKind) -> Result<(), ValidationError> {
+        if len != self.size {
+            let (subject, unit) = size_terms(kind);
+            return Err(size_error("exact_size", format!(
+                "{} must have exactly {} {}", subject, self.size, unit
+            ), "size", self.size, len));
         }
-        
+
         Ok(())
     }
+
+    /// Describe the size as both bounds for each of the kinds of length given
+    fn keywords(&self, kinds: &[LengthKind]) -> Keywords {
+        let mut keywords = Keywords::new();
+        for &kind in kinds {
+            keywords.merge(Keywords::min_length(kind, self.size));
+            keywords.merge(Keywords::max_length(kind, self.size));
+        }
+        keywords
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("exact_size", format!("Size must be exactly {}", self.size)).with_param("size", &self.size)
+    }
+}
+
+impl<T: HasLength + ?Sized> Rule<T> for ExactSize {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.check(value.length(), T::KIND)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[T::KIND])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for ExactSize {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        let (len, kind) = any_length(value)?;
+        self.check(len, kind)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&LengthKind::ALL)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::common::Email;
+
+    #[test]
+    fn dynamic_size_rules_describe_every_kind_of_length() {
+        let keywords = Rule::<dyn Any>::json_schema(&MinSize { min: 2 }).into_value();
+        assert_eq!(keywords, json!({ "minLength": 2, "minItems": 2, "minProperties": 2 }));
+
+        let keywords = Rule::<dyn Any>::json_schema(&ExactSize { size: 1 }).into_value();
+        assert_eq!(keywords["maxItems"], json!(1));
+        assert_eq!(keywords["minProperties"], json!(1));
+    }
+
+    #[test]
+    fn dynamic_each_describes_items_and_entries() {
+        let each: Each<dyn Rule> = Each { rule: Box::new(Email { check_dns: false }) };
+        let keywords = Rule::<dyn Any>::json_schema(&each);
+        assert_eq!(keywords.get("items"), Some(&json!({ "format": "email" })));
+        assert_eq!(keywords.get("additionalProperties"), Some(&json!({ "format": "email" })));
+    }
+
+    #[test]
+    fn dynamic_map_describes_its_rules() {
+        let map: Map = Map { key_rule: None, value_rule: Some(Box::new(Email { check_dns: false })) };
+        assert_eq!(Rule::<dyn Any>::json_schema(&map).get("additionalProperties"), Some(&json!({ "format": "email" })));
+    }
 }
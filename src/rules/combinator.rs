@@ -0,0 +1,518 @@
+use std::any::Any;
+use std::marker::PhantomData;
+
+use crate::context::ValidationContext;
+use crate::error::{ValidationError, Violation};
+use crate::json_schema::Keywords;
+use crate::rules::common::presence_of;
+use crate::rules::{HasLength, Rule, RuleInfo};
+use crate::validator::DEFAULT_GROUP;
+
+/// Combinators available on every rule
+///
+/// ```
+/// # use rustvalidity::rules::prelude::*;
+/// # use rustvalidity::rules::Rule;
+/// # fn main() -> Result<(), rustvalidity::ValidationError> {
+/// let reserved = OneOf { values: vec!["admin".to_string(), "root".to_string()] };
+///
+/// let contact = Email { check_dns: false }.or(Phone { allow_empty: false });
+/// let nickname = Length { min: 3, max: Some(20) }.optional();
+/// let username = Required.and(RegexRule::new("^[a-z]+$")?).and(reserved.not());
+///
+/// assert!(Rule::<str>::validate(&contact, "+14155552671").is_ok());
+/// assert!(Rule::<Option<String>>::validate(&nickname, &None).is_ok());
+/// assert!(Rule::<String>::validate(&username, &"ada".to_string()).is_ok());
+/// assert!(Rule::<String>::validate(&username, &"admin".to_string()).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub trait RuleExt<T: ?Sized>: Rule<T> + Sized {
+    /// Pass only if both rules pass; `other` is skipped once `self` reports an error
+    fn and<R: Rule<T>>(self, other: R) -> And<Self, R, T> {
+        And {
+            first: self,
+            second: other,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pass if either rule passes
+    fn or<R: Rule<T>>(self, other: R) -> Or<Self, R, T> {
+        Or {
+            first: self,
+            second: other,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Pass only if the rule fails
+    fn not(self) -> Not<Self, T> {
+        Not::new(self)
+    }
+
+    /// Skip the rule for absent values: `None`, empty strings and empty collections
+    fn optional(self) -> Optional<Self, T> {
+        Optional {
+            rule: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Only apply the rule when one of the groups is checked, e.g. `["create"]`
+    fn in_groups<I, S>(self, groups: I) -> Grouped<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Grouped::new(self, groups)
+    }
+
+    /// Box the rule, e.g. to collect rules of different types in a `Vec`
+    fn boxed(self) -> Box<dyn Rule<T>>
+    where
+        Self: 'static,
+    {
+        Box::new(self)
+    }
+}
+
+impl<T: ?Sized, R: Rule<T>> RuleExt<T> for R {}
+
+/// Passes if every rule passes; every failing rule is reported
+pub fn all_of<T: ?Sized>(rules: Vec<Box<dyn Rule<T>>>) -> AllOf<T> {
+    AllOf { rules }
+}
+
+/// Passes if at least one rule passes, keeping the warnings of the rule that passed
+///
+/// Without any rule, no value passes.
+pub fn any_of<T: ?Sized>(rules: Vec<Box<dyn Rule<T>>>) -> AnyOf<T> {
+    AnyOf { rules }
+}
+
+/// Merge the outcome of two checks, keeping every violation
+fn merged(first: Result<(), ValidationError>, second: Result<(), ValidationError>) -> Result<(), ValidationError> {
+    match (first, second) {
+        (Ok(()), Ok(())) => Ok(()),
+        (Err(err), Ok(())) | (Ok(()), Err(err)) => Err(err),
+        (Err(first), Err(second)) => Err(first.merge(second)),
+    }
+}
+
+/// Describe two rules that must both pass, flattening chains of [`RuleExt::and`]
+fn all_rules(first: RuleInfo, second: RuleInfo) -> RuleInfo {
+    let mut info = RuleInfo::new("all_of", "Value must satisfy every rule");
+    for rule in [first, second] {
+        if rule.name == "all_of" && rule.params.is_empty() {
+            info.rules.extend(rule.rules);
+        } else {
+            info.rules.push(rule);
+        }
+    }
+    info
+}
+
+/// Build the error reported when none of the alternatives passed
+///
+/// An `any_of` violation listing the codes of the failed rules in its
+/// `alternatives` parameter comes first, followed by the errors of every
+/// alternative with their own paths, parameters and values.
+fn no_alternative(errors: Vec<ValidationError>) -> ValidationError {
+    let codes: Vec<&str> = errors
+        .iter()
+        .flat_map(ValidationError::violations)
+        .map(|violation| violation.code.as_str())
+        .collect();
+    let summary = Violation::new("any_of", "Value must satisfy at least one of the alternatives").with_param("alternatives", &codes);
+    errors.into_iter().fold(summary.into(), ValidationError::merge)
+}
+
+/// Rule created by [`RuleExt::and`]
+pub struct And<A, B, T: ?Sized> {
+    first: A,
+    second: B,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized, A: Rule<T>, B: Rule<T>> Rule<T> for And<A, B, T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        match self.first.validate_in(value, ctx) {
+            Err(err) if err.is_fatal() => Err(err),
+            first => merged(first, self.second.validate_in(value, ctx)),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        let mut keywords = self.first.json_schema();
+        keywords.merge(self.second.json_schema());
+        keywords
+    }
+
+    fn describe(&self) -> RuleInfo {
+        all_rules(self.first.describe(), self.second.describe())
+    }
+}
+
+/// Rule created by [`RuleExt::or`]
+///
+/// The first alternative that passes decides, with its warnings. When both
+/// alternatives fail, an `any_of` violation whose `alternatives`
+/// parameter lists the codes of the failed rules is reported together with
+/// the errors of both alternatives.
+pub struct Or<A, B, T: ?Sized> {
+    first: A,
+    second: B,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized, A: Rule<T>, B: Rule<T>> Rule<T> for Or<A, B, T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let first = match self.first.validate_in(value, ctx) {
+            Err(err) if err.is_fatal() => err,
+            passed => return passed,
+        };
+        match self.second.validate_in(value, ctx) {
+            Err(second) if second.is_fatal() => Err(no_alternative(vec![first, second])),
+            passed => passed,
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::any_of([self.first.json_schema(), self.second.json_schema()])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("any_of", "Value must satisfy at least one rule")
+            .with_rule(self.first.describe())
+            .with_rule(self.second.describe())
+    }
+}
+
+/// Rule created by [`RuleExt::not`], reporting the `not` code when the wrapped rule passes
+pub struct Not<R, T: ?Sized> {
+    rule: R,
+    message: String,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<R, T: ?Sized> Not<R, T> {
+    /// Negate a rule
+    pub fn new(rule: R) -> Self {
+        Not {
+            rule,
+            message: "Value must not satisfy the rule".to_string(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the message reported when the wrapped rule passes
+    pub fn with_message<M: Into<String>>(mut self, message: M) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+impl<T: ?Sized, R: Rule<T>> Rule<T> for Not<R, T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        match self.rule.validate_in(value, ctx) {
+            Err(err) if err.is_fatal() => Ok(()),
+            _ => Err(Violation::new("not", self.message.as_str()).into()),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        let keywords = self.rule.json_schema();
+        if keywords.is_empty() {
+            return Keywords::new();
+        }
+        Keywords::new().with("not", keywords.into_value())
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("not", self.message.as_str()).with_rule(self.rule.describe())
+    }
+}
+
+/// Rule created by [`RuleExt::optional`]
+pub struct Optional<R, T: ?Sized> {
+    rule: R,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized, R: Rule<T>> Optional<R, T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("optional", "Value may be absent; otherwise it must satisfy the rule").with_rule(self.rule.describe())
+    }
+}
+
+impl<T, R: Rule<T>> Rule<Option<T>> for Optional<R, T> {
+    fn validate(&self, value: &Option<T>) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &Option<T>, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        match value {
+            Some(value) => self.rule.validate_in(value, ctx),
+            None => Ok(()),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.rule.json_schema().optional()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: HasLength + ?Sized, R: Rule<T>> Rule<T> for Optional<R, T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        if value.length() == 0 {
+            return Ok(());
+        }
+        self.rule.validate_in(value, ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        // An empty value is accepted too, which keywords cannot express without knowing the type
+        let empty = Keywords::max_length(T::KIND, 0);
+        Keywords::any_of([empty, self.rule.json_schema().optional()])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Unwraps `Option<String>`, `Option<&str>` and `Option<i32>` before applying the wrapped rule
+impl<R: Rule> Rule for Optional<R, dyn Any> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &dyn Any, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        if let Some(option) = value.downcast_ref::<Option<String>>() {
+            return option.as_ref().map_or(Ok(()), |inner| self.rule.validate_in(inner, ctx));
+        }
+        if let Some(option) = value.downcast_ref::<Option<&str>>() {
+            return option.as_ref().map_or(Ok(()), |inner| self.rule.validate_in(inner, ctx));
+        }
+        if let Some(option) = value.downcast_ref::<Option<i32>>() {
+            return option.as_ref().map_or(Ok(()), |inner| self.rule.validate_in(inner, ctx));
+        }
+        if presence_of(value) == Some(false) {
+            return Ok(());
+        }
+        self.rule.validate_in(value, ctx)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.rule.json_schema().optional()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Rule created by [`RuleExt::in_groups`]
+///
+/// Outside its groups the rule passes without being checked. Rules without
+/// groups belong to the [default group](crate::validator::DEFAULT_GROUP),
+/// which is also the group JSON Schema exports describe.
+pub struct Grouped<R> {
+    rule: R,
+    groups: Vec<String>,
+}
+
+impl<R> Grouped<R> {
+    /// Tag a rule with the groups it belongs to
+    pub fn new<I, S>(rule: R, groups: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Grouped {
+            rule,
+            groups: groups.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<T: ?Sized, R: Rule<T>> Rule<T> for Grouped<R> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        if !ctx.selects(Some(&self.groups)) {
+            return Ok(());
+        }
+        self.rule.validate_in(value, ctx)
+    }
+
+    fn groups(&self) -> Option<&[String]> {
+        Some(&self.groups)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        if !self.groups.iter().any(|group| group == DEFAULT_GROUP) {
+            return Keywords::new();
+        }
+        self.rule.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.rule.describe().with_param("groups", &self.groups)
+    }
+}
+
+/// Rule created by [`all_of`]
+pub struct AllOf<T: ?Sized = dyn Any> {
+    pub rules: Vec<Box<dyn Rule<T>>>,
+}
+
+impl<T: ?Sized> Rule<T> for AllOf<T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let mut result = Ok(());
+        for rule in &self.rules {
+            result = merged(result, rule.validate_in(value, ctx));
+        }
+        result
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::from_rules(&self.rules)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo {
+            rules: self.rules.iter().map(|rule| rule.describe()).collect(),
+            ..RuleInfo::new("all_of", "Value must satisfy every rule")
+        }
+    }
+}
+
+/// Rule created by [`any_of`]
+///
+/// Reports an `any_of` violation and the errors of every rule when no rule passes, like [`RuleExt::or`].
+pub struct AnyOf<T: ?Sized = dyn Any> {
+    pub rules: Vec<Box<dyn Rule<T>>>,
+}
+
+impl<T: ?Sized> Rule<T> for AnyOf<T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+        for rule in &self.rules {
+            match rule.validate_in(value, ctx) {
+                Err(err) if err.is_fatal() => errors.push(err),
+                passed => return passed,
+            }
+        }
+        Err(no_alternative(errors))
+    }
+
+    fn json_schema(&self) -> Keywords {
+        Keywords::any_of(self.rules.iter().map(|rule| rule.json_schema()))
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo {
+            rules: self.rules.iter().map(|rule| rule.describe()).collect(),
+            ..RuleInfo::new("any_of", "Value must satisfy at least one rule")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::collection::Each;
+    use crate::error::Severity;
+    use crate::rules::common::{Advisory, Email, Length, Phone};
+    use crate::rules::numeric::{Max, Min};
+
+    #[test]
+    fn or_reports_every_alternative() {
+        let contact = Email { check_dns: false }.or(Phone { allow_empty: false });
+        let err = Rule::<str>::validate(&contact, "nope").unwrap_err();
+        let violations = err.violations();
+        assert_eq!(violations[0].code, "any_of");
+        assert_eq!(violations[0].param("alternatives"), Some(&serde_json::json!(["email.format", "phone.format"])));
+        let codes: Vec<&str> = violations[1..].iter().map(|violation| violation.code.as_str()).collect();
+        assert_eq!(codes, ["email.format", "phone.format"]);
+    }
+
+    #[test]
+    fn or_passes_when_one_alternative_passes() {
+        let contact = Email { check_dns: false }.or(Phone { allow_empty: false });
+        assert!(Rule::<str>::validate(&contact, "ada@example.com").is_ok());
+    }
+
+    #[test]
+    fn any_of_keeps_paths_and_params_of_alternatives() {
+        let rule: AnyOf<Vec<i32>> = any_of(vec![
+            Box::new(Each { rule: Box::new(Min { value: 10 }) }),
+            Box::new(Length { min: 5, max: None }),
+        ]);
+        let err = rule.validate(&vec![20, 3]).unwrap_err();
+        let leaves = err.leaves();
+        assert!(leaves.iter().any(|(path, violation)| path.to_string() == "[1]" && violation.code == "min"));
+        let length = leaves.iter().find(|(path, violation)| path.is_root() && violation.code.starts_with("length")).unwrap();
+        assert_eq!(length.1.param("min"), Some(&serde_json::json!(5)));
+    }
+
+    #[test]
+    fn any_of_without_rules_passes_no_value() {
+        let rule: AnyOf<i32> = any_of(Vec::new());
+        let err = rule.validate(&1).unwrap_err();
+        assert_eq!(err.code(), Some("any_of"));
+        assert!(err.is_fatal());
+        assert_eq!(rule.json_schema().into_value(), serde_json::json!({ "not": {} }));
+    }
+
+    #[test]
+    fn any_of_keeps_the_warnings_of_the_rule_that_passed() {
+        let rule: AnyOf<i32> = any_of(vec![Box::new(Max { value: 0 }), Box::new(Advisory::warning(Min { value: 10 }))]);
+        let err = rule.validate(&5).unwrap_err();
+        assert!(!err.is_fatal());
+        let violations = err.violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!((violations[0].code.as_str(), violations[0].severity), ("min", Severity::Warning));
+        assert!(rule.validate(&-1).is_ok());
+    }
+
+    #[test]
+    fn or_keeps_the_warnings_of_the_alternative_that_passed() {
+        let rule = Max { value: 0 }.or(Advisory::warning(Min { value: 10 }));
+        let err = Rule::<i32>::validate(&rule, &5).unwrap_err();
+        assert!(!err.is_fatal());
+        assert_eq!(err.code(), Some("min"));
+    }
+}
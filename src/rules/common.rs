@@ -1,46 +1,102 @@
+use std::any::Any;
 use std::str::FromStr;
+use std::sync::LazyLock;
 use regex::Regex;
-use chrono::{DateTime, NaiveDate};
-use url::Url;
-use uuid::Uuid;
+use chrono::NaiveDate;
+use serde::Serialize;
 use serde_json::Value;
-use std::fmt::Debug;
 
-use crate::error::ValidationError;
-use crate::rules::Rule;
+use crate::error::{Severity, ValidationError, Violation};
+use crate::json_schema::Keywords;
+use crate::rules::{downcast_str, invalid_type, HasLength, LengthKind, Presence, Rule, RuleInfo};
+
+// Patterns are compiled on first use and shared by every validation
+static EMAIL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap());
+const PHONE_PATTERN: &str = r"\+?\d{10,15}";
+static PHONE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(&format!("^{}$", PHONE_PATTERN)).unwrap());
 
 /// Validates that a value is not empty (strings, collections, options)
 pub struct Required;
 
-impl Rule for Required {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        // Handle String type
-        if let Some(s) = value.downcast_ref::<String>() {
-            if s.is_empty() {
-                return Err(ValidationError::new("Value is required"));
-            }
-        } 
-        // Handle &str type
-        else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            if s.is_empty() {
-                return Err(ValidationError::new("Value is required"));
-            }
-        } 
-        // Handle Option types
-        else if let Some(o) = (value as &dyn std::any::Any).downcast_ref::<Option<String>>() {
-            if o.is_none() {
-                return Err(ValidationError::new("Value is required"));
-            }
+impl Required {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required", "Value is required")
+    }
+
+    /// Mark the property as required and, for the kinds of length given, as not empty
+    fn keywords(&self, kinds: &[LengthKind]) -> Keywords {
+        let mut keywords = Keywords::new().required();
+        for &kind in kinds {
+            keywords.merge(Keywords::min_length(kind, 1));
         }
-        // Handle Vec types
-        else if let Some(v) = (value as &dyn std::any::Any).downcast_ref::<Vec<String>>() {
-            if v.is_empty() {
-                return Err(ValidationError::new("Value is required"));
-            }
+        keywords
+    }
+}
+
+impl<T: Presence + ?Sized> Rule<T> for Required {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        if !value.is_present() {
+            return Err(required_error());
         }
-        
+
         Ok(())
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(T::LENGTH_KIND.as_slice())
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for Required {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match presence_of(value) {
+            Some(true) => Ok(()),
+            Some(false) => Err(required_error()),
+            None => Err(invalid_type(
+                "Value must be a string, collection or option",
+                "string, collection or option",
+            )),
+        }
+    }
+
+    // An option holding an empty string is present, so emptiness cannot be described
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+fn required_error() -> ValidationError {
+    Violation::new("required", "Value is required").into()
+}
+
+/// Check whether a dynamically typed value is present, if its type can be absent
+pub(crate) fn presence_of(value: &dyn Any) -> Option<bool> {
+    if let Some(s) = downcast_str(value) {
+        Some(s.is_present())
+    } else if let Some(o) = value.downcast_ref::<Option<String>>() {
+        Some(o.is_present())
+    } else if let Some(o) = value.downcast_ref::<Option<&str>>() {
+        Some(o.is_present())
+    } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
+        Some(v.is_present())
+    } else if let Some(v) = value.downcast_ref::<Vec<i32>>() {
+        Some(v.is_present())
+    } else if let Some(v) = value.downcast_ref::<serde_json::Value>() {
+        Some(v.is_present())
+    } else {
+        value
+            .downcast_ref::<std::collections::HashMap<String, String>>()
+            .map(|map| map.is_present())
+    }
 }
 
 /// Validates string length
@@ -49,48 +105,96 @@ pub struct Length {
     pub max: Option<usize>,
 }
 
-impl Rule for Length {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        // Handle String type
-        if let Some(s) = value.downcast_ref::<String>() {
-            let len = s.len();
-            if len < self.min {
-                return Err(ValidationError::new(format!("Length must be at least {}", self.min)));
-            }
-            if let Some(max) = self.max {
-                if len > max {
-                    return Err(ValidationError::new(format!("Length must not exceed {}", max)));
-                }
-            }
-        } 
-        // Handle &str type
-        else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            let len = s.len();
-            if len < self.min {
-                return Err(ValidationError::new(format!("Length must be at least {}", self.min)));
-            }
-            if let Some(max) = self.max {
-                if len > max {
-                    return Err(ValidationError::new(format!("Length must not exceed {}", max)));
-                }
+impl Length {
+    fn check(&self, len: usize, kind: LengthKind) -> Result<(), ValidationError> {
+        let is_collection = kind != LengthKind::Text;
+        if len < self.min {
+            let message = if is_collection {
+                format!("Collection must have at least {} items", self.min)
+            } else {
+                format!("Length must be at least {}", self.min)
+            };
+            return Err(self.violation("length.min", message, len));
+        }
+        if let Some(max) = self.max {
+            if len > max {
+                let message = if is_collection {
+                    format!("Collection must not exceed {} items", max)
+                } else {
+                    format!("Length must not exceed {}", max)
+                };
+                return Err(self.violation("length.max", message, len));
             }
-        } 
-        // Handle Vec types
-        else if let Some(v) = (value as &dyn std::any::Any).downcast_ref::<Vec<String>>() {
-            let len = v.len();
-            if len < self.min {
-                return Err(ValidationError::new(format!("Collection must have at least {} items", self.min)));
+        }
+
+        Ok(())
+    }
+
+    fn violation(&self, code: &str, message: String, len: usize) -> ValidationError {
+        Violation::new(code, message)
+            .with_param("min", &self.min)
+            .with_param("max", &self.max)
+            .with_param("actual", &len)
+            .into()
+    }
+
+    fn info(&self) -> RuleInfo {
+        let description = match self.max {
+            Some(max) if self.min > 0 => format!("Length must be between {} and {}", self.min, max),
+            Some(max) => format!("Length must not exceed {}", max),
+            None => format!("Length must be at least {}", self.min),
+        };
+        RuleInfo::new("length", description)
+            .with_param("min", &self.min)
+            .with_param("max", &self.max)
+    }
+
+    /// Describe the bounds for each of the kinds of length given
+    fn keywords(&self, kinds: &[LengthKind]) -> Keywords {
+        let mut keywords = Keywords::new();
+        for &kind in kinds {
+            if self.min > 0 {
+                keywords.merge(Keywords::min_length(kind, self.min));
             }
             if let Some(max) = self.max {
-                if len > max {
-                    return Err(ValidationError::new(format!("Collection must not exceed {} items", max)));
-                }
+                keywords.merge(Keywords::max_length(kind, max));
             }
+        }
+        keywords
+    }
+}
+
+impl<T: HasLength + ?Sized> Rule<T> for Length {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.check(value.length(), T::KIND)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[T::KIND])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for Length {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        if let Some(s) = downcast_str(value) {
+            self.validate(s)
+        } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
+            self.validate(v)
         } else {
-            return Err(ValidationError::new("Value must be a string or collection"));
+            Err(invalid_type("Value must be a string or collection", "string or collection"))
         }
-        
-        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords(&[LengthKind::Text, LengthKind::Items])
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
@@ -99,18 +203,52 @@ pub struct OneOf<T: PartialEq + Clone + 'static> {
     pub values: Vec<T>,
 }
 
-impl<T: PartialEq + Clone + Send + Sync + 'static> Rule for OneOf<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(val) = value.downcast_ref::<T>() {
-            if !self.values.contains(val) {
-                return Err(ValidationError::new(format!("Value must be one of the allowed options")));
-            }
-        } else {
-            return Err(ValidationError::new("Value is not of the expected type"));
+impl<T: PartialEq + Clone + Serialize + 'static> OneOf<T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("one_of", "Value must be one of the allowed options").with_param("values", &self.values)
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("enum", serde_json::to_value(&self.values).unwrap_or_default())
+    }
+}
+
+impl<T: PartialEq + Clone + Serialize + Send + Sync + 'static> Rule<T> for OneOf<T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        if !self.values.contains(value) {
+            return Err(Violation::new("one_of", "Value must be one of the allowed options")
+                .with_param("values", &self.values)
+                .with_value(value)
+                .into());
         }
-        
+
         Ok(())
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialEq + Clone + Serialize + Send + Sync + 'static> Rule for OneOf<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match value.downcast_ref::<T>() {
+            Some(value) => self.validate(value),
+            None => Err(invalid_type("Value is not of the expected type", std::any::type_name::<T>())),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 /// Validates email format
@@ -118,29 +256,72 @@ pub struct Email {
     pub check_dns: bool,
 }
 
+impl Email {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("email", "Value must be a valid email address").with_param("check_dns", &self.check_dns)
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("format", "email")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for Email {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Email {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_email(value, self.check_dns)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Email {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_email(s, self.check_dns)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_email(s, self.check_dns)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_email(email: &str, _check_dns: bool) -> Result<(), ValidationError> {
     // Basic email validation using regex
-    let email_regex = Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap();
-    
-    if !email_regex.is_match(email) {
-        return Err(ValidationError::new("Invalid email format"));
+    if !EMAIL_REGEX.is_match(email) {
+        return Err(Violation::new("email.format", "Invalid email format")
+            .with_value(email)
+            .into());
     }
-    
+
     // DNS validation would be implemented here if check_dns is true
     // For simplicity, we're skipping actual DNS validation
-    
+
     Ok(())
 }
 
@@ -149,16 +330,63 @@ pub struct UrlRule {
     pub allowed_schemes: Option<Vec<String>>,
 }
 
+impl UrlRule {
+    fn info(&self) -> RuleInfo {
+        match &self.allowed_schemes {
+            Some(schemes) => RuleInfo::new("url", format!("Value must be a valid URL with scheme {}", schemes.join(", ")))
+                .with_param("schemes", schemes),
+            None => RuleInfo::new("url", "Value must be a valid URL"),
+        }
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("format", "uri")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for UrlRule {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for UrlRule {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_url(value, &self.allowed_schemes)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for UrlRule {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_url(s, &self.allowed_schemes)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_url(s, &self.allowed_schemes)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_url(url_str: &str, allowed_schemes: &Option<Vec<String>>) -> Result<(), ValidationError> {
@@ -166,58 +394,154 @@ fn validate_url(url_str: &str, allowed_schemes: &Option<Vec<String>>) -> Result<
         Ok(url) => {
             if let Some(schemes) = allowed_schemes {
                 if !schemes.contains(&url.scheme().to_string()) {
-                    return Err(ValidationError::new(format!(
+                    return Err(Violation::new("url.scheme", format!(
                         "URL scheme must be one of: {:?}", schemes
-                    )));
+                    ))
+                    .with_param("schemes", schemes)
+                    .with_value(url_str)
+                    .into());
                 }
             }
             Ok(())
         },
-        Err(_) => Err(ValidationError::new("Invalid URL format")),
+        Err(_) => Err(Violation::new("url.format", "Invalid URL format")
+            .with_value(url_str)
+            .into()),
     }
 }
 
 /// Validates JSON format
 pub struct Json;
 
+impl Json {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("json", "Value must be valid JSON")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("contentMediaType", "application/json")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for Json {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Json {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_json(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Json {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_json(s)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_json(s)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_json(json_str: &str) -> Result<(), ValidationError> {
     match serde_json::from_str::<Value>(json_str) {
         Ok(_) => Ok(()),
-        Err(_) => Err(ValidationError::new("Invalid JSON format")),
+        Err(err) => Err(Violation::new("json.format", "Invalid JSON format")
+            .with_param("line", &err.line())
+            .with_param("column", &err.column())
+            .into()),
     }
 }
 
 /// Validates UUID format
 pub struct UuidRule;
 
+impl UuidRule {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("uuid", "Value must be a valid UUID")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("format", "uuid")
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for UuidRule {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for UuidRule {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_uuid(value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for UuidRule {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_uuid(s)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_uuid(s)
-        } else {
-            Err(ValidationError::new("Value must be a string"))
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_uuid(uuid_str: &str) -> Result<(), ValidationError> {
     match uuid::Uuid::from_str(uuid_str) {
         Ok(_) => Ok(()),
-        Err(_) => Err(ValidationError::new("Invalid UUID format")),
+        Err(_) => Err(Violation::new("uuid.format", "Invalid UUID format")
+            .with_value(uuid_str)
+            .into()),
     }
 }
 
@@ -228,67 +552,181 @@ pub struct Date {
     pub max: Option<NaiveDate>,
 }
 
+impl Date {
+    fn info(&self) -> RuleInfo {
+        let mut description = format!("Value must be a date in the format {}", self.format);
+        if let Some(min) = self.min {
+            description.push_str(&format!(", not before {}", min));
+        }
+        if let Some(max) = self.max {
+            description.push_str(&format!(", not after {}", max));
+        }
+        RuleInfo::new("date", description)
+            .with_param("format", &self.format)
+            .with_param("min", &self.min.map(|min| min.to_string()))
+            .with_param("max", &self.max.map(|max| max.to_string()))
+    }
+
+    /// Describe dates in the ISO 8601 format, the only one JSON Schema has a format for
+    fn keywords(&self) -> Keywords {
+        if self.format == "%Y-%m-%d" {
+            Keywords::new().with("format", "date")
+        } else {
+            Keywords::new()
+        }
+    }
+}
+
+impl Rule<str> for Date {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_date(value, &self.format, &self.min, &self.max)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<String> for Date {
+    fn validate(&self, value: &String) -> Result<(), ValidationError> {
+        self.validate(value.as_str())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<&str> for Date {
+    fn validate(&self, value: &&str) -> Result<(), ValidationError> {
+        self.validate(*value)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<NaiveDate> for Date {
+    fn validate(&self, value: &NaiveDate) -> Result<(), ValidationError> {
+        validate_naive_date(value, &self.min, &self.max)
+    }
+
+    // Dates serialize in the ISO 8601 format whatever the rule's format
+    fn json_schema(&self) -> Keywords {
+        Keywords::new().with("format", "date")
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Date {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_date(s, &self.format, &self.min, &self.max)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_date(s, &self.format, &self.min, &self.max)
-        } else if let Some(date) = (value as &dyn std::any::Any).downcast_ref::<NaiveDate>() {
-            validate_naive_date(date, &self.min, &self.max)
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        if let Some(s) = downcast_str(value) {
+            self.validate(s)
+        } else if let Some(date) = value.downcast_ref::<NaiveDate>() {
+            self.validate(date)
         } else {
-            Err(ValidationError::new("Value must be a string or date"))
+            Err(invalid_type("Value must be a string or date", "string or date"))
         }
     }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_date(
-    date_str: &str, 
-    format: &str, 
-    min: &Option<NaiveDate>, 
+    date_str: &str,
+    format: &str,
+    min: &Option<NaiveDate>,
     max: &Option<NaiveDate>
 ) -> Result<(), ValidationError> {
     match NaiveDate::parse_from_str(date_str, format) {
         Ok(date) => validate_naive_date(&date, min, max),
-        Err(_) => Err(ValidationError::new(format!("Invalid date format, expected {}", format))),
+        Err(_) => Err(Violation::new("date.format", format!("Invalid date format, expected {}", format))
+            .with_param("format", format)
+            .with_value(date_str)
+            .into()),
     }
 }
 
 fn validate_naive_date(
-    date: &NaiveDate, 
-    min: &Option<NaiveDate>, 
+    date: &NaiveDate,
+    min: &Option<NaiveDate>,
     max: &Option<NaiveDate>
 ) -> Result<(), ValidationError> {
     if let Some(min_date) = min {
         if date < min_date {
-            return Err(ValidationError::new(format!("Date must not be before {}", min_date)));
+            return Err(Violation::new("date.min", format!("Date must not be before {}", min_date))
+                .with_param("min", &min_date.to_string())
+                .with_value(&date.to_string())
+                .into());
         }
     }
-    
+
     if let Some(max_date) = max {
         if date > max_date {
-            return Err(ValidationError::new(format!("Date must not be after {}", max_date)));
+            return Err(Violation::new("date.max", format!("Date must not be after {}", max_date))
+                .with_param("max", &max_date.to_string())
+                .with_value(&date.to_string())
+                .into());
         }
     }
-    
+
     Ok(())
 }
 
 /// Custom validation rule using a closure
-pub struct Custom<F>
+///
+/// The closure's argument type selects the values the rule checks, e.g.
+/// `|s: &String| ...` or `|value: &dyn Any| ...` for the dynamically typed form.
+pub struct Custom<F> {
+    pub validator: F,
+}
+
+impl<T, F> Rule<T> for Custom<F>
 where
-    F: for<'a> Fn(&'a dyn std::any::Any) -> Result<(), ValidationError> + Send + Sync,
+    F: Fn(&T) -> Result<(), ValidationError> + Send + Sync,
 {
-    pub validator: F,
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        (self.validator)(value)
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("custom", "Custom check")
+    }
 }
 
 impl<F> Rule for Custom<F>
 where
-    F: for<'a> Fn(&'a dyn std::any::Any) -> Result<(), ValidationError> + Send + Sync,
+    F: for<'a> Fn(&'a dyn Any) -> Result<(), ValidationError> + Send + Sync,
 {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
         (self.validator)(value)
     }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("custom", "Custom check")
+    }
 }
 
 /// Phone number validation
@@ -296,29 +734,190 @@ pub struct Phone {
     pub allow_empty: bool,
 }
 
-impl Rule for Phone {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(s) = value.downcast_ref::<String>() {
-            validate_phone(s, self.allow_empty)
-        } else if let Some(s) = (value as &dyn std::any::Any).downcast_ref::<&str>() {
-            validate_phone(s, self.allow_empty)
+impl Phone {
+    fn keywords(&self) -> Keywords {
+        if self.allow_empty {
+            Keywords::new().with("pattern", format!("^({})?$", PHONE_PATTERN))
         } else {
-            Err(ValidationError::new("Value must be a string"))
+            Keywords::new().with("pattern", format!("^{}$", PHONE_PATTERN))
         }
     }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("phone", "Value must be a phone number of 10 to 15 digits").with_param("allow_empty", &self.allow_empty)
+    }
+}
+
+impl<T: AsRef<str>> Rule<T> for Phone {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value.as_ref())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule<str> for Phone {
+    fn validate(&self, value: &str) -> Result<(), ValidationError> {
+        validate_phone(value, self.allow_empty)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl Rule for Phone {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        match downcast_str(value) {
+            Some(s) => self.validate(s),
+            None => Err(invalid_type("Value must be a string", "string")),
+        }
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
 }
 
 fn validate_phone(phone: &str, allow_empty: bool) -> Result<(), ValidationError> {
     if phone.is_empty() && allow_empty {
         return Ok(());
     }
-    
+
     // Basic phone validation: +1234567890 or 1234567890
-    let phone_regex = Regex::new(r"^\+?\d{10,15}$").unwrap();
-    
-    if !phone_regex.is_match(phone) {
-        return Err(ValidationError::new("Invalid phone number format"));
+    if !PHONE_REGEX.is_match(phone) {
+        return Err(Violation::new("phone.format", "Invalid phone number format")
+            .with_value(phone)
+            .into());
     }
-    
+
     Ok(())
 }
+
+/// Reports the violations of the wrapped rule with a non-fatal severity
+///
+/// Useful for advisory checks such as "password is weak but acceptable": the
+/// findings show up in a [`ValidationReport`](crate::report::ValidationReport)
+/// without making validation fail.
+pub struct Advisory<R> {
+    pub rule: R,
+    pub severity: Severity,
+}
+
+impl<R> Advisory<R> {
+    /// Report the violations of a rule as warnings
+    pub fn warning(rule: R) -> Self {
+        Advisory { rule, severity: Severity::Warning }
+    }
+
+    /// Report the violations of a rule as informational findings
+    pub fn info(rule: R) -> Self {
+        Advisory { rule, severity: Severity::Info }
+    }
+}
+
+impl<T: ?Sized, R: Rule<T>> Rule<T> for Advisory<R> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.rule
+            .validate(value)
+            .map_err(|err| err.with_severity(self.severity))
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.rule.describe().with_param("severity", &self.severity)
+    }
+}
+
+/// Warns when a deprecated field holds a value
+pub struct Deprecated {
+    pub message: Option<String>,
+}
+
+impl Deprecated {
+    fn check(&self, present: bool) -> Result<(), ValidationError> {
+        if !present {
+            return Ok(());
+        }
+
+        let message = self.message.as_deref().unwrap_or("Field is deprecated");
+        Err(Violation::warning("deprecated", message).into())
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("deprecated", "Field is deprecated and should not be set").with_param("message", &self.message)
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("deprecated", true)
+    }
+}
+
+impl<T: Presence + ?Sized> Rule<T> for Deprecated {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.check(value.is_present())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+/// Values that cannot be absent, such as numbers, always count as present
+impl Rule for Deprecated {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.check(presence_of(value).unwrap_or(true))
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn length_counts_characters() {
+        let length = Length { min: 0, max: Some(4) };
+        assert!(length.validate("日本語です").is_err());
+        assert!(length.validate("日本語").is_ok());
+        assert_eq!(Rule::<str>::json_schema(&length).into_value(), json!({ "maxLength": 4 }));
+    }
+
+    #[test]
+    fn dynamic_length_describes_strings_and_collections() {
+        let keywords = Rule::<dyn Any>::json_schema(&Length { min: 1, max: None }).into_value();
+        assert_eq!(keywords, json!({ "minLength": 1, "minItems": 1 }));
+    }
+
+    #[test]
+    fn dates_are_described_in_iso_format() {
+        let date = Date { format: "%d/%m/%Y".to_string(), min: None, max: None };
+        assert!(Rule::<str>::json_schema(&date).is_empty());
+        assert_eq!(Rule::<NaiveDate>::json_schema(&date).get("format"), Some(&json!("date")));
+    }
+}
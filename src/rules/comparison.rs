@@ -0,0 +1,350 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, TimeZone};
+
+use crate::error::{ValidationError, Violation};
+use crate::path::FieldPath;
+use crate::rules::{Rule, RuleInfo};
+
+/// Reads a field of a struct
+pub type Accessor<S, V> = Box<dyn Fn(&S) -> &V + Send + Sync>;
+
+/// Relation that must hold between two values
+type Check<V> = Box<dyn Fn(&V, &V) -> bool + Send + Sync>;
+
+/// Values with a distance between them, checked by [`FieldRef::within`]
+pub trait Distance {
+    /// The type the distance is measured in, e.g. [`TimeDelta`] for dates
+    type Delta: PartialOrd;
+
+    /// The absolute difference between two values
+    fn distance(&self, other: &Self) -> Self::Delta;
+
+    /// Check whether two values are at most `tolerance` apart
+    fn is_within(&self, other: &Self, tolerance: &Self::Delta) -> bool {
+        self.distance(other) <= *tolerance
+    }
+
+    /// Render a distance in messages, e.g. `30 days`
+    fn describe(delta: &Self::Delta) -> String;
+}
+
+/// Render a time span in the largest unit dividing it
+fn describe_time(delta: &TimeDelta) -> String {
+    let seconds = delta.num_seconds();
+    let (amount, unit) = match seconds {
+        s if s != 0 && s % 86_400 == 0 => (s / 86_400, "day"),
+        s if s != 0 && s % 3_600 == 0 => (s / 3_600, "hour"),
+        s if s != 0 && s % 60 == 0 => (s / 60, "minute"),
+        s => (s, "second"),
+    };
+    if amount == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", amount, unit)
+    }
+}
+
+macro_rules! impl_distance_for_integer {
+    ($($ty:ty),*) => {
+        $(impl Distance for $ty {
+            type Delta = $ty;
+
+            /// Saturates at the largest value when the distance between signed extremes does not fit the type
+            fn distance(&self, other: &Self) -> $ty {
+                <$ty>::try_from(self.abs_diff(*other)).unwrap_or(<$ty>::MAX)
+            }
+
+            fn is_within(&self, other: &Self, tolerance: &$ty) -> bool {
+                // Compared unsigned and widened, so no distance overflows
+                u128::try_from(*tolerance).is_ok_and(|tolerance| self.abs_diff(*other) as u128 <= tolerance)
+            }
+
+            fn describe(delta: &$ty) -> String {
+                delta.to_string()
+            }
+        })*
+    };
+}
+
+impl_distance_for_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_distance_for_float {
+    ($($ty:ty),*) => {
+        $(impl Distance for $ty {
+            type Delta = $ty;
+
+            fn distance(&self, other: &Self) -> $ty {
+                (self - other).abs()
+            }
+
+            fn describe(delta: &$ty) -> String {
+                delta.to_string()
+            }
+        })*
+    };
+}
+
+impl_distance_for_float!(f32, f64);
+
+impl Distance for NaiveDate {
+    type Delta = TimeDelta;
+
+    fn distance(&self, other: &Self) -> TimeDelta {
+        (*self - *other).abs()
+    }
+
+    fn describe(delta: &TimeDelta) -> String {
+        describe_time(delta)
+    }
+}
+
+impl Distance for NaiveDateTime {
+    type Delta = TimeDelta;
+
+    fn distance(&self, other: &Self) -> TimeDelta {
+        (*self - *other).abs()
+    }
+
+    fn describe(delta: &TimeDelta) -> String {
+        describe_time(delta)
+    }
+}
+
+impl<Tz: TimeZone> Distance for DateTime<Tz> {
+    type Delta = TimeDelta;
+
+    fn distance(&self, other: &Self) -> TimeDelta {
+        self.clone().signed_duration_since(other.clone()).abs()
+    }
+
+    fn describe(delta: &TimeDelta) -> String {
+        describe_time(delta)
+    }
+}
+
+/// The field a comparison is about, created with [`Compare::field`]
+pub struct FieldRef<S, V: ?Sized> {
+    name: String,
+    accessor: Accessor<S, V>,
+}
+
+/// Compares two fields of a struct, e.g. `start_date < end_date`
+///
+/// The error is reported at the first field unless another path is chosen with
+/// [`Compare::at`]. Its `other` parameter names the second field; values are not
+/// included, so the rule can compare secrets such as passwords.
+///
+/// ```
+/// # use chrono::NaiveDate;
+/// # use rustvalidity::rules::comparison::Compare;
+/// # use rustvalidity::validator::Validator;
+/// struct Signup {
+///     password: String,
+///     password_confirmation: String,
+///     start_date: NaiveDate,
+///     end_date: NaiveDate,
+/// }
+///
+/// let schema = Validator::for_type::<Signup>()
+///     .rule(Compare::field("password_confirmation", |s: &Signup| &s.password_confirmation)
+///         .equal_to("password", |s| &s.password))
+///     .rule(Compare::field("start_date", |s: &Signup| &s.start_date)
+///         .less_than("end_date", |s| &s.end_date)
+///         .at("end_date"));
+///
+/// let signup = Signup {
+///     password: "correct horse".to_string(),
+///     password_confirmation: "correct hose".to_string(),
+///     start_date: NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(),
+///     end_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+/// };
+/// let err = schema.validate_all(&signup).unwrap_err();
+/// assert_eq!(err.field_violations("password_confirmation").len(), 1);
+/// assert_eq!(err.field_violations("end_date")[0].params["other"], "end_date");
+/// ```
+pub struct Compare<S, V: ?Sized> {
+    left: FieldRef<S, V>,
+    right: FieldRef<S, V>,
+    check: Check<V>,
+    violation: Violation,
+    path: FieldPath,
+}
+
+impl<S: 'static, V: ?Sized + 'static> Compare<S, V> {
+    /// Start a comparison of a field with another one
+    pub fn field<N, A>(name: N, accessor: A) -> FieldRef<S, V>
+    where
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        FieldRef {
+            name: name.into(),
+            accessor: Box::new(accessor),
+        }
+    }
+
+    /// Report the error at another path
+    pub fn at<P: Into<FieldPath>>(mut self, path: P) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Set the message reported when the comparison fails
+    pub fn with_message<M: Into<String>>(mut self, message: M) -> Self {
+        self.violation.message = message.into();
+        self
+    }
+}
+
+impl<S: 'static, V: ?Sized + 'static> FieldRef<S, V> {
+    fn compare<N, A, C>(self, name: N, accessor: A, check: C, code: &str, relation: &str) -> Compare<S, V>
+    where
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+        C: Fn(&V, &V) -> bool + Send + Sync + 'static,
+    {
+        let right = Compare::field(name, accessor);
+        let violation = Violation::new(code, format!("{} must be {} {}", self.name, relation, right.name))
+            .with_param("field", &self.name)
+            .with_param("other", &right.name);
+        Compare {
+            path: FieldPath::from(self.name.as_str()),
+            left: self,
+            right,
+            check: Box::new(check),
+            violation,
+        }
+    }
+
+    /// The field must equal the other field
+    pub fn equal_to<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialEq,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left == right, "compare.equal", "equal to")
+    }
+
+    /// The field must differ from the other field
+    pub fn not_equal_to<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialEq,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left != right, "compare.not_equal", "different from")
+    }
+
+    /// The field must be less than the other field
+    pub fn less_than<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialOrd,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left < right, "compare.less", "less than")
+    }
+
+    /// The field must be less than or equal to the other field
+    pub fn less_or_equal<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialOrd,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left <= right, "compare.less_or_equal", "less than or equal to")
+    }
+
+    /// The field must be greater than the other field
+    pub fn greater_than<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialOrd,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left > right, "compare.greater", "greater than")
+    }
+
+    /// The field must be greater than or equal to the other field
+    pub fn greater_or_equal<N, A>(self, name: N, accessor: A) -> Compare<S, V>
+    where
+        V: PartialOrd,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        self.compare(name, accessor, |left, right| left >= right, "compare.greater_or_equal", "greater than or equal to")
+    }
+
+    /// The field must be at most `tolerance` away from the other field, e.g. two dates within 30 days
+    pub fn within<N, A>(self, name: N, accessor: A, tolerance: V::Delta) -> Compare<S, V>
+    where
+        V: Distance,
+        V::Delta: Send + Sync + 'static,
+        N: Into<String>,
+        A: Fn(&S) -> &V + Send + Sync + 'static,
+    {
+        let param = V::describe(&tolerance);
+        let relation = format!("within {} of", param);
+        let mut compare = self.compare(
+            name,
+            accessor,
+            move |left, right| left.is_within(right, &tolerance),
+            "compare.within",
+            &relation,
+        );
+        compare.violation.params.insert("tolerance".to_string(), param.into());
+        compare
+    }
+}
+
+impl<S, V: ?Sized> Rule<S> for Compare<S, V> {
+    fn validate(&self, value: &S) -> Result<(), ValidationError> {
+        let left = (self.left.accessor)(value);
+        let right = (self.right.accessor)(value);
+        if (self.check)(left, right) {
+            return Ok(());
+        }
+        Err(ValidationError::from(self.violation.clone()).at(self.path.clone()))
+    }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo {
+            params: self.violation.params.clone(),
+            ..RuleInfo::new("compare", self.violation.message.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Span {
+        low: i32,
+        high: i32,
+    }
+
+    fn within(tolerance: i32) -> Compare<Span, i32> {
+        Compare::field("high", |s: &Span| &s.high).within("low", |s| &s.low, tolerance)
+    }
+
+    #[test]
+    fn within_accepts_close_values() {
+        assert!(within(5).validate(&Span { low: 10, high: 14 }).is_ok());
+        assert!(within(5).validate(&Span { low: 14, high: 10 }).is_ok());
+    }
+
+    #[test]
+    fn within_does_not_overflow_at_signed_extremes() {
+        let err = within(10).validate(&Span { low: i32::MIN, high: i32::MAX }).unwrap_err();
+        assert_eq!(err.violations()[0].code, "compare.within");
+        assert!(within(i32::MAX).validate(&Span { low: -1, high: i32::MAX }).is_err());
+        assert!(within(i32::MAX).validate(&Span { low: 0, high: i32::MAX }).is_ok());
+    }
+
+    #[test]
+    fn within_never_accepts_a_negative_tolerance() {
+        assert!(within(-1).validate(&Span { low: 3, high: 3 }).is_err());
+        assert_eq!(i32::MIN.distance(&i32::MAX), i32::MAX);
+    }
+}
@@ -1,200 +1,285 @@
+use std::any::Any;
+
+use serde::Serialize;
+
+use crate::context::ValidationContext;
 use crate::error::ValidationError;
-use crate::rules::Rule;
+use crate::rules::common::Required;
+use crate::rules::{Presence, Rule, RuleInfo};
+
+/// A predicate over the value being validated
+pub type ValueCondition<T = dyn Any> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// A predicate over the context of the value being validated, e.g. a sibling field read from the parent
+pub type ContextCondition = Box<dyn Fn(&ValidationContext<'_>) -> bool + Send + Sync>;
+
+/// Reads another value from the context of the value being validated
+pub type ContextValue<T> = Box<dyn Fn(&ValidationContext<'_>) -> Option<T> + Send + Sync>;
 
 /// Validates a value only if a condition is true
-pub struct If {
-    pub condition: Box<dyn for<'a> Fn(&'a dyn std::any::Any) -> bool + Send + Sync>,
-    pub then: Box<dyn Rule>,
+pub struct If<T: ?Sized = dyn Any> {
+    pub condition: ValueCondition<T>,
+    pub then: Box<dyn Rule<T>>,
 }
 
-impl Rule for If {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+impl<T: ?Sized> Rule<T> for If<T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
         if (self.condition)(value) {
-            self.then.validate_any(value)
+            self.then.validate_in(value, ctx)
         } else {
             Ok(())
         }
     }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("if", "Value must satisfy the rule when the condition holds").with_rule(self.then.describe())
+    }
 }
 
 /// Validates a value only if a condition is false
-pub struct Unless {
-    pub condition: Box<dyn for<'a> Fn(&'a dyn std::any::Any) -> bool + Send + Sync>,
-    pub then: Box<dyn Rule>,
+pub struct Unless<T: ?Sized = dyn Any> {
+    pub condition: ValueCondition<T>,
+    pub then: Box<dyn Rule<T>>,
 }
 
-impl Rule for Unless {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+impl<T: ?Sized> Rule<T> for Unless<T> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
         if !(self.condition)(value) {
-            self.then.validate_any(value)
+            self.then.validate_in(value, ctx)
         } else {
             Ok(())
         }
     }
+
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new("unless", "Value must satisfy the rule unless the condition holds").with_rule(self.then.describe())
+    }
 }
 
 /// Validates that a value is required if a condition is true
+///
+/// ```
+/// # use rustvalidity::context::ValidationContext;
+/// # use rustvalidity::rules::conditional::RequiredIf;
+/// # use rustvalidity::rules::Rule;
+/// struct Signup {
+///     account_type: String,
+///     company: String,
+/// }
+///
+/// let company = RequiredIf {
+///     condition: Box::new(|ctx| ctx.parent::<Signup>().is_some_and(|s| s.account_type == "business")),
+/// };
+///
+/// let signup = Signup { account_type: "business".to_string(), company: String::new() };
+/// let ctx = ValidationContext::new().with_parent(&signup);
+/// assert!(company.validate_in(&signup.company, &ctx).is_err());
+/// ```
 pub struct RequiredIf {
-    pub condition: Box<dyn Fn() -> bool + Send + Sync>,
+    pub condition: ContextCondition,
 }
 
-impl Rule for RequiredIf {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if (self.condition)() {
-            // Check if value is empty or null
-            if let Some(s) = value.downcast_ref::<String>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(s) = value.downcast_ref::<&str>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(o) = value.downcast_ref::<Option<String>>() {
-                if o.is_none() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
-                if v.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            }
-        }
-        
-        Ok(())
+impl RequiredIf {
+    fn applies(&self, ctx: &ValidationContext<'_>) -> bool {
+        (self.condition)(ctx)
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required_if", "Value is required when the condition holds")
     }
 }
 
 /// Validates that a value is required if another field has a specific value
 pub struct RequiredWith<T: PartialEq + 'static> {
-    pub other_field: Box<dyn Fn() -> Option<T> + Send + Sync>,
+    pub other_field: ContextValue<T>,
     pub expected_value: T,
 }
 
-impl<T: PartialEq + Send + Sync + 'static> Rule for RequiredWith<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(other_value) = (self.other_field)() {
-            if other_value == self.expected_value {
-                // Check if value is empty or null
-                if let Some(s) = value.downcast_ref::<String>() {
-                    if s.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(s) = value.downcast_ref::<&str>() {
-                    if s.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(o) = value.downcast_ref::<Option<String>>() {
-                    if o.is_none() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
-                    if v.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                }
-            }
-        }
-        
-        Ok(())
+impl<T: PartialEq + Serialize + Send + Sync + 'static> RequiredWith<T> {
+    fn applies(&self, ctx: &ValidationContext<'_>) -> bool {
+        (self.other_field)(ctx).is_some_and(|other_value| other_value == self.expected_value)
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required_with", "Value is required when the other field has the expected value")
+            .with_param("expected_value", &self.expected_value)
     }
 }
 
 /// Validates that a value is required if another field does not have a specific value
 pub struct RequiredWithout<T: PartialEq + 'static> {
-    pub other_field: Box<dyn Fn() -> Option<T> + Send + Sync>,
+    pub other_field: ContextValue<T>,
     pub expected_value: T,
 }
 
-impl<T: PartialEq + Send + Sync + 'static> Rule for RequiredWithout<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(other_value) = (self.other_field)() {
-            if other_value != self.expected_value {
-                // Check if value is empty or null
-                if let Some(s) = value.downcast_ref::<String>() {
-                    if s.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(s) = value.downcast_ref::<&str>() {
-                    if s.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(o) = value.downcast_ref::<Option<String>>() {
-                    if o.is_none() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
-                    if v.is_empty() {
-                        return Err(ValidationError::new("Value is required"));
-                    }
-                }
-            }
-        }
-        
-        Ok(())
+impl<T: PartialEq + Serialize + Send + Sync + 'static> RequiredWithout<T> {
+    fn applies(&self, ctx: &ValidationContext<'_>) -> bool {
+        (self.other_field)(ctx).is_some_and(|other_value| other_value != self.expected_value)
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required_without", "Value is required when the other field does not have the expected value")
+            .with_param("expected_value", &self.expected_value)
     }
 }
 
 /// Validates that a value is required if any of the specified conditions are true
 pub struct RequiredIfAny {
-    pub conditions: Vec<Box<dyn Fn() -> bool + Send + Sync>>,
+    pub conditions: Vec<ContextCondition>,
 }
 
-impl Rule for RequiredIfAny {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if self.conditions.iter().any(|condition| condition()) {
-            // Check if value is empty or null
-            if let Some(s) = value.downcast_ref::<String>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(s) = value.downcast_ref::<&str>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(o) = value.downcast_ref::<Option<String>>() {
-                if o.is_none() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
-                if v.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
-                }
-            }
-        }
-        
-        Ok(())
+impl RequiredIfAny {
+    fn applies(&self, ctx: &ValidationContext<'_>) -> bool {
+        self.conditions.iter().any(|condition| condition(ctx))
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required_if_any", "Value is required when any of the conditions holds")
     }
 }
 
 /// Validates that a value is required if all of the specified conditions are true
 pub struct RequiredIfAll {
-    pub conditions: Vec<Box<dyn Fn() -> bool + Send + Sync>>,
+    pub conditions: Vec<ContextCondition>,
 }
 
-impl Rule for RequiredIfAll {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if self.conditions.iter().all(|condition| condition()) {
-            // Check if value is empty or null
-            if let Some(s) = value.downcast_ref::<String>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
+impl RequiredIfAll {
+    fn applies(&self, ctx: &ValidationContext<'_>) -> bool {
+        self.conditions.iter().all(|condition| condition(ctx))
+    }
+
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("required_if_all", "Value is required when all of the conditions hold")
+    }
+}
+
+/// Implement both forms of a conditional requirement: when the condition
+/// applies the value must be present, as checked by [`Required`]
+///
+/// Conditions are evaluated against the context, so called through
+/// [`Rule::validate`] they only see an empty one.
+macro_rules! impl_required_when {
+    ($($rule:ident $(<$param:ident>)?),*) => {
+        $(
+            impl<$($param: PartialEq + Serialize + Send + Sync + 'static,)? V: Presence + ?Sized> Rule<V> for $rule$(<$param>)? {
+                fn validate(&self, value: &V) -> Result<(), ValidationError> {
+                    self.validate_in(value, &ValidationContext::new())
                 }
-            } else if let Some(s) = value.downcast_ref::<&str>() {
-                if s.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
+
+                fn validate_in(&self, value: &V, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+                    if self.applies(ctx) {
+                        return Required.validate(value);
+                    }
+
+                    Ok(())
+                }
+
+                fn describe(&self) -> RuleInfo {
+                    self.info()
+                }
+            }
+
+            impl$(<$param: PartialEq + Serialize + Send + Sync + 'static>)? Rule for $rule$(<$param>)? {
+                fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+                    self.validate_in(value, &ValidationContext::new())
                 }
-            } else if let Some(o) = value.downcast_ref::<Option<String>>() {
-                if o.is_none() {
-                    return Err(ValidationError::new("Value is required"));
+
+                fn validate_in(&self, value: &dyn Any, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+                    if self.applies(ctx) {
+                        return Required.validate(value);
+                    }
+
+                    Ok(())
                 }
-            } else if let Some(v) = value.downcast_ref::<Vec<String>>() {
-                if v.is_empty() {
-                    return Err(ValidationError::new("Value is required"));
+
+                fn describe(&self) -> RuleInfo {
+                    self.info()
                 }
             }
-        }
-        
-        Ok(())
+        )*
+    };
+}
+
+impl_required_when!(RequiredIf, RequiredWith<T>, RequiredWithout<T>, RequiredIfAny, RequiredIfAll);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Order {
+        delivery: String,
+        address: Option<String>,
+    }
+
+    fn delivery() -> ContextValue<String> {
+        Box::new(|ctx| ctx.parent::<Order>().map(|order| order.delivery.clone()))
+    }
+
+    fn check<R: Rule<Option<String>>>(rule: &R, delivery: &str, address: Option<&str>) -> Result<(), ValidationError> {
+        let order = Order {
+            delivery: delivery.to_string(),
+            address: address.map(str::to_string),
+        };
+        rule.validate_in(&order.address, &ValidationContext::new().with_parent(&order))
+    }
+
+    #[test]
+    fn required_with_applies_when_the_other_field_matches() {
+        let rule = RequiredWith { other_field: delivery(), expected_value: "post".to_string() };
+        assert_eq!(check(&rule, "post", None).unwrap_err().code(), Some("required"));
+        assert!(check(&rule, "post", Some("1 Main Street")).is_ok());
+        assert!(check(&rule, "pickup", None).is_ok());
+    }
+
+    #[test]
+    fn required_without_applies_when_the_other_field_differs() {
+        let rule = RequiredWithout { other_field: delivery(), expected_value: "pickup".to_string() };
+        assert!(check(&rule, "post", None).is_err());
+        assert!(check(&rule, "pickup", None).is_ok());
+        // Without a parent the other field is unknown and the value is not required
+        assert!(Rule::<Option<String>>::validate(&rule, &None).is_ok());
+    }
+
+    #[test]
+    fn required_if_any_and_all_combine_conditions() {
+        let post: ContextCondition = Box::new(|ctx| ctx.parent::<Order>().is_some_and(|order| order.delivery == "post"));
+        let courier: ContextCondition = Box::new(|ctx| ctx.parent::<Order>().is_some_and(|order| order.delivery == "courier"));
+        let any = RequiredIfAny { conditions: vec![post, courier] };
+        assert!(check(&any, "courier", None).is_err());
+        assert!(check(&any, "pickup", None).is_ok());
+
+        let all = RequiredIfAll { conditions: vec![Box::new(|_| true), Box::new(|_| false)] };
+        assert!(check(&all, "post", None).is_ok());
+        let all = RequiredIfAll { conditions: Vec::new() };
+        assert!(check(&all, "post", None).is_err());
+    }
+
+    #[test]
+    fn dynamically_typed_forms_check_presence() {
+        let rule = RequiredIf { condition: Box::new(|ctx| ctx.path().to_string() == "company") };
+        let ctx = ValidationContext::new().with_path("company");
+        assert!(Rule::validate_in(&rule, &String::new() as &dyn Any, &ctx).is_err());
+        assert!(Rule::validate_in(&rule, &"Acme".to_string() as &dyn Any, &ctx).is_ok());
+        assert!(Rule::validate(&rule, &String::new() as &dyn Any).is_ok());
+    }
+
+    #[test]
+    fn descriptions_include_the_expected_value() {
+        let post = RequiredWith { other_field: delivery(), expected_value: "post".to_string() };
+        let courier = RequiredWith { other_field: delivery(), expected_value: "courier".to_string() };
+        let (post, courier) = (Rule::<str>::describe(&post), Rule::<str>::describe(&courier));
+        assert_eq!(post.param("expected_value"), Some(&serde_json::json!("post")));
+        assert_ne!(post, courier);
+
+        let without = RequiredWithout { other_field: Box::new(|_| Some(2)), expected_value: 3 };
+        assert_eq!(Rule::<str>::describe(&without).param("expected_value"), Some(&serde_json::json!(3)));
     }
 }
@@ -1,14 +1,452 @@
-use crate::error::ValidationError;
+use std::any::Any;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::context::ValidationContext;
+use crate::error::{ValidationError, Violation};
+use crate::json_schema::Keywords;
 
 pub mod common;
 pub mod numeric;
 pub mod collection;
 pub mod advanced;
 pub mod conditional;
+pub mod combinator;
+pub mod comparison;
+
+/// Code reported when a rule receives a value of a type it cannot check
+pub const INVALID_TYPE: &str = "invalid_type";
 
 /// Trait that all validation rules must implement
-pub trait Rule: Send + Sync {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError>;
+///
+/// `T` is the type of value the rule checks, so applying a rule to a value it
+/// does not support is a compile error. The default, `dyn Any`, is the
+/// dynamically typed form behind `Box<dyn Rule>`: built-in rules implement it by
+/// downcasting to the types they support and report [`INVALID_TYPE`] otherwise.
+///
+/// # Implementing
+///
+/// Implementations provide [`Rule::validate`]. Rules written before the trait
+/// took a type parameter implement the deprecated [`Rule::validate_any`]
+/// instead, which keeps working.
+///
+/// **Every implementation must override `validate` or `validate_any`.** Each
+/// one's default calls the other so that either can be implemented, which means
+/// an implementation overriding neither, such as `impl Rule<i32> for X {}`,
+/// compiles but overflows the stack the first time it is called.
+pub trait Rule<T: ?Sized = dyn Any>: Send + Sync {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        #[allow(deprecated)]
+        self.validate_any(value)
+    }
+
+    /// The method rules implemented before `validate` replaced it, calling `validate` by default
+    #[deprecated(since = "0.2.0", note = "implement and call `Rule::validate` instead")]
+    fn validate_any(&self, value: &T) -> Result<(), ValidationError> {
+        self.validate(value)
+    }
+
+    /// Validate a value knowing where it is being applied
+    ///
+    /// Validation runs and schemas call this method. Rules that depend on the
+    /// surrounding value, caller data or the current time override it; the
+    /// default ignores the context. Rules wrapping other rules forward the
+    /// context to them.
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        let _ = ctx;
+        self.validate(value)
+    }
+
+    /// The validation groups the rule belongs to, or `None` for the default group
+    ///
+    /// Runs skip rules outside the groups they check; see
+    /// [`RuleExt::in_groups`](combinator::RuleExt::in_groups).
+    fn groups(&self) -> Option<&[String]> {
+        None
+    }
+
+    /// The constraints the rule enforces as JSON Schema keywords
+    ///
+    /// Used by [`json_schema`](crate::json_schema) exports. The default
+    /// describes no constraint, which suits rules that depend on the context
+    /// or run arbitrary code.
+    fn json_schema(&self) -> Keywords {
+        Keywords::new()
+    }
+
+    /// What the rule checks: a name, a human description and the parameters
+    ///
+    /// Used to list the rules registered on a
+    /// [`Validator`](crate::validator::Validator) or bound by a
+    /// [`Schema`](crate::schema::Schema), e.g. for API documentation. Built-in
+    /// rules describe themselves; the default names the rule after its type.
+    fn describe(&self) -> RuleInfo {
+        RuleInfo::new(short_type_name::<Self>(), "Custom rule")
+    }
+}
+
+/// Description of a rule, returned by [`Rule::describe`]
+///
+/// The name is the one used by violation codes and [rule sets](crate::rule_set),
+/// e.g. `length` for [`Length`](common::Length); rules made of other rules,
+/// such as [`Each`](collection::Each), list them in `rules`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleInfo {
+    pub name: String,
+
+    /// What a valid value looks like, e.g. "Length must be between 3 and 20"
+    pub description: String,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, Value>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleInfo>,
+}
+
+impl RuleInfo {
+    /// Describe a rule without parameters
+    pub fn new<N: Into<String>, D: Into<String>>(name: N, description: D) -> Self {
+        RuleInfo {
+            name: name.into(),
+            description: description.into(),
+            params: BTreeMap::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Add a parameter; unset optional parameters (`None`) are left out
+    pub fn with_param<K: Into<String>, V: Serialize + ?Sized>(mut self, name: K, value: &V) -> Self {
+        match serde_json::to_value(value) {
+            Ok(Value::Null) | Err(_) => {}
+            Ok(value) => {
+                self.params.insert(name.into(), value);
+            }
+        }
+        self
+    }
+
+    /// Add a rule this one is made of
+    pub fn with_rule(mut self, rule: RuleInfo) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Get a parameter by name
+    pub fn param(&self, name: &str) -> Option<&Value> {
+        self.params.get(name)
+    }
+}
+
+/// Renders the description followed by the parameters, e.g. `Length must be at least 3 (min: 3)`
+impl fmt::Display for RuleInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.description)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+            write!(f, " ({})", params.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: ?Sized, R: Rule<T> + ?Sized> Rule<T> for Box<R> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        (**self).validate(value)
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        (**self).validate_in(value, ctx)
+    }
+
+    fn groups(&self) -> Option<&[String]> {
+        (**self).groups()
+    }
+
+    fn json_schema(&self) -> Keywords {
+        (**self).json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        (**self).describe()
+    }
+}
+
+impl<T: ?Sized, R: Rule<T> + ?Sized> Rule<T> for Arc<R> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        (**self).validate(value)
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        (**self).validate_in(value, ctx)
+    }
+
+    fn groups(&self) -> Option<&[String]> {
+        (**self).groups()
+    }
+
+    fn json_schema(&self) -> Keywords {
+        (**self).json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        (**self).describe()
+    }
+}
+
+/// Adapts a typed rule to the dynamically typed form, e.g. to register it on a
+/// [`Validator`](crate::validator::Validator)
+///
+/// Values that are not a `T` are reported with the [`INVALID_TYPE`] code.
+pub struct Erased<R, T> {
+    pub rule: R,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<R, T> Erased<R, T> {
+    /// Wrap a rule checking values of type `T`
+    pub fn new(rule: R) -> Self {
+        Erased {
+            rule,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R: Rule<T>, T: Any> Rule for Erased<R, T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate_in(value, &ValidationContext::new())
+    }
+
+    fn validate_in(&self, value: &dyn Any, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        match value.downcast_ref::<T>() {
+            Some(value) => self.rule.validate_in(value, ctx),
+            None => Err(invalid_type("Value is not of the expected type", std::any::type_name::<T>())),
+        }
+    }
+
+    fn groups(&self) -> Option<&[String]> {
+        self.rule.groups()
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.rule.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.rule.describe()
+    }
+}
+
+/// Adapts a dynamically typed rule to any concrete value type, e.g. to use a
+/// `Box<dyn Rule>` in a [`Schema`](crate::schema::Schema)
+pub struct Dynamic<R: ?Sized>(pub R);
+
+impl<T: Any, R: Rule + ?Sized> Rule<T> for Dynamic<R> {
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.0.validate(value)
+    }
+
+    fn validate_in(&self, value: &T, ctx: &ValidationContext<'_>) -> Result<(), ValidationError> {
+        self.0.validate_in(value, ctx)
+    }
+
+    fn groups(&self) -> Option<&[String]> {
+        self.0.groups()
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.0.json_schema()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.0.describe()
+    }
+}
+
+/// How the length of a value is counted, used to phrase length messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthKind {
+    /// Characters of a string
+    Text,
+
+    /// Items of a sequence or set
+    Items,
+
+    /// Entries of a map
+    Entries,
+}
+
+impl LengthKind {
+    /// Every kind, for dynamically typed rules whose value may be any of them
+    pub(crate) const ALL: [LengthKind; 3] = [LengthKind::Text, LengthKind::Items, LengthKind::Entries];
+}
+
+/// Values with a length, checked by [`Length`](common::Length) and the size rules
+pub trait HasLength {
+    /// What the length counts
+    const KIND: LengthKind;
+
+    /// The length of the value
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    const KIND: LengthKind = LengthKind::Text;
+
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    const KIND: LengthKind = LengthKind::Text;
+
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl<T> HasLength for [T] {
+    const KIND: LengthKind = LengthKind::Items;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    const KIND: LengthKind = LengthKind::Items;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for VecDeque<T> {
+    const KIND: LengthKind = LengthKind::Items;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> HasLength for HashSet<T, S> {
+    const KIND: LengthKind = LengthKind::Items;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for BTreeSet<T> {
+    const KIND: LengthKind = LengthKind::Items;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> HasLength for HashMap<K, V, S> {
+    const KIND: LengthKind = LengthKind::Entries;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> HasLength for BTreeMap<K, V> {
+    const KIND: LengthKind = LengthKind::Entries;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl HasLength for serde_json::Map<String, serde_json::Value> {
+    const KIND: LengthKind = LengthKind::Entries;
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: HasLength + ?Sized> HasLength for &T {
+    const KIND: LengthKind = T::KIND;
+
+    fn length(&self) -> usize {
+        (**self).length()
+    }
+}
+
+/// Values that can be absent, checked by [`Required`](common::Required)
+///
+/// Strings and collections are present when they are not empty, options when
+/// they are `Some`. JSON values are present unless they are null or an empty
+/// string, array or object.
+pub trait Presence {
+    /// How the length of a present value is counted, if presence depends on it
+    const LENGTH_KIND: Option<LengthKind> = None;
+
+    fn is_present(&self) -> bool;
+}
+
+impl<T: HasLength + ?Sized> Presence for T {
+    const LENGTH_KIND: Option<LengthKind> = Some(T::KIND);
+
+    fn is_present(&self) -> bool {
+        self.length() > 0
+    }
+}
+
+impl<T> Presence for Option<T> {
+    fn is_present(&self) -> bool {
+        self.is_some()
+    }
+}
+
+impl Presence for serde_json::Value {
+    fn is_present(&self) -> bool {
+        match self {
+            serde_json::Value::Null => false,
+            serde_json::Value::String(s) => s.is_present(),
+            serde_json::Value::Array(items) => items.is_present(),
+            serde_json::Value::Object(members) => members.is_present(),
+            _ => true,
+        }
+    }
+}
+
+/// The name of a type without its module path and generic arguments, e.g. `Even`
+pub(crate) fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Build the error reported when a rule receives a value of an unsupported type
+pub(crate) fn invalid_type(message: &str, expected: &str) -> ValidationError {
+    Violation::new(INVALID_TYPE, message)
+        .with_param("expected", expected)
+        .into()
+}
+
+/// Get the string held by a dynamically typed value, if it holds one
+pub(crate) fn downcast_str(value: &dyn Any) -> Option<&str> {
+    if let Some(s) = value.downcast_ref::<String>() {
+        Some(s)
+    } else if let Some(s) = value.downcast_ref::<&str>() {
+        Some(s)
+    } else if let Some(s) = value.downcast_ref::<&String>() {
+        Some(s)
+    } else {
+        value.downcast_ref::<Box<str>>().map(|s| &**s)
+    }
 }
 
 /// Prelude module for commonly used rules
@@ -18,4 +456,99 @@ pub mod prelude {
     pub use super::collection::*;
     pub use super::advanced::*;
     pub use super::conditional::*;
+    pub use super::combinator::*;
+    pub use super::comparison::*;
+    pub use super::{Dynamic, Erased, HasLength, Presence, RuleInfo};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rule written against the trait as it was before `validate`
+    struct LegacyNonZero;
+
+    impl Rule for LegacyNonZero {
+        fn validate_any(&self, value: &dyn Any) -> Result<(), ValidationError> {
+            match value.downcast_ref::<i32>() {
+                Some(0) => Err(Violation::new("non_zero", "Value must not be zero").into()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn legacy_rules_are_called_through_validate() {
+        let rule: Box<dyn Rule> = Box::new(LegacyNonZero);
+        assert!(rule.validate(&1).is_ok());
+        assert_eq!(rule.validate(&0).unwrap_err().code(), Some("non_zero"));
+    }
+
+    /// A typed rule overriding only the deprecated method
+    struct LegacyEven;
+
+    impl Rule<i32> for LegacyEven {
+        fn validate_any(&self, value: &i32) -> Result<(), ValidationError> {
+            match value % 2 {
+                0 => Ok(()),
+                _ => Err(Violation::new("even", "Value must be even").into()),
+            }
+        }
+    }
+
+    #[test]
+    fn typed_legacy_rules_are_called_through_validate_and_validate_in() {
+        assert!(LegacyEven.validate(&2).is_ok());
+        assert_eq!(LegacyEven.validate(&3).unwrap_err().code(), Some("even"));
+        let rule: Box<dyn Rule<i32>> = Box::new(LegacyEven);
+        assert!(rule.validate_in(&3, &ValidationContext::new()).is_err());
+    }
+
+    #[test]
+    fn legacy_rules_run_in_a_validator() {
+        let mut validator = crate::validator::Validator::new();
+        validator.add_rule("non_zero", LegacyNonZero);
+        let mut run = validator.start();
+        run.field("count", &0, &["non_zero"]);
+        let report = run.finish();
+        assert_eq!(report.entries()[0].path.to_string(), "count");
+    }
+
+    #[test]
+    fn descriptions_render_their_parameters() {
+        let info = RuleInfo::new("length", "Length must be at least 3")
+            .with_param("min", &3)
+            .with_param("max", &None::<usize>);
+        assert_eq!(info.param("min"), Some(&Value::from(3)));
+        assert_eq!(info.param("max"), None);
+        assert_eq!(info.to_string(), "Length must be at least 3 (min: 3)");
+        assert_eq!(
+            serde_json::to_value(&info).unwrap(),
+            serde_json::json!({"name": "length", "description": "Length must be at least 3", "params": {"min": 3}})
+        );
+    }
+
+    #[test]
+    fn custom_rules_are_named_after_their_type() {
+        let info = Rule::<i32>::describe(&LegacyEven);
+        assert_eq!(info.name, "LegacyEven");
+        assert_eq!(info.to_string(), "Custom rule");
+    }
+
+    #[test]
+    fn validators_describe_their_rules_by_name() {
+        use combinator::RuleExt;
+        use common::{Email, Length, Required};
+
+        let mut validator = crate::validator::Validator::new();
+        validator.add_rule("username", Required.and(Length { min: 3, max: Some(20) }));
+        validator.add_rule("email", Email { check_dns: false });
+
+        let rules = validator.describe();
+        assert_eq!(rules.iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["email", "username"]);
+        assert_eq!(rules[0].1.param("check_dns"), Some(&Value::from(false)));
+        let parts: Vec<&str> = rules[1].1.rules.iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(rules[1].1.name, "all_of");
+        assert_eq!(parts, ["required", "length"]);
+    }
 }
@@ -1,7 +1,56 @@
-use std::fmt::Debug;
+use std::any::Any;
+use std::fmt::{Debug, Display};
+use std::ops::Rem;
 
-use crate::error::ValidationError;
-use crate::rules::Rule;
+use serde::Serialize;
+
+use crate::error::{ValidationError, Violation};
+use crate::json_schema::Keywords;
+use crate::rules::{invalid_type, Rule, RuleInfo};
+
+/// Primitive numbers checked by the sign rules
+pub trait Number: PartialOrd + Copy + Send + Sync + 'static {
+    /// The zero of the type
+    const ZERO: Self;
+}
+
+/// Numbers that can be negative
+pub trait Signed: Number {}
+
+macro_rules! impl_number {
+    ($zero:expr => $($ty:ty),*) => {
+        $(impl Number for $ty {
+            const ZERO: Self = $zero;
+        })*
+    };
+}
+
+impl_number!(0 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_number!(0.0 => f32, f64);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+impl Signed for f32 {}
+impl Signed for f64 {}
+
+/// Downcast a value to the expected type of a generic rule
+fn expect<T: Any>(value: &dyn Any) -> Result<&T, ValidationError> {
+    value
+        .downcast_ref::<T>()
+        .ok_or_else(|| invalid_type("Value is not of the expected numeric type", std::any::type_name::<T>()))
+}
+
+/// Describe a numeric bound as a JSON Schema keyword; other bounds, such as dates, have none
+fn bound<T: Serialize>(keyword: &str, value: &T) -> Keywords {
+    match serde_json::to_value(value) {
+        Ok(value @ serde_json::Value::Number(_)) => Keywords::new().with(keyword, value),
+        _ => Keywords::new(),
+    }
+}
 
 /// Validates that a numeric value is within a specified range
 pub struct Range<T> {
@@ -9,82 +58,132 @@ pub struct Range<T> {
     pub max: T,
 }
 
-impl<T: PartialOrd + Debug + Clone + Send + Sync + 'static> Rule for Range<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(val) = value.downcast_ref::<T>() {
-            if *val < self.min {
-                return Err(ValidationError::new(format!(
-                    "Value must be greater than or equal to {:?}", self.min
-                )));
-            }
-            if *val > self.max {
-                return Err(ValidationError::new(format!(
-                    "Value must be less than or equal to {:?}", self.max
-                )));
-            }
-            Ok(())
-        } else {
-            Err(ValidationError::new(format!(
-                "Value is not of the expected numeric type"
-            )))
+impl<T: Debug + Serialize> Range<T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("range", format!("Value must be between {:?} and {:?}", self.min, self.max))
+            .with_param("min", &self.min)
+            .with_param("max", &self.max)
+    }
+
+    fn keywords(&self) -> Keywords {
+        let mut keywords = bound("minimum", &self.min);
+        keywords.merge(bound("maximum", &self.max));
+        keywords
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule<T> for Range<T> {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if *val < self.min {
+            return Err(Violation::new("range.min", format!(
+                "Value must be greater than or equal to {:?}", self.min
+            ))
+            .with_param("min", &self.min)
+            .with_param("max", &self.max)
+            .with_value(val)
+            .into());
+        }
+        if *val > self.max {
+            return Err(Violation::new("range.max", format!(
+                "Value must be less than or equal to {:?}", self.max
+            ))
+            .with_param("min", &self.min)
+            .with_param("max", &self.max)
+            .with_value(val)
+            .into());
         }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule for Range<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate(expect::<T>(value)?)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
 /// Validates that a numeric value is positive
 pub struct Positive;
 
+impl Positive {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("positive", "Value must be positive")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("exclusiveMinimum", 0)
+    }
+}
+
+impl<T: Number> Rule<T> for Positive {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if *val <= T::ZERO {
+            return Err(Violation::new("positive", "Value must be positive").into());
+        }
+
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Positive {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
         if let Some(val) = value.downcast_ref::<i8>() {
-            if *val <= 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i16>() {
-            if *val <= 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i32>() {
-            if *val <= 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i64>() {
-            if *val <= 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<f32>() {
-            if *val <= 0.0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<f64>() {
-            if *val <= 0.0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<u8>() {
-            if *val == 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<u16>() {
-            if *val == 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<u32>() {
-            if *val == 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<u64>() {
-            if *val == 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<usize>() {
-            if *val == 0 {
-                return Err(ValidationError::new("Value must be positive"));
-            }
+            self.validate(val)
         } else {
-            return Err(ValidationError::new("Value is not a numeric type"));
+            Err(invalid_type("Value is not a numeric type", "number"))
         }
-        
-        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
@@ -93,18 +192,50 @@ pub struct Min<T> {
     pub value: T,
 }
 
-impl<T: PartialOrd + Debug + Clone + Send + Sync + 'static> Rule for Min<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(val) = value.downcast_ref::<T>() {
-            if *val < self.value {
-                return Err(ValidationError::new(format!(
-                    "Value must be greater than or equal to {:?}", self.value
-                )));
-            }
-            Ok(())
-        } else {
-            Err(ValidationError::new("Value is not of the expected numeric type"))
+impl<T: Debug + Serialize> Min<T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("min", format!("Value must be greater than or equal to {:?}", self.value))
+            .with_param("min", &self.value)
+    }
+
+    fn keywords(&self) -> Keywords {
+        bound("minimum", &self.value)
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule<T> for Min<T> {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if *val < self.value {
+            return Err(Violation::new("min", format!(
+                "Value must be greater than or equal to {:?}", self.value
+            ))
+            .with_param("min", &self.value)
+            .with_value(val)
+            .into());
         }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule for Min<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate(expect::<T>(value)?)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
@@ -113,57 +244,109 @@ pub struct Max<T> {
     pub value: T,
 }
 
-impl<T: PartialOrd + Debug + Clone + Send + Sync + 'static> Rule for Max<T> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(val) = value.downcast_ref::<T>() {
-            if *val > self.value {
-                return Err(ValidationError::new(format!(
-                    "Value must be less than or equal to {:?}", self.value
-                )));
-            }
-            Ok(())
-        } else {
-            Err(ValidationError::new(format!(
-                "Value is not of the expected numeric type"
-            )))
+impl<T: Debug + Serialize> Max<T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("max", format!("Value must be less than or equal to {:?}", self.value))
+            .with_param("max", &self.value)
+    }
+
+    fn keywords(&self) -> Keywords {
+        bound("maximum", &self.value)
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule<T> for Max<T> {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if *val > self.value {
+            return Err(Violation::new("max", format!(
+                "Value must be less than or equal to {:?}", self.value
+            ))
+            .with_param("max", &self.value)
+            .with_value(val)
+            .into());
         }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: PartialOrd + Debug + Clone + Serialize + Send + Sync + 'static> Rule for Max<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate(expect::<T>(value)?)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
 /// Validates that a numeric value is negative
 pub struct Negative;
 
+impl Negative {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("negative", "Value must be negative")
+    }
+
+    fn keywords(&self) -> Keywords {
+        Keywords::new().with("exclusiveMaximum", 0)
+    }
+}
+
+impl<T: Signed> Rule<T> for Negative {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if *val >= T::ZERO {
+            return Err(Violation::new("negative", "Value must be negative").into());
+        }
+
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
 impl Rule for Negative {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
         if let Some(val) = value.downcast_ref::<i8>() {
-            if *val >= 0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i16>() {
-            if *val >= 0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i32>() {
-            if *val >= 0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<i64>() {
-            if *val >= 0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<f32>() {
-            if *val >= 0.0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else if let Some(val) = value.downcast_ref::<f64>() {
-            if *val >= 0.0 {
-                return Err(ValidationError::new("Value must be negative"));
-            }
+            self.validate(val)
         } else {
-            return Err(ValidationError::new("Value is not a signed numeric type"));
+            Err(invalid_type("Value is not a signed numeric type", "signed number"))
         }
-        
-        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
 
@@ -172,20 +355,52 @@ pub struct DivisibleBy<T> {
     pub divisor: T,
 }
 
-impl Rule for DivisibleBy<i32> {
-    fn validate_any(&self, value: &dyn std::any::Any) -> Result<(), ValidationError> {
-        if let Some(val) = value.downcast_ref::<i32>() {
-            if self.divisor == 0 {
-                return Err(ValidationError::new("Divisor cannot be zero"));
-            }
-            if *val % self.divisor != 0 {
-                return Err(ValidationError::new(format!(
-                    "Value must be divisible by {}", self.divisor
-                )));
-            }
-            Ok(())
-        } else {
-            Err(ValidationError::new("Value is not of the expected numeric type"))
+impl<T: Display + Serialize> DivisibleBy<T> {
+    fn info(&self) -> RuleInfo {
+        RuleInfo::new("divisible_by", format!("Value must be divisible by {}", self.divisor))
+            .with_param("divisor", &self.divisor)
+    }
+
+    fn keywords(&self) -> Keywords {
+        bound("multipleOf", &self.divisor)
+    }
+}
+
+impl<T: Number + Rem<Output = T> + Display + Serialize> Rule<T> for DivisibleBy<T> {
+    fn validate(&self, val: &T) -> Result<(), ValidationError> {
+        if self.divisor == T::ZERO {
+            return Err(Violation::new("divisible_by.zero", "Divisor cannot be zero").into());
         }
+        if *val % self.divisor != T::ZERO {
+            return Err(Violation::new("divisible_by", format!(
+                "Value must be divisible by {}", self.divisor
+            ))
+            .with_param("divisor", &self.divisor)
+            .with_value(val)
+            .into());
+        }
+        Ok(())
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
+    }
+}
+
+impl<T: Number + Rem<Output = T> + Display + Serialize> Rule for DivisibleBy<T> {
+    fn validate(&self, value: &dyn Any) -> Result<(), ValidationError> {
+        self.validate(expect::<T>(value)?)
+    }
+
+    fn json_schema(&self) -> Keywords {
+        self.keywords()
+    }
+
+    fn describe(&self) -> RuleInfo {
+        self.info()
     }
 }
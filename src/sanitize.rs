@@ -0,0 +1,241 @@
+//! Normalization of input before it is validated
+//!
+//! Modifiers rewrite string values in place, e.g. trimming whitespace or
+//! lowercasing an email address. They run in the order they are declared, so
+//! `Nfkc` followed by `DigitsOnly` turns full-width digits into ASCII ones
+//! before everything else is stripped:
+//!
+//! ```
+//! # use rustvalidity::sanitize::{sanitize, DigitsOnly, Modifier, Nfkc};
+//! let modifiers: Vec<Box<dyn Modifier>> = vec![Box::new(Nfkc), Box::new(DigitsOnly)];
+//! let mut phone = "＋１ (415) 555-2671".to_string();
+//! sanitize(&mut phone, &modifiers);
+//! assert_eq!(phone, "14155552671");
+//! ```
+//!
+//! With the `derive` feature, modifiers are declared next to the rules of a
+//! field with `#[validate(sanitize(trim, lowercase), email)]`.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::ValidationError;
+use crate::validator::Validate;
+
+/// Rewrites a string in place
+pub trait Modifier: Send + Sync {
+    fn modify(&self, value: &mut String);
+}
+
+impl<M: Modifier + ?Sized> Modifier for Box<M> {
+    fn modify(&self, value: &mut String) {
+        (**self).modify(value)
+    }
+}
+
+/// Removes leading and trailing whitespace
+pub struct Trim;
+
+impl Modifier for Trim {
+    fn modify(&self, value: &mut String) {
+        let end = value.trim_end().len();
+        value.truncate(end);
+        let start = value.len() - value.trim_start().len();
+        value.drain(..start);
+    }
+}
+
+/// Converts to lowercase
+pub struct Lowercase;
+
+impl Modifier for Lowercase {
+    fn modify(&self, value: &mut String) {
+        *value = value.to_lowercase();
+    }
+}
+
+/// Converts to uppercase
+pub struct Uppercase;
+
+impl Modifier for Uppercase {
+    fn modify(&self, value: &mut String) {
+        *value = value.to_uppercase();
+    }
+}
+
+/// Replaces every run of whitespace with a single space and trims the ends
+pub struct CollapseWhitespace;
+
+impl Modifier for CollapseWhitespace {
+    fn modify(&self, value: &mut String) {
+        *value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    }
+}
+
+/// Applies Unicode canonical composition (NFC), e.g. `e` followed by a combining accent becomes `é`
+pub struct Nfc;
+
+impl Modifier for Nfc {
+    fn modify(&self, value: &mut String) {
+        *value = value.nfc().collect();
+    }
+}
+
+/// Applies Unicode compatibility composition (NFKC), e.g. full-width `１２３` becomes `123`
+pub struct Nfkc;
+
+impl Modifier for Nfkc {
+    fn modify(&self, value: &mut String) {
+        *value = value.nfkc().collect();
+    }
+}
+
+/// Removes control characters, including tabs and line breaks
+pub struct StripControl;
+
+impl Modifier for StripControl {
+    fn modify(&self, value: &mut String) {
+        value.retain(|c| !c.is_control());
+    }
+}
+
+/// Keeps only ASCII digits, e.g. for phone numbers
+///
+/// Other scripts' digits are removed; run [`Nfkc`] first to keep full-width ones.
+pub struct DigitsOnly;
+
+impl Modifier for DigitsOnly {
+    fn modify(&self, value: &mut String) {
+        value.retain(|c| c.is_ascii_digit());
+    }
+}
+
+/// Values whose strings modifiers can rewrite
+pub trait Sanitizable {
+    /// Apply a modifier to every string of the value
+    fn sanitize_with(&mut self, modifier: &dyn Modifier);
+}
+
+impl Sanitizable for String {
+    fn sanitize_with(&mut self, modifier: &dyn Modifier) {
+        modifier.modify(self);
+    }
+}
+
+impl<T: Sanitizable> Sanitizable for Option<T> {
+    fn sanitize_with(&mut self, modifier: &dyn Modifier) {
+        if let Some(value) = self {
+            value.sanitize_with(modifier);
+        }
+    }
+}
+
+impl<T: Sanitizable> Sanitizable for Vec<T> {
+    fn sanitize_with(&mut self, modifier: &dyn Modifier) {
+        for value in self {
+            value.sanitize_with(modifier);
+        }
+    }
+}
+
+/// Apply modifiers to a value in order
+pub fn sanitize<T, M>(value: &mut T, modifiers: &[M])
+where
+    T: Sanitizable + ?Sized,
+    M: Modifier,
+{
+    for modifier in modifiers {
+        value.sanitize_with(modifier);
+    }
+}
+
+/// Types that normalize their own fields before validation
+///
+/// Implemented by the derive macro for structs with `sanitize(...)` fields.
+pub trait Sanitize {
+    /// Apply the modifiers of every field
+    fn sanitize(&mut self);
+
+    /// Sanitize the value, then validate it
+    fn sanitize_and_validate(&mut self) -> Result<(), ValidationError>
+    where
+        Self: Validate,
+    {
+        self.sanitize();
+        self.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply<M: Modifier>(modifier: M, value: &str) -> String {
+        let mut value = value.to_string();
+        modifier.modify(&mut value);
+        value
+    }
+
+    #[test]
+    fn modifiers_rewrite_strings() {
+        assert_eq!(apply(Trim, " \t ada \n"), "ada");
+        assert_eq!(apply(Lowercase, "Ada@Example.COM"), "ada@example.com");
+        assert_eq!(apply(Uppercase, "de"), "DE");
+        assert_eq!(apply(CollapseWhitespace, "  Ada \t  Lovelace "), "Ada Lovelace");
+        assert_eq!(apply(StripControl, "a\tb\u{7}c\n"), "abc");
+        assert_eq!(apply(DigitsOnly, "+1 (415) 555-2671"), "14155552671");
+    }
+
+    #[test]
+    fn normalization_forms_differ() {
+        assert_eq!(apply(Nfc, "e\u{301}"), "\u{e9}");
+        assert_eq!(apply(Nfc, "\u{ff11}\u{ff12}"), "\u{ff11}\u{ff12}");
+        assert_eq!(apply(Nfkc, "\u{ff11}\u{ff12}"), "12");
+        // Full-width digits are not ASCII digits until they are normalized
+        assert_eq!(apply(DigitsOnly, "\u{ff11}\u{ff12}"), "");
+    }
+
+    #[test]
+    fn containers_sanitize_every_string() {
+        let mut tags = vec![" Rust ".to_string(), "WASM".to_string()];
+        sanitize(&mut tags, &[Box::new(Trim) as Box<dyn Modifier>, Box::new(Lowercase)]);
+        assert_eq!(tags, ["rust", "wasm"]);
+
+        let mut nickname = Some("  Ada ".to_string());
+        sanitize(&mut nickname, &[Trim]);
+        assert_eq!(nickname.as_deref(), Some("Ada"));
+
+        let mut missing: Option<String> = None;
+        sanitize(&mut missing, &[Trim]);
+        assert!(missing.is_none());
+    }
+
+    struct Signup {
+        email: String,
+    }
+
+    impl Sanitize for Signup {
+        fn sanitize(&mut self) {
+            sanitize(&mut self.email, &[Box::new(Trim) as Box<dyn Modifier>, Box::new(Lowercase)]);
+        }
+    }
+
+    impl Validate for Signup {
+        fn validate(&self) -> Result<(), ValidationError> {
+            if self.email == self.email.trim() {
+                Ok(())
+            } else {
+                Err(ValidationError::new("Email has surrounding whitespace"))
+            }
+        }
+    }
+
+    #[test]
+    fn values_are_sanitized_before_they_are_validated() {
+        let mut signup = Signup {
+            email: " Ada@Example.com ".to_string(),
+        };
+        assert!(signup.validate().is_err());
+        assert!(signup.sanitize_and_validate().is_ok());
+        assert_eq!(signup.email, "ada@example.com");
+    }
+}
@@ -0,0 +1,359 @@
+use std::any::Any;
+
+use crate::context::{Clock, ContextData};
+use crate::error::ValidationError;
+use crate::path::FieldPath;
+use crate::report::ValidationReport;
+use crate::rules::{Rule, RuleInfo};
+use crate::sanitize::{Modifier, Sanitizable};
+use crate::validator::{Validate, ValidationMode, ValidationRun, Validator};
+
+type FieldCheck<T> = Box<dyn for<'v> Fn(&'v T, &mut ValidationRun<'v>) + Send + Sync>;
+type FieldModifier<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+/// A bound field with the descriptions of its rules
+struct Field<T> {
+    path: FieldPath,
+    rules: Vec<RuleInfo>,
+    check: FieldCheck<T>,
+}
+
+/// Rules bound to the fields of a type, created with [`Validator::for_type`]
+///
+/// Fields are read through accessor closures and checked in declaration order;
+/// failures are reported at the field's path. A schema is built once and can
+/// be reused for any number of values:
+///
+/// ```
+/// # use rustvalidity::rules::common::{Email, Length, Required};
+/// # use rustvalidity::validator::Validator;
+/// struct User {
+///     username: String,
+///     email: String,
+/// }
+///
+/// let schema = Validator::for_type::<User>()
+///     .field("username", |u| &u.username, vec![Box::new(Required), Box::new(Length { min: 3, max: Some(20) })])
+///     .field("email", |u| &u.email, vec![Box::new(Required), Box::new(Email { check_dns: false })]);
+///
+/// let user = User { username: "ada".to_string(), email: "ada@example.com".to_string() };
+/// assert!(schema.validate(&user).is_ok());
+///
+/// let user = User { username: "al".to_string(), email: "al".to_string() };
+/// let err = schema.validate_all(&user).unwrap_err();
+/// assert_eq!(err.field_violations("username")[0].code, "length.min");
+/// assert_eq!(err.field_violations("email")[0].code, "email.format");
+/// ```
+///
+/// Rules see the validated value as the parent in their
+/// [`ValidationContext`](crate::context::ValidationContext), so they can read
+/// sibling fields.
+///
+/// Fields can also be normalized before they are checked, with modifiers
+/// registered by [`Schema::modify`] and applied by [`Schema::sanitize`].
+pub struct Schema<T> {
+    validator: Validator,
+    fields: Vec<Field<T>>,
+    modifiers: Vec<FieldModifier<T>>,
+}
+
+impl<T: 'static> Schema<T> {
+    /// Create a schema without any field
+    pub fn new() -> Self {
+        Schema {
+            validator: Validator::new(),
+            fields: Vec::new(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// Set how far validation goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.validator = self.validator.with_mode(mode);
+        self
+    }
+
+    /// Stop checking a field after its first error while still checking the other fields
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.validator = self.validator.bail(bail);
+        self
+    }
+
+    /// Add data available to every rule through its context
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.validator = self.validator.with_data(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.validator = self.validator.with_clock(clock);
+        self
+    }
+
+    /// Declare a validation group with the groups it inherits instead of the default group
+    pub fn with_group<I, S>(mut self, group: &str, parents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.validator = self.validator.with_group(group, parents);
+        self
+    }
+
+    /// Bind rules to a field read through an accessor
+    ///
+    /// Rules are checked against the field's type, so a rule that does not
+    /// support it is a compile error.
+    pub fn field<P, F, A>(mut self, path: P, accessor: A, rules: Vec<Box<dyn Rule<F>>>) -> Self
+    where
+        P: Into<FieldPath>,
+        F: ?Sized + 'static,
+        A: Fn(&T) -> &F + Send + Sync + 'static,
+    {
+        let path = path.into();
+        let field_path = path.clone();
+        self.fields.push(Field {
+            path,
+            rules: rules.iter().map(|rule| rule.describe()).collect(),
+            check: Box::new(move |value, run| {
+                run.rules(field_path.clone(), accessor(value), &rules);
+            }),
+        });
+        self
+    }
+
+    /// Normalize a field read through a mutable accessor, applying the modifiers in order
+    ///
+    /// ```
+    /// # use rustvalidity::rules::common::Email;
+    /// # use rustvalidity::sanitize::{Lowercase, Trim};
+    /// # use rustvalidity::validator::Validator;
+    /// struct Signup {
+    ///     email: String,
+    /// }
+    ///
+    /// let schema = Validator::for_type::<Signup>()
+    ///     .modify(|s| &mut s.email, vec![Box::new(Trim), Box::new(Lowercase)])
+    ///     .field("email", |s| &s.email, vec![Box::new(Email { check_dns: false })]);
+    ///
+    /// let mut signup = Signup { email: "  Ada@Example.com ".to_string() };
+    /// assert!(schema.validate_sanitized(&mut signup).is_ok());
+    /// assert_eq!(signup.email, "ada@example.com");
+    /// ```
+    pub fn modify<F, A>(mut self, accessor: A, modifiers: Vec<Box<dyn Modifier>>) -> Self
+    where
+        F: Sanitizable + ?Sized + 'static,
+        A: Fn(&mut T) -> &mut F + Send + Sync + 'static,
+    {
+        self.modifiers.push(Box::new(move |value| {
+            let field = accessor(value);
+            for modifier in &modifiers {
+                field.sanitize_with(modifier.as_ref());
+            }
+        }));
+        self
+    }
+
+    /// Validate a nested value with its own [`Validate`] implementation, reporting below the field
+    ///
+    /// The nested value is checked within the same run, so it shares the run's
+    /// mode and root.
+    pub fn nested<P, F, A>(mut self, path: P, accessor: A) -> Self
+    where
+        P: Into<FieldPath>,
+        F: Validate + ?Sized + 'static,
+        A: Fn(&T) -> &F + Send + Sync + 'static,
+    {
+        let path = path.into();
+        let field_path = path.clone();
+        self.fields.push(Field {
+            path,
+            rules: Vec::new(),
+            check: Box::new(move |value, run| {
+                let nested = accessor(value);
+                run.nest(field_path.clone(), |run| nested.validate_with(run));
+            }),
+        });
+        self
+    }
+
+    /// Check the whole value with a struct-level rule, such as a [`Compare`](crate::rules::comparison::Compare) of two fields
+    ///
+    /// Errors are reported at the paths the rule attaches them to.
+    pub fn rule<R: Rule<T> + 'static>(mut self, rule: R) -> Self {
+        let rules: Vec<Box<dyn Rule<T>>> = vec![Box::new(rule)];
+        self.fields.push(Field {
+            path: FieldPath::root(),
+            rules: rules.iter().map(|rule| rule.describe()).collect(),
+            check: Box::new(move |value, run| {
+                run.rules(FieldPath::root(), value, &rules);
+            }),
+        });
+        self
+    }
+
+    /// The paths of the bound fields in declaration order
+    pub fn fields(&self) -> Vec<&FieldPath> {
+        self.fields.iter().map(|field| &field.path).collect()
+    }
+
+    /// Describe the rules bound to each field, in declaration order
+    ///
+    /// Struct-level rules are listed at the root path; nested values checked
+    /// with [`Schema::nested`] are listed without rules.
+    pub fn describe(&self) -> Vec<(&FieldPath, &[RuleInfo])> {
+        self.fields.iter().map(|field| (&field.path, field.rules.as_slice())).collect()
+    }
+
+    /// Check every field of a value as part of a validation run
+    pub fn apply<'v>(&self, value: &'v T, run: &mut ValidationRun<'v>) {
+        run.scope(value, |run| {
+            for field in &self.fields {
+                if run.is_stopped() {
+                    break;
+                }
+                (field.check)(value, run);
+            }
+        });
+    }
+
+    /// Apply the registered modifiers to a value
+    pub fn sanitize(&self, value: &mut T) {
+        for modify in &self.modifiers {
+            modify(value);
+        }
+    }
+
+    /// Sanitize a value, then validate it in the schema's mode
+    pub fn validate_sanitized(&self, value: &mut T) -> Result<(), ValidationError> {
+        self.sanitize(value);
+        self.validate(value)
+    }
+
+    /// Validate a value in the schema's mode
+    pub fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.report(value).into_result()
+    }
+
+    /// Validate a value and collect every error
+    pub fn validate_all(&self, value: &T) -> Result<(), ValidationError> {
+        let mut run = self.validator.start_with(ValidationMode::CollectAll);
+        self.apply(value, &mut run);
+        run.into_result()
+    }
+
+    /// Validate a value in the schema's mode with data supplied for this call, e.g. the current user
+    pub fn validate_with_data(&self, value: &T, data: &ContextData) -> Result<(), ValidationError> {
+        let mut run = self.validator.start().with_data(data);
+        self.apply(value, &mut run);
+        run.into_result()
+    }
+
+    /// Validate a value against the rules of a group, e.g. `"create"`, in the schema's mode
+    pub fn validate_group(&self, value: &T, group: &str) -> Result<(), ValidationError> {
+        self.report_group(value, group).into_result()
+    }
+
+    /// Validate a value against the rules of a group and return every finding, including warnings
+    pub fn report_group(&self, value: &T, group: &str) -> ValidationReport {
+        let mut run = self.validator.start().with_group(group);
+        self.apply(value, &mut run);
+        run.finish()
+    }
+
+    /// Validate a value in the schema's mode and return every finding, including warnings
+    pub fn report(&self, value: &T) -> ValidationReport {
+        let mut run = self.validator.start();
+        self.apply(value, &mut run);
+        run.finish()
+    }
+}
+
+impl<T: 'static> Default for Schema<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::common::{Length, Required};
+    use crate::rules::numeric::Min;
+
+    struct Line {
+        sku: String,
+        quantity: i32,
+    }
+
+    struct Order {
+        reference: String,
+        line: Line,
+    }
+
+    impl Validate for Line {
+        fn validate(&self) -> Result<(), ValidationError> {
+            line_schema().validate(self)
+        }
+
+        fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+            line_schema().apply(self, run);
+        }
+    }
+
+    fn line_schema() -> Schema<Line> {
+        Validator::for_type::<Line>()
+            .field("sku", |line| &line.sku, vec![Box::new(Required), Box::new(Length { min: 3, max: None })])
+            .field("quantity", |line| &line.quantity, vec![Box::new(Min { value: 1 })])
+    }
+
+    fn order_schema() -> Schema<Order> {
+        Validator::for_type::<Order>()
+            .field("reference", |order| &order.reference, vec![Box::new(Required)])
+            .nested("line", |order| &order.line)
+    }
+
+    fn order(sku: &str, quantity: i32) -> Order {
+        Order {
+            reference: String::new(),
+            line: Line { sku: sku.to_string(), quantity },
+        }
+    }
+
+    fn paths(err: &ValidationError) -> Vec<String> {
+        err.leaves().into_iter().map(|(path, violation)| format!("{} {}", path, violation.code)).collect()
+    }
+
+    #[test]
+    fn fields_are_reported_at_their_paths() {
+        let err = order_schema().validate_all(&order("", 0)).unwrap_err();
+        assert_eq!(paths(&err), ["line.quantity min", "line.sku required", "line.sku length.min", "reference required"]);
+        let paths: Vec<String> = order_schema().fields().iter().map(|path| path.to_string()).collect();
+        assert_eq!(paths, ["reference", "line"]);
+    }
+
+    #[test]
+    fn modes_decide_how_far_validation_goes() {
+        // Fail-fast stops at the first failing rule
+        let schema = order_schema().with_mode(ValidationMode::FailFast);
+        let err = schema.validate(&order("", 0)).unwrap_err();
+        assert_eq!(paths(&err), ["reference required"]);
+
+        // Bail stops checking a field after its first error
+        let schema = line_schema().with_mode(ValidationMode::CollectAll).bail(true);
+        let err = schema.validate(&Line { sku: String::new(), quantity: 0 }).unwrap_err();
+        assert_eq!(paths(&err), ["quantity min", "sku required"]);
+
+        let schema = line_schema().with_mode(ValidationMode::CollectAll);
+        let err = schema.validate(&Line { sku: String::new(), quantity: 1 }).unwrap_err();
+        assert_eq!(paths(&err), ["sku required", "sku length.min"]);
+    }
+
+    #[test]
+    fn reports_hold_every_finding() {
+        let report = order_schema().report(&Order { reference: "A-1".to_string(), line: Line { sku: "ABC".to_string(), quantity: 2 } });
+        assert!(report.is_valid());
+        assert!(report.is_empty());
+    }
+}
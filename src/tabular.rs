@@ -0,0 +1,601 @@
+//! Row-by-row validation of tabular data such as CSV
+//!
+//! A [`TableSchema`] binds rules to columns by header name. Rows are read and
+//! checked one at a time, so large files are validated without loading them,
+//! and every finding is reported at its row and column:
+//!
+//! ```
+//! # use rustvalidity::rules::common::Email;
+//! # use rustvalidity::rules::numeric::Range;
+//! # use rustvalidity::tabular::TableSchema;
+//! # use rustvalidity::validator::ValidationMode;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let schema = TableSchema::new()
+//!     .with_mode(ValidationMode::CollectAll)
+//!     .required("email")
+//!     .column::<String>("email", vec![Box::new(Email { check_dns: false })])
+//!     .column::<i64>("quantity", vec![Box::new(Range { min: 1, max: 100 })]);
+//!
+//! let csv = "email,quantity\nada@example.com,3\n,250\nbob@example.com,three\n";
+//! let report = schema.validate_csv(csv.as_bytes())?;
+//! for entry in report.entries() {
+//!     println!("row {}, {}: {}", entry.row, entry.column, entry.violation); // row 3, quantity: Value is not an integer
+//! }
+//! assert_eq!(report.row_count(), 3);
+//! assert_eq!(report.invalid_rows(), 2);
+//! assert_eq!(report.counts().get("range"), Some(&1));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Cells are converted with [`FromValue`] to the type the rules of their column
+//! are written for, so `"42"` is a number for a `Range<i64>`. Empty cells are
+//! missing: column rules skip them, and [`TableSchema::required`] reports them.
+//!
+//! The header is checked as well: columns with rules that are missing from it,
+//! columns it repeats and, unless [`TableSchema::allow_unexpected`] says
+//! otherwise, columns without rules are reported. The rules of a missing
+//! column are not checked.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::context::Clock;
+use crate::dynamic::FromValue;
+use crate::error::{ErrorEntry, Violation};
+use crate::path::FieldPath;
+use crate::report::ValidationReport;
+use crate::rules::common::Required;
+use crate::rules::{Rule, RuleInfo};
+use crate::validator::{ValidationMode, ValidationRun, Validator};
+
+/// Code reported for a column with rules that the header lacks
+pub const MISSING_COLUMN: &str = "column.missing";
+
+/// Code reported for a header column without rules
+pub const UNEXPECTED_COLUMN: &str = "column.unexpected";
+
+/// Code reported for a column the header names more than once
+pub const DUPLICATE_COLUMN: &str = "column.duplicate";
+
+/// Code reported for a row with more cells than the header has columns
+pub const ROW_LENGTH: &str = "row_length";
+
+type CellCheck = Box<dyn Fn(FieldPath, &Value, &mut ValidationRun, &mut bool) + Send + Sync>;
+
+/// Errors raised while reading tabular data
+#[derive(Debug, Error)]
+pub enum TableError {
+    #[error("Failed to read CSV: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+/// A column with the rules checking its cells
+struct Column {
+    name: String,
+    rules: Vec<RuleInfo>,
+    checks: Vec<CellCheck>,
+}
+
+/// Rules bound to the columns of a table, checked row by row
+pub struct TableSchema {
+    validator: Validator,
+    columns: Vec<Column>,
+    allow_unexpected: bool,
+}
+
+impl TableSchema {
+    /// Create a schema without any column
+    pub fn new() -> Self {
+        TableSchema {
+            validator: Validator::new(),
+            columns: Vec::new(),
+            allow_unexpected: false,
+        }
+    }
+
+    /// Set how far the validation of a row goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.validator = self.validator.with_mode(mode);
+        self
+    }
+
+    /// Stop checking a cell after its first error while still checking the other cells
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.validator = self.validator.bail(bail);
+        self
+    }
+
+    /// Add data available to every rule through its context
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.validator = self.validator.with_data(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.validator = self.validator.with_clock(clock);
+        self
+    }
+
+    /// Accept header columns without rules instead of reporting them
+    pub fn allow_unexpected(mut self, allow: bool) -> Self {
+        self.allow_unexpected = allow;
+        self
+    }
+
+    /// Require the cells of a column to be filled in
+    pub fn required(self, column: &str) -> Self {
+        let rules: Vec<Box<dyn Rule<Value>>> = vec![Box::new(Required)];
+        self.bind(column, rules.iter().map(|rule| rule.describe()).collect(), Box::new(move |path, cell, run, _| {
+            run.rules(path, cell, &rules);
+        }))
+    }
+
+    /// Bind rules on a type to a column, converting its cells with [`FromValue`]
+    ///
+    /// Empty cells are skipped; combine with [`TableSchema::required`] for
+    /// columns that must be filled in. A cell that cannot be converted is
+    /// reported with the `invalid_type` code, e.g. "Value is not a number".
+    pub fn column<T: FromValue + 'static>(self, column: &str, rules: Vec<Box<dyn Rule<T>>>) -> Self {
+        self.bind(column, rules.iter().map(|rule| rule.describe()).collect(), Box::new(move |path, cell, run, mistyped| {
+            if cell.is_null() {
+                return;
+            }
+            match T::from_value(cell) {
+                Ok(value) => {
+                    run.rules(path, &value, &rules);
+                },
+                Err(err) => {
+                    // Columns with several typed bindings report a cell they cannot read once
+                    if !std::mem::replace(mistyped, true) {
+                        run.result(path, Err(err));
+                    }
+                },
+            }
+        }))
+    }
+
+    fn bind(mut self, column: &str, rules: Vec<RuleInfo>, check: CellCheck) -> Self {
+        match self.columns.iter_mut().find(|bound| bound.name == column) {
+            Some(bound) => {
+                bound.rules.extend(rules);
+                bound.checks.push(check);
+            },
+            None => self.columns.push(Column {
+                name: column.to_string(),
+                rules,
+                checks: vec![check],
+            }),
+        }
+        self
+    }
+
+    /// The columns rules are bound to, in declaration order
+    pub fn columns(&self) -> Vec<&str> {
+        self.columns.iter().map(|column| column.name.as_str()).collect()
+    }
+
+    /// Describe the rules bound to each column, in declaration order
+    pub fn describe(&self) -> Vec<(&str, &[RuleInfo])> {
+        self.columns.iter().map(|column| (column.name.as_str(), column.rules.as_slice())).collect()
+    }
+
+    /// Check the columns of a header, reporting at the names of the offending columns
+    pub fn check_header<I, S>(&self, header: I) -> ValidationReport
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let header: Vec<String> = header.into_iter().map(|name| name.as_ref().to_string()).collect();
+        let mut report = ValidationReport::new();
+        let mut seen = HashSet::new();
+        for name in &header {
+            if !seen.insert(name.as_str()) {
+                report.add(column_path(name), Violation::new(DUPLICATE_COLUMN, format!("Column '{}' appears more than once", name)));
+            } else if !self.allow_unexpected && !self.columns.iter().any(|column| column.name == *name) {
+                report.add(column_path(name), Violation::new(UNEXPECTED_COLUMN, format!("Unexpected column '{}'", name)));
+            }
+        }
+        for column in &self.columns {
+            if !seen.contains(column.name.as_str()) {
+                report.add(
+                    column_path(&column.name),
+                    Violation::new(MISSING_COLUMN, format!("Missing column '{}'", column.name)),
+                );
+            }
+        }
+        report
+    }
+
+    /// Check the cells of a row, given in the order of the header's columns
+    ///
+    /// Findings are reported at the names of the columns. Rows shorter than the
+    /// header have empty cells in the columns they lack.
+    pub fn check_row<H, C>(&self, header: &[H], cells: &[C]) -> ValidationReport
+    where
+        H: AsRef<str>,
+        C: AsRef<str>,
+    {
+        let mut row = Map::new();
+        for (name, cell) in header.iter().zip(cells.iter().map(Some).chain(std::iter::repeat(None))) {
+            let cell = match cell.map(AsRef::as_ref) {
+                Some(text) if !text.is_empty() => Value::String(text.to_string()),
+                _ => Value::Null,
+            };
+            // The first of repeated columns is the one checked
+            row.entry(name.as_ref()).or_insert(cell);
+        }
+
+        let mut run = self.validator.start();
+        if cells.len() > header.len() {
+            let message = format!("Row has {} cells, the header has {} columns", cells.len(), header.len());
+            run.result(FieldPath::root(), Err(Violation::new(ROW_LENGTH, message).into()));
+        }
+        run.scope(&row, |run| {
+            for column in &self.columns {
+                let Some(cell) = row.get(&column.name) else {
+                    continue;
+                };
+                let mut mistyped = false;
+                for check in &column.checks {
+                    if run.is_stopped() {
+                        return;
+                    }
+                    check(column_path(&column.name), cell, run, &mut mistyped);
+                }
+            }
+        });
+        run.finish()
+    }
+
+    /// Read CSV rows with a header row, checking them one at a time
+    ///
+    /// The header is checked as soon as the reader is created and is available
+    /// through [`CsvRows::header`].
+    pub fn csv_rows<R: Read>(&self, reader: R) -> Result<CsvRows<'_, R>, TableError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let header: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+        Ok(CsvRows {
+            report: self.check_header(&header),
+            schema: self,
+            header,
+            records: reader.into_records(),
+            row: 0,
+        })
+    }
+
+    /// Validate CSV data with a header row, collecting the findings of every row
+    pub fn validate_csv<R: Read>(&self, reader: R) -> Result<TableReport, TableError> {
+        let mut rows = self.csv_rows(reader)?;
+        let mut report = TableReport {
+            header: rows.header().clone(),
+            rows: Vec::new(),
+            row_count: 0,
+        };
+        for row in &mut rows {
+            let row = row?;
+            report.row_count += 1;
+            if !row.report.is_empty() {
+                report.rows.push(row);
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl Default for TableSchema {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The path findings of a column are reported at, its name as a single field
+fn column_path(name: &str) -> FieldPath {
+    FieldPath::root().field(name)
+}
+
+/// Rows of a CSV reader, each checked as it is read, created by [`TableSchema::csv_rows`]
+pub struct CsvRows<'s, R> {
+    schema: &'s TableSchema,
+    header: Vec<String>,
+    report: ValidationReport,
+    records: csv::StringRecordsIntoIter<R>,
+    row: usize,
+}
+
+impl<R> CsvRows<'_, R> {
+    /// The findings of the header check
+    pub fn header(&self) -> &ValidationReport {
+        &self.report
+    }
+
+    /// The names of the header's columns
+    pub fn columns(&self) -> &[String] {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for CsvRows<'_, R> {
+    type Item = Result<RowReport, TableError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err.into())),
+        };
+        self.row += 1;
+        let cells: Vec<&str> = record.iter().collect();
+        Some(Ok(RowReport {
+            row: self.row,
+            line: record.position().map_or(0, |position| position.line()),
+            report: self.schema.check_row(&self.header, &cells),
+        }))
+    }
+}
+
+/// The findings of a row
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowReport {
+    /// Number of the row among the data rows, starting at 1 after the header
+    pub row: usize,
+
+    /// Line of the file the row starts on
+    pub line: u64,
+
+    /// Findings keyed by column name
+    pub report: ValidationReport,
+}
+
+/// A finding at a row and column of a table, as listed by [`TableReport::entries`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CellEntry {
+    pub row: usize,
+    pub line: u64,
+
+    /// The column name, or an empty path for findings about the row as a whole
+    pub column: FieldPath,
+
+    #[serde(flatten)]
+    pub violation: Violation,
+}
+
+/// The findings of a table: its header and the rows that have findings
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableReport {
+    header: ValidationReport,
+    rows: Vec<RowReport>,
+    row_count: usize,
+}
+
+impl TableReport {
+    /// Check whether neither the header nor a row has an `Error` finding
+    pub fn is_valid(&self) -> bool {
+        self.header.is_valid() && self.rows.iter().all(|row| row.report.is_valid())
+    }
+
+    /// The findings of the header check
+    pub fn header(&self) -> &ValidationReport {
+        &self.header
+    }
+
+    /// The rows with findings, in file order
+    pub fn rows(&self) -> &[RowReport] {
+        &self.rows
+    }
+
+    /// The findings of a row, by its number
+    pub fn row(&self, row: usize) -> Option<&ValidationReport> {
+        self.rows.iter().find(|report| report.row == row).map(|report| &report.report)
+    }
+
+    /// The findings of a cell, by row number and column name
+    pub fn cell(&self, row: usize, column: &str) -> Vec<&Violation> {
+        self.row(row)
+            .and_then(|report| report.findings().get(column_path(column)))
+            .map(|tree| tree.violations().iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Number of data rows read
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Number of rows with an `Error` finding
+    pub fn invalid_rows(&self) -> usize {
+        self.rows.iter().filter(|row| !row.report.is_valid()).count()
+    }
+
+    /// Every finding of the rows, by row and then by column
+    pub fn entries(&self) -> Vec<CellEntry> {
+        self.rows
+            .iter()
+            .flat_map(|row| {
+                row.report.findings().leaves().into_iter().map(|(column, violation)| CellEntry {
+                    row: row.row,
+                    line: row.line,
+                    column,
+                    violation: violation.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Number of row findings per rule, named by the first part of their code, e.g. `length` for `length.min`
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for row in &self.rows {
+            for (_, violation) in row.report.findings().leaves() {
+                let rule = violation.code.split('.').next().unwrap_or_default();
+                *counts.entry(rule.to_string()).or_default() += 1;
+            }
+        }
+        counts
+    }
+}
+
+#[derive(Serialize)]
+struct WireTableReport {
+    valid: bool,
+    rows: usize,
+    invalid_rows: usize,
+    header: Vec<ErrorEntry>,
+    findings: Vec<CellEntry>,
+    counts: BTreeMap<String, usize>,
+}
+
+/// Serializes as `{"valid", "rows", "invalid_rows", "header": [ErrorEntry], "findings": [CellEntry], "counts"}`
+impl Serialize for TableReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WireTableReport {
+            valid: self.is_valid(),
+            rows: self.row_count,
+            invalid_rows: self.invalid_rows(),
+            header: self.header.entries(),
+            findings: self.entries(),
+            counts: self.counts(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::common::Length;
+    use crate::rules::numeric::Range;
+
+    fn schema() -> TableSchema {
+        TableSchema::new()
+            .with_mode(ValidationMode::CollectAll)
+            .required("name")
+            .column::<String>("name", vec![Box::new(Length { min: 2, max: Some(10) })])
+            .column::<i64>("age", vec![Box::new(Range { min: 0, max: 150 })])
+    }
+
+    fn codes(report: &ValidationReport) -> Vec<(String, String)> {
+        report
+            .findings()
+            .leaves()
+            .into_iter()
+            .map(|(path, violation)| (path.to_string(), violation.code.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn header_reports_missing_unexpected_and_duplicate_columns() {
+        let report = schema().check_header(["name", "email", "name"]);
+        let mut found = codes(&report);
+        found.sort();
+        assert_eq!(found, vec![
+            ("age".to_string(), MISSING_COLUMN.to_string()),
+            ("email".to_string(), UNEXPECTED_COLUMN.to_string()),
+            ("name".to_string(), DUPLICATE_COLUMN.to_string()),
+        ]);
+
+        let report = schema().allow_unexpected(true).check_header(["age", "name", "email"]);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn rows_report_at_their_columns() {
+        let header = ["name", "age"];
+        assert!(schema().check_row(&header, &["Ada", "36"]).is_empty());
+
+        let report = schema().check_row(&header, &["A", "200"]);
+        assert_eq!(codes(&report), vec![
+            ("age".to_string(), "range.max".to_string()),
+            ("name".to_string(), "length.min".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn empty_cells_are_missing() {
+        let header = ["name", "age"];
+        // The optional age is skipped, the required name is reported once
+        let report = schema().check_row(&header, &["", ""]);
+        assert_eq!(codes(&report), vec![("name".to_string(), "required".to_string())]);
+
+        // Short rows lack their last cells
+        let report = schema().check_row(&header, &["Ada"]);
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn cells_are_converted_to_the_rule_type() {
+        let header = ["name", "age"];
+        let report = schema().check_row(&header, &["Ada", "thirty"]);
+        let found = report.entries();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path.to_string(), "age");
+        assert_eq!(found[0].violation.code, "invalid_type");
+        assert_eq!(found[0].violation.message, "Value is not an integer");
+
+        let report = schema().check_row(&header, &["Ada", "36.5"]);
+        assert_eq!(codes(&report), vec![("age".to_string(), "invalid_type".to_string())]);
+    }
+
+    #[test]
+    fn long_rows_are_reported_at_the_root() {
+        let report = schema().check_row(&["name", "age"], &["Ada", "36", "extra"]);
+        assert_eq!(codes(&report), vec![(String::new(), ROW_LENGTH.to_string())]);
+    }
+
+    #[test]
+    fn csv_reports_rows_lines_and_counts() {
+        let csv = "name,age,email\nAda,36,a@example.com\nB,200,b@example.com\n\"Cy\nril\",x,c@example.com\n,,\n";
+        let report = schema().validate_csv(csv.as_bytes()).unwrap();
+
+        assert!(!report.is_valid());
+        assert_eq!(codes(report.header()), vec![("email".to_string(), UNEXPECTED_COLUMN.to_string())]);
+        assert_eq!(report.row_count(), 4);
+        assert_eq!(report.invalid_rows(), 3);
+        assert!(report.row(1).is_none());
+
+        let rows: Vec<(usize, u64)> = report.rows().iter().map(|row| (row.row, row.line)).collect();
+        assert_eq!(rows, vec![(2, 3), (3, 4), (4, 6)]);
+
+        let cells: Vec<&str> = report.cell(2, "age").iter().map(|violation| violation.code.as_str()).collect();
+        assert_eq!(cells, vec!["range.max"]);
+
+        let entries: Vec<(usize, String, String)> = report
+            .entries()
+            .into_iter()
+            .map(|entry| (entry.row, entry.column.to_string(), entry.violation.code))
+            .collect();
+        assert_eq!(entries, vec![
+            (2, "age".to_string(), "range.max".to_string()),
+            (2, "name".to_string(), "length.min".to_string()),
+            (3, "age".to_string(), "invalid_type".to_string()),
+            (4, "name".to_string(), "required".to_string()),
+        ]);
+
+        let counts: Vec<(String, usize)> = report.counts().into_iter().collect();
+        assert_eq!(counts, vec![
+            ("invalid_type".to_string(), 1),
+            ("length".to_string(), 1),
+            ("range".to_string(), 1),
+            ("required".to_string(), 1),
+        ]);
+
+        let wire = serde_json::to_value(&report).unwrap();
+        assert_eq!(wire["rows"], 4);
+        assert_eq!(wire["invalid_rows"], 3);
+        assert_eq!(wire["findings"][0]["row"], 2);
+        assert_eq!(wire["findings"][0]["column"], "age");
+    }
+
+    #[test]
+    fn malformed_csv_is_an_error() {
+        let csv: &[u8] = b"name,age\n\xff\xfe,1\n";
+        let schema = schema();
+        let mut rows = schema.csv_rows(csv).unwrap();
+        assert!(matches!(rows.next(), Some(Err(TableError::Csv(_)))));
+    }
+}
@@ -0,0 +1,162 @@
+//! Values that are known to have passed validation
+//!
+//! A [`Validated<T>`] can only be obtained by validating a `T`, so a function
+//! taking one does not need to validate its argument again:
+//!
+//! ```
+//! # use rustvalidity::error::ValidationError;
+//! # use rustvalidity::validated::Validated;
+//! # use rustvalidity::validator::Validate;
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     email: String,
+//! }
+//!
+//! impl Validate for User {
+//!     fn validate(&self) -> Result<(), ValidationError> {
+//!         if self.email.contains('@') { Ok(()) } else { Err(ValidationError::new("Invalid email")) }
+//!     }
+//! }
+//!
+//! fn register(user: Validated<User>) -> String {
+//!     user.email.clone() // known to be valid
+//! }
+//!
+//! # fn main() -> Result<(), serde_json::Error> {
+//! let user: Validated<User> = serde_json::from_str(r#"{"email": "ada@example.com"}"#)?;
+//! assert_eq!(register(user), "ada@example.com");
+//!
+//! // Invalid users fail to deserialize
+//! assert!(serde_json::from_str::<Validated<User>>(r#"{"email": "ada"}"#).is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::ops::Deref;
+
+use serde::de::{Deserialize, Deserializer, Error as _};
+use serde::ser::{Serialize, Serializer};
+
+use crate::error::ValidationError;
+use crate::validator::Validate;
+
+/// A value that passed [`Validate::validate`]
+///
+/// Dereferences to the value but gives no mutable access, which could make
+/// it invalid again; take it apart with [`Validated::into_inner`] to change it.
+/// Deserializing a `Validated<T>` deserializes a `T` and validates it,
+/// reporting validation errors as deserialization errors.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Validated<T>(T);
+
+impl<T: Validate> Validated<T> {
+    /// Validate a value, keeping it if it is valid
+    pub fn new(value: T) -> Result<Self, ValidationError> {
+        value.validate()?;
+        Ok(Validated(value))
+    }
+
+    /// Validate a value against the rules of a group, e.g. `"create"`
+    pub fn with_group(value: T, group: &str) -> Result<Self, ValidationError> {
+        value.validate_group(group)?;
+        Ok(Validated(value))
+    }
+}
+
+impl<T> Validated<T> {
+    /// The validated value
+    pub fn get(&self) -> &T {
+        &self.0
+    }
+
+    /// Give up the proof of validation and return the value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Validated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for Validated<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Validated<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: Serialize> Serialize for Validated<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Validate> Deserialize<'de> for Validated<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = T::deserialize(deserializer)?;
+        Validated::new(value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+    struct Quantity {
+        amount: i32,
+    }
+
+    impl Validate for Quantity {
+        fn validate(&self) -> Result<(), ValidationError> {
+            if self.amount > 0 {
+                Ok(())
+            } else {
+                Err(ValidationError::new("Amount must be positive"))
+            }
+        }
+    }
+
+    #[test]
+    fn only_valid_values_are_wrapped() {
+        let quantity = Validated::new(Quantity { amount: 3 }).unwrap();
+        assert_eq!(quantity.amount, 3);
+        assert_eq!(quantity.get(), &Quantity { amount: 3 });
+        assert_eq!(quantity.into_inner().amount, 3);
+
+        assert!(Validated::new(Quantity { amount: 0 }).is_err());
+        assert!(Quantity { amount: -1 }.into_validated().is_err());
+        assert!(Validated::with_group(Quantity { amount: 1 }, "create").is_ok());
+    }
+
+    #[test]
+    fn deserializing_validates_the_value() {
+        let quantity: Validated<Quantity> = serde_json::from_str(r#"{"amount": 2}"#).unwrap();
+        assert_eq!(quantity.amount, 2);
+
+        let err = serde_json::from_str::<Validated<Quantity>>(r#"{"amount": 0}"#).unwrap_err();
+        assert!(err.to_string().contains("Amount must be positive"));
+
+        // Malformed input fails before validation
+        let err = serde_json::from_str::<Validated<Quantity>>(r#"{"amount": "two"}"#).unwrap_err();
+        assert!(err.is_data());
+    }
+
+    #[test]
+    fn serializes_as_the_value() {
+        let quantity = Validated::new(Quantity { amount: 5 }).unwrap();
+        assert_eq!(serde_json::to_value(&quantity).unwrap(), serde_json::json!({"amount": 5}));
+    }
+}
@@ -1,19 +1,96 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::any::Any;
-use std::marker::PhantomData;
+use std::fmt;
+use std::sync::Arc;
 
-use crate::error::ValidationError;
-use crate::rules::Rule;
+use crate::context::{Clock, ContextData, SystemClock, ValidationContext};
+use crate::error::{ValidationError, Violation};
+use crate::path::FieldPath;
+use crate::report::ValidationReport;
+use crate::rules::{Rule, RuleInfo};
+use crate::schema::Schema;
+use crate::validated::Validated;
+
+/// Code reported when a field refers to a rule that was never registered
+pub const UNKNOWN_RULE: &str = "rule.unknown";
+
+/// The group of rules that are not tagged with any group
+///
+/// Groups inherit the default group unless they are declared with other
+/// parents, e.g. with [`Validator::with_group`].
+pub const DEFAULT_GROUP: &str = "default";
+
+/// How far validation goes once a rule has failed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValidationMode {
+    /// Stop at the first error
+    #[default]
+    FailFast,
+
+    /// Run every rule and collect every error
+    CollectAll,
+}
 
 /// Trait for types that can be validated
+///
+/// `validate` only fails on `Error` violations. Implementations with advisory
+/// rules collect their findings in a [`ValidationReport`] and return
+/// [`ValidationReport::into_result`], overriding `report` to expose the
+/// warnings as well.
 pub trait Validate {
     /// Validate the value and return a Result
     fn validate(&self) -> Result<(), ValidationError>;
+
+    /// Validate the value and return every finding, including warnings
+    fn report(&self) -> ValidationReport {
+        ValidationReport::from_result(self.validate())
+    }
+
+    /// Validate the value within a run started by a [`Validator`]
+    ///
+    /// Implement this to check fields against the rules registered on the
+    /// validator, honoring its mode. Hand-written implementations must override
+    /// it for [`Validator::validate_all`] and bailing to apply to their fields:
+    /// the default records the findings of `report` as a whole, so it collects
+    /// no more errors than `validate` stops at on its own. It must also be
+    /// overridden when `validate` itself delegates to a validator.
+    ///
+    /// The checks of the default are not tagged with groups, so they belong to
+    /// the [default group](DEFAULT_GROUP) and are skipped by runs checking a
+    /// group that does not inherit it.
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        if run.selects(None) {
+            run.record(FieldPath::root(), self.report());
+        }
+    }
+
+    /// Validate the value against the rules of a group, e.g. `"create"`
+    ///
+    /// The default runs a [`Validator`] in fail-fast mode. Only implementations
+    /// of `validate_with` select rules by group: without one, every check is in
+    /// the default group, which the groups of a plain validator inherit, so
+    /// the value is validated in full whatever the group.
+    fn validate_group(&self, group: &str) -> Result<(), ValidationError> {
+        Validator::new().validate_group(self, group)
+    }
+
+    /// Validate the value and wrap it as proof that it passed
+    fn into_validated(self) -> Result<Validated<Self>, ValidationError>
+    where
+        Self: Sized,
+    {
+        Validated::new(self)
+    }
 }
 
 /// Main validator struct that holds validation rules
 pub struct Validator {
     rules: HashMap<String, Box<dyn Rule>>,
+    mode: ValidationMode,
+    bail: bool,
+    data: ContextData,
+    clock: Arc<dyn Clock>,
+    groups: HashMap<String, Vec<String>>,
 }
 
 impl Validator {
@@ -21,8 +98,85 @@ impl Validator {
     pub fn new() -> Self {
         Validator {
             rules: HashMap::new(),
+            mode: ValidationMode::default(),
+            bail: false,
+            data: ContextData::new(),
+            clock: Arc::new(SystemClock),
+            groups: HashMap::new(),
         }
     }
+
+    /// Start a schema binding rules to the fields of a type
+    pub fn for_type<T: 'static>() -> Schema<T> {
+        Schema::new()
+    }
+
+    /// Set how far validation goes once a rule has failed
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Stop checking a field after its first error while still checking the other fields
+    pub fn bail(mut self, bail: bool) -> Self {
+        self.bail = bail;
+        self
+    }
+
+    /// Add data available to every rule through its [`ValidationContext`]
+    pub fn with_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.data.insert(data);
+        self
+    }
+
+    /// Set the clock rules read the current time from
+    pub fn with_clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Declare a group with the groups it inherits, replacing the default group as its parent
+    ///
+    /// ```
+    /// # use rustvalidity::Validator;
+    /// // "update" checks its own rules and those of "profile", but not the untagged ones
+    /// let validator = Validator::new()
+    ///     .with_group("update", ["profile"])
+    ///     .with_group("profile", Vec::<String>::new());
+    /// assert_eq!(validator.resolve_group("update"), ["update", "profile"]);
+    /// ```
+    pub fn with_group<I, S>(mut self, group: &str, parents: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.groups.insert(group.to_string(), parents.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// A group together with every group it inherits
+    pub fn resolve_group(&self, group: &str) -> Vec<String> {
+        let mut resolved = Vec::new();
+        let mut seen = HashSet::new();
+        let mut pending = vec![group.to_string()];
+        while let Some(group) = pending.pop() {
+            if !seen.insert(group.clone()) {
+                continue;
+            }
+            match self.groups.get(&group) {
+                Some(parents) => pending.extend(parents.iter().rev().cloned()),
+                None if group != DEFAULT_GROUP => pending.push(DEFAULT_GROUP.to_string()),
+                None => {},
+            }
+            resolved.push(group);
+        }
+        resolved
+    }
+
+    /// The mode used by [`Validator::validate`]
+    pub fn mode(&self) -> ValidationMode {
+        self.mode
+    }
     
     /// Add a rule to the validator
     pub fn add_rule<R>(&mut self, name: &str, rule: R) 
@@ -36,17 +190,267 @@ impl Validator {
     pub fn get_rule(&self, name: &str) -> Option<&dyn Rule> {
         self.rules.get(name).map(|r| r.as_ref())
     }
+
+    /// The registered rules ordered by name
+    pub fn rules(&self) -> Vec<(&str, &dyn Rule)> {
+        let mut rules: Vec<(&str, &dyn Rule)> = self.rules.iter().map(|(name, rule)| (name.as_str(), rule.as_ref())).collect();
+        rules.sort_by_key(|(name, _)| *name);
+        rules
+    }
+
+    /// Describe the registered rules, ordered by the names they are registered under
+    pub fn describe(&self) -> Vec<(&str, RuleInfo)> {
+        self.rules().into_iter().map(|(name, rule)| (name, rule.describe())).collect()
+    }
     
-    /// Validate a value against the rules
+    /// Start a validation run in the validator's mode
+    pub fn start(&self) -> ValidationRun<'_> {
+        self.start_with(self.mode)
+    }
+
+    /// Start a validation run in a specific mode
+    pub fn start_with(&self, mode: ValidationMode) -> ValidationRun<'_> {
+        ValidationRun {
+            validator: self,
+            mode,
+            report: ValidationReport::new(),
+            stopped: false,
+            root: None,
+            parent: None,
+            prefix: FieldPath::root(),
+            data: &self.data,
+            clock: self.clock.as_ref(),
+            groups: None,
+        }
+    }
+
+    /// Validate a value in the validator's mode
     pub fn validate<T: Validate + ?Sized>(&self, value: &T) -> Result<(), ValidationError> {
-        // Use the Validate trait for validation
-        value.validate()
+        self.report_with(value, self.mode).into_result()
     }
     
     /// Validate all fields and collect all errors
     pub fn validate_all<T: Validate + ?Sized>(&self, value: &T) -> Result<(), ValidationError> {
-        // Similar to validate, but collects all errors instead of stopping at the first one
-        value.validate()
+        self.report_with(value, ValidationMode::CollectAll).into_result()
+    }
+
+    /// Validate a value in the validator's mode and return every finding, including warnings
+    pub fn report<T: Validate + ?Sized>(&self, value: &T) -> ValidationReport {
+        self.report_with(value, self.mode)
+    }
+
+    /// Validate a value against the rules of a group in the validator's mode
+    pub fn validate_group<T: Validate + ?Sized>(&self, value: &T, group: &str) -> Result<(), ValidationError> {
+        self.report_group(value, group).into_result()
+    }
+
+    /// Validate a value against the rules of a group and return every finding, including warnings
+    pub fn report_group<T: Validate + ?Sized>(&self, value: &T, group: &str) -> ValidationReport {
+        let mut run = self.start().with_group(group);
+        value.validate_with(&mut run);
+        run.finish()
+    }
+
+    fn report_with<T: Validate + ?Sized>(&self, value: &T, mode: ValidationMode) -> ValidationReport {
+        let mut run = self.start_with(mode);
+        value.validate_with(&mut run);
+        run.finish()
+    }
+}
+
+/// A single validation pass over a value, started with [`Validator::start`]
+///
+/// Fields are checked against registered rules with [`ValidationRun::field`].
+/// In fail-fast mode the run stops at the first error and every later check is
+/// skipped; with bailing enabled a field stops at its first error.
+///
+/// Rules are called with a [`ValidationContext`] built from the run: the value
+/// of the outermost [`ValidationRun::scope`] is the root, the innermost one the
+/// parent, and paths are prefixed by the enclosing [`ValidationRun::nest`]s.
+///
+/// A run checks the [default group](DEFAULT_GROUP) unless another group is
+/// selected with [`ValidationRun::with_group`]; rules outside the checked
+/// groups are skipped.
+pub struct ValidationRun<'v> {
+    validator: &'v Validator,
+    mode: ValidationMode,
+    report: ValidationReport,
+    stopped: bool,
+    root: Option<&'v dyn Any>,
+    parent: Option<&'v dyn Any>,
+    prefix: FieldPath,
+    data: &'v ContextData,
+    clock: &'v dyn Clock,
+    groups: Option<Arc<[String]>>,
+}
+
+impl<'v> ValidationRun<'v> {
+    /// The mode of the run
+    pub fn mode(&self) -> ValidationMode {
+        self.mode
+    }
+
+    /// Check whether the run has stopped after an error in fail-fast mode
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Use data supplied for this run instead of the validator's
+    pub fn with_data(mut self, data: &'v ContextData) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Use a clock for this run instead of the validator's
+    pub fn with_clock(mut self, clock: &'v dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Check the rules of a group and of the groups it inherits
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.groups = Some(self.validator.resolve_group(group).into());
+        self
+    }
+
+    /// Check whether the run checks rules belonging to some groups; `None` stands for the default group
+    pub fn selects(&self, groups: Option<&[String]>) -> bool {
+        self.context(FieldPath::root()).selects(groups)
+    }
+
+    /// The context of a rule applied to a field of the current parent
+    pub fn context<P: Into<FieldPath>>(&self, path: P) -> ValidationContext<'v> {
+        let mut ctx = ValidationContext::new()
+            .with_path(self.prefix.concat(&path.into()))
+            .with_data(self.data)
+            .with_clock(self.clock);
+        if let Some(root) = self.root {
+            ctx = ctx.with_root(root);
+        }
+        if let Some(parent) = self.parent {
+            ctx = ctx.with_parent(parent);
+        }
+        if let Some(groups) = &self.groups {
+            ctx = ctx.with_group_set(groups.clone());
+        }
+        ctx
+    }
+
+    /// Check the fields of a value, which is the parent in the context of their rules
+    ///
+    /// The value of the first scope entered becomes the root.
+    pub fn scope<V: Any, F: FnOnce(&mut Self)>(&mut self, value: &'v V, f: F) -> &mut Self {
+        let outer = self.parent.replace(value);
+        if self.root.is_none() {
+            self.root = Some(value);
+        }
+        f(self);
+        self.parent = outer;
+        self
+    }
+
+    /// Check a nested value, reporting its findings below a path
+    pub fn nest<P: Into<FieldPath>, F: FnOnce(&mut Self)>(&mut self, path: P, f: F) -> &mut Self {
+        let inner = self.prefix.concat(&path.into());
+        let outer = std::mem::replace(&mut self.prefix, inner);
+        f(self);
+        self.prefix = outer;
+        self
+    }
+
+    /// Check a field against rules registered on the validator, in order
+    ///
+    /// A name without a registered rule is reported with the [`UNKNOWN_RULE`] code.
+    pub fn field<P: Into<FieldPath>, V: Any>(&mut self, path: P, value: &V, rules: &[&str]) -> &mut Self {
+        if self.stopped {
+            return self;
+        }
+        let path = path.into();
+        let validator = self.validator;
+        let ctx = self.context(path.clone());
+        for name in rules {
+            let result = match validator.get_rule(name) {
+                Some(rule) if !ctx.selects(rule.groups()) => continue,
+                Some(rule) => rule.validate_in(value as &dyn Any, &ctx),
+                None => Err(Violation::new(UNKNOWN_RULE, format!("No rule registered as '{}'", name))
+                    .with_param("rule", name)
+                    .into()),
+            };
+            if !self.check(&path, result) {
+                break;
+            }
+        }
+        self
+    }
+
+    /// Check a field against a list of rules, in order
+    pub fn rules<P: Into<FieldPath>, V: ?Sized>(&mut self, path: P, value: &V, rules: &[Box<dyn Rule<V>>]) -> &mut Self {
+        if self.stopped {
+            return self;
+        }
+        let path = path.into();
+        let ctx = self.context(path.clone());
+        for rule in rules.iter().filter(|rule| ctx.selects(rule.groups())) {
+            if !self.check(&path, rule.validate_in(value, &ctx)) {
+                break;
+            }
+        }
+        self
+    }
+
+    /// Record the outcome of a check performed by hand
+    pub fn result<P: Into<FieldPath>>(&mut self, path: P, result: Result<(), ValidationError>) -> &mut Self {
+        self.check(&path.into(), result);
+        self
+    }
+
+    /// Record the findings of a nested validation below a path
+    pub fn record<P: Into<FieldPath>>(&mut self, path: P, report: ValidationReport) -> &mut Self {
+        if self.stopped {
+            return self;
+        }
+        let fatal = !report.is_valid();
+        let path = self.prefix.concat(&path.into());
+        for (inner, violation) in report.findings().leaves() {
+            self.report.add(path.concat(&inner), violation.clone());
+        }
+        if fatal && self.mode == ValidationMode::FailFast {
+            self.stopped = true;
+        }
+        self
+    }
+
+    /// Finish the run and return its findings
+    pub fn finish(self) -> ValidationReport {
+        self.report
+    }
+
+    /// Finish the run and return its errors
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        self.report.into_result()
+    }
+
+    /// Record a result and tell whether the current field should keep going
+    fn check(&mut self, path: &FieldPath, result: Result<(), ValidationError>) -> bool {
+        if self.stopped {
+            return false;
+        }
+        let Err(err) = result else {
+            return true;
+        };
+
+        let fatal = err.is_fatal();
+        self.report.record_at(self.prefix.concat(path), err);
+        if !fatal {
+            return true;
+        }
+        match self.mode {
+            ValidationMode::FailFast => {
+                self.stopped = true;
+                false
+            },
+            ValidationMode::CollectAll => !self.validator.bail,
+        }
     }
 }
 
@@ -56,31 +460,254 @@ impl Default for Validator {
     }
 }
 
+/// An absent value is valid; a present one is validated as it is
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        if let Some(value) = self {
+            value.validate_with(run);
+        }
+    }
+}
+
+impl<T: Validate + ?Sized> Validate for Box<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        (**self).validate()
+    }
+
+    fn report(&self) -> ValidationReport {
+        (**self).report()
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        (**self).validate_with(run);
+    }
+}
+
+/// Elements are validated in order, each reporting below its index
+impl<T: Validate> Validate for [T] {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        for (index, item) in self.iter().enumerate() {
+            if run.is_stopped() {
+                break;
+            }
+            run.nest(index, |run| item.validate_with(run));
+        }
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        self.as_slice().validate_with(run);
+    }
+}
+
+/// Values are validated in key order, each reporting below its key
+impl<K: fmt::Display, V: Validate> Validate for BTreeMap<K, V> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        for (key, value) in self {
+            if run.is_stopped() {
+                break;
+            }
+            run.nest(FieldPath::root().key(key.to_string()), |run| value.validate_with(run));
+        }
+    }
+}
+
+/// Values are validated each reporting below its key
+impl<K: fmt::Display, V: Validate, S> Validate for HashMap<K, V, S> {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Validator::new().validate(self)
+    }
+
+    fn validate_with<'v>(&'v self, run: &mut ValidationRun<'v>) {
+        for (key, value) in self {
+            if run.is_stopped() {
+                break;
+            }
+            run.nest(FieldPath::root().key(key.to_string()), |run| value.validate_with(run));
+        }
+    }
+}
+
 /// A pattern for combining multiple validation rules
 pub struct Pattern<T> {
-    rules: Vec<Box<dyn Rule>>,
-    _marker: PhantomData<T>,
+    rules: Vec<Box<dyn Rule<T>>>,
+    validator: Validator,
 }
 
 impl<T> Pattern<T> {
     /// Create a new pattern with the given rules
-    pub fn new(rules: Vec<Box<dyn Rule>>) -> Self {
+    pub fn new(rules: Vec<Box<dyn Rule<T>>>) -> Self {
         Pattern {
             rules,
-            _marker: PhantomData,
+            validator: Validator::new(),
         }
     }
+
+    /// Set whether the pattern stops at its first error or reports every failing rule
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.validator = self.validator.with_mode(mode);
+        self
+    }
     
     /// Validate a value against all rules in the pattern
-    pub fn validate(&self, value: &T) -> Result<(), ValidationError> 
-    where
-        T: Any,
-    {
-        for rule in &self.rules {
-            if let Err(err) = rule.validate_any(value) {
-                return Err(err);
-            }
+    ///
+    /// Warnings do not fail validation.
+    pub fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self.report(value).into_result()
+    }
+
+    /// Validate a value against all rules in the pattern and return every finding
+    pub fn report(&self, value: &T) -> ValidationReport {
+        let mut run = self.validator.start();
+        run.rules(FieldPath::root(), value, &self.rules);
+        run.finish()
+    }
+
+    /// Check a field against the pattern as part of a validation run
+    ///
+    /// The run's mode applies instead of the pattern's own.
+    pub fn apply<P: Into<FieldPath>>(&self, run: &mut ValidationRun<'_>, path: P, value: &T) {
+        run.rules(path, value, &self.rules);
+    }
+}
+
+/// Support for the derive macro, which validates fields of types implementing [`Validate`] below their path
+#[doc(hidden)]
+pub mod __private {
+    use std::marker::PhantomData;
+
+    use super::{Validate, ValidationRun};
+
+    pub struct Probe<T: ?Sized>(PhantomData<T>);
+
+    impl<T: ?Sized> Probe<T> {
+        pub fn new() -> Self {
+            Probe(PhantomData)
+        }
+    }
+
+    impl<T: ?Sized> Default for Probe<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Picked by method resolution on `(&Probe::<T>::new())` when `T` implements [`Validate`]
+    pub trait Nested<T: ?Sized> {
+        fn nest<'v>(&self, run: &mut ValidationRun<'v>, path: &str, value: &'v T);
+    }
+
+    impl<T: Validate + ?Sized> Nested<T> for Probe<T> {
+        fn nest<'v>(&self, run: &mut ValidationRun<'v>, path: &str, value: &'v T) {
+            run.nest(path, |run| value.validate_with(run));
+        }
+    }
+
+    /// Fallback for other types, found only after an extra autoref; they have nothing to validate
+    pub trait Unnested<T: ?Sized> {
+        fn nest<'v>(&self, run: &mut ValidationRun<'v>, path: &str, value: &'v T);
+    }
+
+    impl<T: ?Sized> Unnested<T> for &Probe<T> {
+        fn nest<'v>(&self, _: &mut ValidationRun<'v>, _: &str, _: &'v T) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::numeric::{Max, Min};
+    use crate::rules::{Dynamic, RuleInfo};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// A hand-written implementation without `validate_with`
+    struct Quantity(i32);
+
+    impl Validate for Quantity {
+        fn validate(&self) -> Result<(), ValidationError> {
+            Min { value: 1 }.validate(&self.0)
+        }
+    }
+
+    #[test]
+    fn default_validate_with_checks_groups_inheriting_the_default_group() {
+        assert!(Quantity(0).validate_group("create").is_err());
+        let validator = Validator::new().with_group("update", [DEFAULT_GROUP, "profile"]);
+        assert!(!validator.report_group(&Quantity(0), "update").is_valid());
+    }
+
+    #[test]
+    fn default_validate_with_skips_groups_without_the_default_group() {
+        let validator = Validator::new()
+            .with_group("update", ["profile"])
+            .with_group("profile", Vec::<String>::new());
+        assert!(validator.report_group(&Quantity(0), "update").is_empty());
+        assert!(validator.validate(&Quantity(0)).is_err());
+    }
+
+    #[test]
+    fn pattern_stops_at_first_error_by_default() {
+        let pattern = Pattern::new(vec![Box::new(Min { value: 10 }), Box::new(Max { value: 0 })]);
+        let err = pattern.validate(&5).unwrap_err();
+        assert_eq!(err.violations().len(), 1);
+        assert_eq!(err.code(), Some("min"));
+    }
+
+    #[test]
+    fn pattern_collects_every_error_in_collect_all_mode() {
+        let pattern = Pattern::new(vec![Box::new(Min { value: 10 }), Box::new(Max { value: 0 })])
+            .with_mode(ValidationMode::CollectAll);
+        let report = pattern.report(&5);
+        let codes: Vec<&str> = report.findings().violations().iter().map(|v| v.code.as_str()).collect();
+        assert_eq!(codes, ["min", "max"]);
+    }
+
+    /// A failing rule counting how often it was called
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Rule for Counted {
+        fn validate(&self, _: &dyn Any) -> Result<(), ValidationError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(Violation::new("counted", "Value was counted").into())
+        }
+
+        fn describe(&self) -> RuleInfo {
+            RuleInfo::new("counted", "Value is counted")
         }
-        Ok(())
+    }
+
+    #[test]
+    fn fail_fast_runs_no_rule_after_the_stop() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut validator = Validator::new().with_mode(ValidationMode::FailFast);
+        validator.add_rule("counted", Counted(Arc::clone(&calls)));
+        let rules: Vec<Box<dyn Rule<i32>>> = vec![Box::new(Dynamic(Counted(Arc::clone(&calls))))];
+
+        let mut run = validator.start();
+        run.field("first", &1, &["counted"])
+            .field("second", &2, &["counted"])
+            .rules("third", &3, &rules)
+            .rules("fourth", &4, &rules);
+        assert!(run.is_stopped());
+        assert_eq!(run.finish().findings().leaves().len(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
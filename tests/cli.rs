@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde_json::Value;
+
+const RULES: &str = r#"{"fields": {"name": ["required", {"length": {"min": 3}}], "age": [{"range": {"min": 18, "max": 130}}]}}"#;
+
+/// A directory holding the rule set and data files of one test
+fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustvalidity-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("rules.json"), RULES).unwrap();
+    for (file, content) in files {
+        fs::write(dir.join(file), content).unwrap();
+    }
+    dir
+}
+
+/// Run the binary in a workspace, returning its exit code and JSON report
+fn run(dir: &PathBuf, args: &[&str]) -> (i32, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rustvalidity"))
+        .current_dir(dir)
+        .args(["--rules", "rules.json", "--format", "json"])
+        .args(args)
+        .output()
+        .unwrap();
+    let report = serde_json::from_slice(&output.stdout).unwrap_or(Value::Null);
+    (output.status.code().unwrap(), report)
+}
+
+// Three invalid records, with two, three and two errors
+const INVALID: &str = "{\"name\": \"Al\", \"age\": 7}\n{\"name\": \"\", \"age\": 200}\n{\"name\": \"Bo\", \"age\": 3}\n";
+
+#[test]
+fn valid_records_exit_with_zero() {
+    let dir = workspace("valid", &[("users.ndjson", "{\"name\": \"Ada\", \"age\": 36}\n\n{\"name\": \"Alan\"}\n")]);
+    let (code, report) = run(&dir, &["users.ndjson"]);
+    assert_eq!(code, 0);
+    assert_eq!(report["summary"]["records"], 2);
+    assert_eq!(report["summary"]["valid"], true);
+}
+
+#[test]
+fn invalid_records_exit_with_one() {
+    let dir = workspace("invalid", &[("users.ndjson", INVALID)]);
+    let (code, report) = run(&dir, &["users.ndjson"]);
+    assert_eq!(code, 1);
+    assert_eq!(report["summary"]["invalid"], 3);
+    assert_eq!(report["summary"]["errors"], 7);
+    assert_eq!(report["summary"]["stopped"], false);
+    assert_eq!(report["results"][1]["record"], "line 2");
+}
+
+#[test]
+fn fail_fast_stops_at_the_first_error() {
+    let dir = workspace("fail-fast", &[("users.ndjson", INVALID)]);
+    let (code, report) = run(&dir, &["--fail-fast", "users.ndjson", "users.ndjson"]);
+    assert_eq!(code, 1);
+    assert_eq!(report["summary"]["records"], 1);
+    assert_eq!(report["summary"]["errors"], 1);
+    assert_eq!(report["summary"]["stopped"], true);
+    assert_eq!(report["results"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn max_errors_keeps_the_errors_within_the_limit() {
+    let dir = workspace("max-errors", &[("users.ndjson", INVALID)]);
+    let (code, report) = run(&dir, &["--max-errors", "3", "users.ndjson"]);
+    assert_eq!(code, 1);
+    assert_eq!(report["summary"]["records"], 2);
+    assert_eq!(report["summary"]["invalid"], 2);
+    assert_eq!(report["summary"]["errors"], 3);
+    assert_eq!(report["summary"]["stopped"], true);
+    assert_eq!(report["results"][1]["findings"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn unreadable_files_exit_with_two_after_checking_the_others() {
+    let dir = workspace("unreadable", &[("users.ndjson", INVALID)]);
+    let (code, report) = run(&dir, &["missing.ndjson", "users.ndjson"]);
+    assert_eq!(code, 2);
+    assert_eq!(report["summary"]["unreadable"], 1);
+    assert_eq!(report["summary"]["invalid"], 3);
+    assert_eq!(report["results"][0]["findings"][0]["code"], "unreadable");
+}
+
+#[test]
+fn invalid_rule_sets_exit_with_two() {
+    let dir = workspace("bad-rules", &[("users.ndjson", INVALID), ("bad.json", r#"{"fields": {"name": ["requried"]}}"#)]);
+    let output = Command::new(env!("CARGO_BIN_EXE_rustvalidity"))
+        .current_dir(&dir)
+        .args(["--rules", "bad.json", "users.ndjson"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown rule 'requried' on field 'name'"));
+}
+
+#[test]
+fn csv_rows_are_reported_by_line() {
+    let dir = workspace("csv", &[("users.csv", "name,age\nAda,36\nAl,forty\n")]);
+    let (code, report) = run(&dir, &["users.csv"]);
+    assert_eq!(code, 1);
+    assert_eq!(report["results"][0]["record"], "line 3");
+    let codes: Vec<&str> = report["results"][0]["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|finding| finding["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(codes, ["invalid_type", "length.min"]);
+}
@@ -0,0 +1,117 @@
+use rustvalidity::prelude::*;
+
+#[derive(Debug, Validate)]
+struct Address {
+    #[validate(required, length(max = 100))]
+    street: String,
+
+    #[validate(required)]
+    city: String,
+}
+
+#[derive(Debug, Validate)]
+struct Customer {
+    #[validate(required)]
+    name: String,
+
+    billing_address: Address,
+    shipping_addresses: Vec<Address>,
+    previous_address: Option<Address>,
+}
+
+fn address(city: &str) -> Address {
+    Address {
+        street: "1 Main Street".to_string(),
+        city: city.to_string(),
+    }
+}
+
+fn customer() -> Customer {
+    Customer {
+        name: "Jane Doe".to_string(),
+        billing_address: address("Springfield"),
+        shipping_addresses: Vec::new(),
+        previous_address: None,
+    }
+}
+
+fn paths(err: &ValidationError) -> Vec<String> {
+    err.entries().iter().map(|entry| entry.path.to_string()).collect()
+}
+
+#[test]
+fn valid_nested_structs_pass() {
+    let mut customer = customer();
+    customer.shipping_addresses.push(address("Shelbyville"));
+    customer.previous_address = Some(address("Capital City"));
+    assert!(customer.validate().is_ok());
+}
+
+#[test]
+fn invalid_nested_address_fails_validate() {
+    let mut customer = customer();
+    customer.billing_address.city = String::new();
+    let err = customer.validate().unwrap_err();
+    assert_eq!(paths(&err), ["billing_address.city"]);
+    assert_eq!(err.violations()[0].code, "required");
+}
+
+#[test]
+fn nested_collections_and_options_report_below_the_field() {
+    let mut customer = customer();
+    customer.shipping_addresses = vec![address("Shelbyville"), address("")];
+    customer.previous_address = Some(address(""));
+    let err = customer.validate().unwrap_err();
+    assert_eq!(paths(&err), ["previous_address.city", "shipping_addresses[1].city"]);
+}
+
+#[derive(Debug, Validate)]
+struct Measurement {
+    #[validate(min = 2)]
+    count: u32,
+
+    #[validate(range(min = 0, max = 1))]
+    ratio: f64,
+
+    #[validate(min = 18, max = 130)]
+    age: Option<i32>,
+
+    #[validate(range(min = -1.5, max = 1.5))]
+    offset: Option<f32>,
+}
+
+fn measurement() -> Measurement {
+    Measurement {
+        count: 2,
+        ratio: 0.5,
+        age: None,
+        offset: None,
+    }
+}
+
+#[test]
+fn numeric_bounds_keep_the_type_of_the_field() {
+    assert!(measurement().validate().is_ok());
+
+    let mut low = measurement();
+    low.count = 1;
+    low.ratio = 1.25;
+    let err = low.validate().unwrap_err();
+    assert_eq!(paths(&err), ["count", "ratio"]);
+    assert_eq!(err.violations()[1].code, "range.max");
+}
+
+#[test]
+fn numeric_bounds_skip_absent_optional_values() {
+    let mut present = measurement();
+    present.age = Some(30);
+    present.offset = Some(-1.5);
+    assert!(present.validate().is_ok());
+
+    present.age = Some(12);
+    present.offset = Some(1.75);
+    let err = present.validate().unwrap_err();
+    assert_eq!(paths(&err), ["age", "offset"]);
+    assert_eq!(err.violations()[0].code, "min");
+    assert_eq!(err.violations()[1].code, "range.max");
+}